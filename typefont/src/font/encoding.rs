@@ -0,0 +1,276 @@
+/// Mac OS Roman 中 `0x80` 到 `0xFF` 对应的字符，`0x00` 到 `0x7F` 与 `ASCII` 相同
+const MAC_ROMAN_HIGH: [char; 128] = [
+    '\u{00C4}', '\u{00C5}', '\u{00C7}', '\u{00C9}', '\u{00D1}', '\u{00D6}', '\u{00DC}', '\u{00E1}',
+    '\u{00E0}', '\u{00E2}', '\u{00E4}', '\u{00E3}', '\u{00E5}', '\u{00E7}', '\u{00E9}', '\u{00E8}',
+    '\u{00EA}', '\u{00EB}', '\u{00ED}', '\u{00EC}', '\u{00EE}', '\u{00EF}', '\u{00F1}', '\u{00F3}',
+    '\u{00F2}', '\u{00F4}', '\u{00F6}', '\u{00F5}', '\u{00FA}', '\u{00F9}', '\u{00FB}', '\u{00FC}',
+    '\u{2020}', '\u{00B0}', '\u{00A2}', '\u{00A3}', '\u{00A7}', '\u{2022}', '\u{00B6}', '\u{00DF}',
+    '\u{00AE}', '\u{00A9}', '\u{2122}', '\u{00B4}', '\u{00A8}', '\u{2260}', '\u{00C6}', '\u{00D8}',
+    '\u{221E}', '\u{00B1}', '\u{2264}', '\u{2265}', '\u{00A5}', '\u{00B5}', '\u{2202}', '\u{2211}',
+    '\u{220F}', '\u{03C0}', '\u{222B}', '\u{00AA}', '\u{00BA}', '\u{03A9}', '\u{00E6}', '\u{00F8}',
+    '\u{00BF}', '\u{00A1}', '\u{00AC}', '\u{221A}', '\u{0192}', '\u{2248}', '\u{2206}', '\u{00AB}',
+    '\u{00BB}', '\u{2026}', '\u{00A0}', '\u{00C0}', '\u{00C3}', '\u{00D5}', '\u{0152}', '\u{0153}',
+    '\u{2013}', '\u{2014}', '\u{201C}', '\u{201D}', '\u{2018}', '\u{2019}', '\u{00F7}', '\u{25CA}',
+    '\u{00FF}', '\u{0178}', '\u{2044}', '\u{20AC}', '\u{2039}', '\u{203A}', '\u{FB01}', '\u{FB02}',
+    '\u{2021}', '\u{00B7}', '\u{201A}', '\u{201E}', '\u{2030}', '\u{00C2}', '\u{00CA}', '\u{00C1}',
+    '\u{00CB}', '\u{00C8}', '\u{00CD}', '\u{00CE}', '\u{00CF}', '\u{00CC}', '\u{00D3}', '\u{00D4}',
+    '\u{F8FF}', '\u{00D2}', '\u{00DA}', '\u{00DB}', '\u{00D9}', '\u{0131}', '\u{02C6}', '\u{02DC}',
+    '\u{00AF}', '\u{02D8}', '\u{02D9}', '\u{02DA}', '\u{00B8}', '\u{02DD}', '\u{02DB}', '\u{02C7}',
+];
+
+/// Mac OS Roman 字节对应的字符
+pub fn mac_roman_to_char(byte: u8) -> char {
+    match byte {
+        0x00..=0x7F => byte as char,
+        _ => MAC_ROMAN_HIGH[byte as usize - 0x80],
+    }
+}
+
+/// 字符在 Mac OS Roman 中的字节，无法编码时返回 `None`
+pub fn char_to_mac_roman(c: char) -> Option<u8> {
+    match c {
+        '\0'..='\x7F' => Some(c as u8),
+        _ => MAC_ROMAN_HIGH
+            .iter()
+            .position(|&high| high == c)
+            .map(|i| i as u8 + 0x80),
+    }
+}
+
+/// 解码 `UTF-16BE` 字符串，长度为奇数或包含不成对的代理项时返回 `None`
+pub fn decode_utf16be(bytes: &[u8]) -> Option<String> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let units = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_be_bytes([c[0], c[1]]));
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .ok()
+}
+
+pub fn encode_utf16be(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(u16::to_be_bytes).collect()
+}
+
+/// 解码 Mac OS Roman 字符串
+pub fn decode_mac_roman(bytes: &[u8]) -> String {
+    bytes.iter().map(|&byte| mac_roman_to_char(byte)).collect()
+}
+
+/// 编码为 Mac OS Roman 字符串，包含无法编码的字符时返回 `None`
+pub fn encode_mac_roman(text: &str) -> Option<Vec<u8>> {
+    text.chars().map(char_to_mac_roman).collect()
+}
+
+/// `cmap` 平台 3 中的传统 CJK 多字节编码
+///
+/// 字符代码为单字节，或高字节在前的双字节。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegacyEncoding {
+    /// 编码 2，日文
+    ShiftJis,
+    /// 编码 3，简体中文 `GBK`
+    Prc,
+    /// 编码 4，繁体中文
+    Big5,
+    /// 编码 5，韩文 `KS X 1001`（扩展为 `CP949`）
+    Wansung,
+    /// 编码 6，韩文组合型编码
+    Johab,
+}
+
+impl LegacyEncoding {
+    /// 平台 3 的编码 ID 对应的编码
+    pub fn from_windows_encoding(encoding_id: u16) -> Option<Self> {
+        use crate::font::table::cmap::windows_platform_encoding::{
+            BIG5, JOHAB, PRC, SHIFT_JIS, WANSUNG,
+        };
+
+        match encoding_id {
+            SHIFT_JIS => Some(Self::ShiftJis),
+            PRC => Some(Self::Prc),
+            BIG5 => Some(Self::Big5),
+            WANSUNG => Some(Self::Wansung),
+            JOHAB => Some(Self::Johab),
+            _ => None,
+        }
+    }
+
+    pub fn windows_encoding(self) -> u16 {
+        use crate::font::table::cmap::windows_platform_encoding::{
+            BIG5, JOHAB, PRC, SHIFT_JIS, WANSUNG,
+        };
+
+        match self {
+            Self::ShiftJis => SHIFT_JIS,
+            Self::Prc => PRC,
+            Self::Big5 => BIG5,
+            Self::Wansung => WANSUNG,
+            Self::Johab => JOHAB,
+        }
+    }
+
+    /// `byte` 是否为双字节字符的高字节
+    pub fn is_lead_byte(self, byte: u8) -> bool {
+        match self {
+            Self::ShiftJis => matches!(byte, 0x81..=0x9F | 0xE0..=0xFC),
+            Self::Prc | Self::Big5 | Self::Wansung => matches!(byte, 0x81..=0xFE),
+            Self::Johab => matches!(byte, 0x84..=0xD3 | 0xD8..=0xDE | 0xE0..=0xF9),
+        }
+    }
+
+    /// 把字节序列拆分为字符代码，末尾不完整的高字节作为单字节代码
+    pub fn codes(self, bytes: &[u8]) -> Vec<u16> {
+        let mut codes = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes.get(i + 1) {
+                Some(&trail) if self.is_lead_byte(bytes[i]) => {
+                    codes.push(u16::from_be_bytes([bytes[i], trail]));
+                    i += 2;
+                }
+                _ => {
+                    codes.push(bytes[i] as u16);
+                    i += 1;
+                }
+            }
+        }
+        codes
+    }
+
+    /// 字符代码对应的 `Unicode` 字符，无法转换或对应多个字符时返回 `None`
+    #[cfg(feature = "cjk")]
+    pub fn to_char(self, code: u16) -> Option<char> {
+        if code < 0x80 {
+            return char::from_u32(code as u32);
+        }
+        let bytes = code.to_be_bytes();
+        let bytes = match code {
+            0x80..=0xFF => &bytes[1..],
+            _ => &bytes[..],
+        };
+        let encoding = match self {
+            Self::ShiftJis => encoding_rs::SHIFT_JIS,
+            Self::Prc => encoding_rs::GBK,
+            Self::Big5 => encoding_rs::BIG5,
+            Self::Wansung => encoding_rs::EUC_KR,
+            Self::Johab => return johab_to_char(code),
+        };
+        decode_single(encoding, bytes)
+    }
+}
+
+#[cfg(feature = "cjk")]
+impl LegacyEncoding {
+    /// 解码字节序列，包含无法转换的字符代码时返回 `None`
+    pub fn decode(self, bytes: &[u8]) -> Option<String> {
+        self.codes(bytes)
+            .into_iter()
+            .map(|code| self.to_char(code))
+            .collect()
+    }
+
+    /// 编码字符串，包含无法编码的字符时返回 `None`，不支持编码为 `Johab`
+    pub fn encode(self, text: &str) -> Option<Vec<u8>> {
+        let encoding = match self {
+            Self::ShiftJis => encoding_rs::SHIFT_JIS,
+            Self::Prc => encoding_rs::GBK,
+            Self::Big5 => encoding_rs::BIG5,
+            Self::Wansung => encoding_rs::EUC_KR,
+            Self::Johab => return None,
+        };
+        let (bytes, _, had_errors) = encoding.encode(text);
+        match had_errors {
+            true => None,
+            false => Some(bytes.into_owned()),
+        }
+    }
+}
+
+#[cfg(feature = "cjk")]
+fn decode_single(encoding: &'static encoding_rs::Encoding, bytes: &[u8]) -> Option<char> {
+    let text = encoding.decode_without_bom_handling_and_without_replacement(bytes)?;
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// 初声单独出现时对应的兼容字母，相对于 `U+3130`
+#[cfg(feature = "cjk")]
+const JOHAB_COMPAT_INITIAL: [u8; 19] = [
+    0x01, 0x02, 0x04, 0x07, 0x08, 0x09, 0x11, 0x12, 0x13, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1A, 0x1B,
+    0x1C, 0x1D, 0x1E,
+];
+
+/// 终声单独出现时对应的兼容字母，相对于 `U+3130`
+#[cfg(feature = "cjk")]
+const JOHAB_COMPAT_FINAL: [u8; 27] = [
+    0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F, 0x10, 0x11,
+    0x12, 0x14, 0x15, 0x16, 0x17, 0x18, 0x1A, 0x1B, 0x1C, 0x1D, 0x1E,
+];
+
+/// `Johab` 没有现成的映射表：
+/// 韩文音节由 5 位的初声、中声、终声组合而成，可以直接计算；
+/// 符号和汉字与 `KS X 1001` 按行一一对应，转换后按 `EUC-KR` 解码
+#[cfg(feature = "cjk")]
+fn johab_to_char(code: u16) -> Option<char> {
+    let [lead, trail] = code.to_be_bytes();
+    match lead {
+        0x84..=0xD3 => {
+            let initial = match (code >> 10) & 0x1F {
+                1 => None,
+                c @ 2..=20 => Some(c - 2),
+                _ => return None,
+            };
+            let medial = match (code >> 5) & 0x1F {
+                2 => None,
+                c @ 3..=7 => Some(c - 3),
+                c @ 10..=15 => Some(c - 5),
+                c @ 18..=23 => Some(c - 7),
+                c @ 26..=29 => Some(c - 9),
+                _ => return None,
+            };
+            let final_ = match code & 0x1F {
+                1 => None,
+                c @ 2..=17 => Some(c - 1),
+                c @ 19..=29 => Some(c - 2),
+                _ => return None,
+            };
+            let code_point = match (initial, medial, final_) {
+                (Some(l), Some(v), t) => {
+                    0xAC00 + (l as u32 * 21 + v as u32) * 28 + t.unwrap_or(0) as u32
+                }
+                (Some(l), None, None) => 0x3130 + JOHAB_COMPAT_INITIAL[l as usize] as u32,
+                (None, Some(v), None) => 0x314F + v as u32,
+                (None, None, Some(t)) => 0x3130 + JOHAB_COMPAT_FINAL[t as usize - 1] as u32,
+                _ => return None,
+            };
+            char::from_u32(code_point)
+        }
+        0xD9..=0xDE | 0xE0..=0xF9 => {
+            let t2 = match trail {
+                0x31..=0x7E => trail - 0x31,
+                0x91..=0xFE => trail - 0x43,
+                _ => return None,
+            };
+            // `KS X 1001` 第 4 行的字母在 `Johab` 中按韩文编码
+            if lead == 0xDA && (0xA1..=0xD3).contains(&trail) {
+                return None;
+            }
+            let row = match lead {
+                0xD9..=0xDE => 2 * (lead - 0xD9),
+                _ => 2 * (lead - 0xE0) + 0x29,
+            };
+            let (row, cell) = match t2 < 94 {
+                true => (row, t2),
+                false => (row + 1, t2 - 94),
+            };
+            decode_single(encoding_rs::EUC_KR, &[row + 0xA1, cell + 0xA1])
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,154 @@
+use super::Tag;
+use crate::font::io::ReadFrom;
+use crate::font::open_type::TableDirectory;
+use crate::font::table::cbdt::CbdtRef;
+use crate::font::table::glyf::GlyfRef;
+use crate::font::table::head::Head;
+use crate::font::table::hhea::Hhea;
+use crate::font::table::hmtx::HmtxRef;
+use crate::font::table::loca::LocaRef;
+use crate::font::table::maxp::Maxp;
+use crate::font::table::name::NameRef;
+use crate::io::error::{ErrorContext, IOError};
+use crate::io::reader::ReaderBoxed;
+use crate::io::slice_reader::SliceReader;
+
+/// # 借用的字体数据
+///
+/// 与 [OpenType](super::open_type::OpenType) 不同，
+/// `FontRef` 不持有数据，而是直接引用调用方提供的字体数据，
+/// 返回的表视图（如 [GlyfRef]、[NameRef]、[CbdtRef]）也借用自同一份数据，
+/// 字形数组、字符串存储区、字形指令、位图数据等均不复制。
+///
+/// 较小且需要完整读取的表（如 `head`、`hhea`、`maxp`）仍按值读取。
+///
+/// [OpenType](super::open_type::OpenType) 中的表仍然持有数据：
+/// 它通过读取器从文件、内存映射或内存中读取并缓存表，表也需要能够修改后写回。
+pub struct FontRef<'a> {
+    data: &'a [u8],
+    table_directory: TableDirectory,
+}
+
+impl<'a> FontRef<'a> {
+    /// 读取位于数据开头的字体
+    pub fn new(data: &'a [u8]) -> Result<Self, IOError> {
+        Self::with_offset(data, 0)
+    }
+
+    /// 读取表目录位于 `offset` 处的字体，如字体集合中的字体，表偏移量相对于 `data` 开头
+    pub fn with_offset(data: &'a [u8], offset: usize) -> Result<Self, IOError> {
+        let mut reader: ReaderBoxed = Box::new(SliceReader::new(data));
+        reader.seek(offset)?;
+        let table_directory = TableDirectory::read_from(&mut reader)
+            .at(reader.position() - offset)
+            .context("tableDirectory", offset)?;
+        Ok(Self {
+            data,
+            table_directory,
+        })
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn table_directory(&self) -> &TableDirectory {
+        &self.table_directory
+    }
+
+    /// 表的原始数据，表不存在时返回 `None`
+    pub fn table_data(&self, tag: &Tag) -> Result<Option<&'a [u8]>, IOError> {
+        let index = match self.table_directory.find(tag) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let record = &self.table_directory.table_records()[index];
+        let start = record.offset() as usize;
+        let end = start + record.length() as usize;
+        match self.data.get(start..end) {
+            Some(data) => Ok(Some(data)),
+            None => Err(IOError::NoEnoughData),
+        }
+    }
+
+    fn read_table<T: for<'r> ReadFrom<ReaderBoxed<'r>>>(
+        &self,
+        tag: &[u8; 4],
+    ) -> Result<Option<T>, IOError> {
+        let tag = Tag::from(*tag);
+        match self.table_data(&tag)? {
+            Some(data) => {
+                let mut reader: ReaderBoxed = Box::new(SliceReader::new(data));
+                let table_offset = data.as_ptr() as usize - self.data.as_ptr() as usize;
+                T::read_from(&mut reader)
+                    .at(reader.position())
+                    .in_table(&tag, table_offset)
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn head(&self) -> Result<Option<Head>, IOError> {
+        self.read_table(b"head")
+    }
+
+    pub fn hhea(&self) -> Result<Option<Hhea>, IOError> {
+        self.read_table(b"hhea")
+    }
+
+    pub fn maxp(&self) -> Result<Option<Maxp>, IOError> {
+        self.read_table(b"maxp")
+    }
+
+    /// 需要 `head` 表确定偏移量格式，`maxp` 表确定偏移量个数
+    pub fn loca(&self) -> Result<Option<LocaRef<'a>>, IOError> {
+        let (head, maxp) = match (self.head()?, self.maxp()?) {
+            (Some(head), Some(maxp)) => (head, maxp),
+            _ => return Ok(None),
+        };
+        match self.table_data(&Tag::from(*b"loca"))? {
+            Some(data) => {
+                LocaRef::new(data, head.index_to_loc_format(), maxp.num_glyphs()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn glyf(&self) -> Result<Option<GlyfRef<'a>>, IOError> {
+        let loca = match self.loca()? {
+            Some(loca) => loca,
+            None => return Ok(None),
+        };
+        Ok(self
+            .table_data(&Tag::from(*b"glyf"))?
+            .map(|data| GlyfRef::new(data, loca)))
+    }
+
+    pub fn hmtx(&self) -> Result<Option<HmtxRef<'a>>, IOError> {
+        let (hhea, maxp) = match (self.hhea()?, self.maxp()?) {
+            (Some(hhea), Some(maxp)) => (hhea, maxp),
+            _ => return Ok(None),
+        };
+        match self.table_data(&Tag::from(*b"hmtx"))? {
+            Some(data) => {
+                HmtxRef::new(data, hhea.number_of_h_metrics(), maxp.num_glyphs()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn name(&self) -> Result<Option<NameRef<'a>>, IOError> {
+        match self.table_data(&Tag::from(*b"name"))? {
+            Some(data) => NameRef::new(data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn cbdt(&self) -> Result<Option<CbdtRef<'a>>, IOError> {
+        match self.table_data(&Tag::from(*b"CBDT"))? {
+            Some(data) => CbdtRef::new(data).map(Some),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,1334 @@
+use super::graphics_state::{GraphicsState, RoundMode};
+use super::math::{
+    F26Dot6, ONE_14, ceil, dot_14, floor, mul_14, mul_div, mul_div_no_round, mul_fix, normalize,
+};
+use super::zone::{ON_CURVE, TOUCHED_X, TOUCHED_Y, Zone};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// 指令所在的程序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Program {
+    /// `fpgm`
+    Font,
+    /// `prep`
+    ControlValue,
+    /// 字形的指令
+    Glyph,
+}
+
+impl Program {
+    fn name(self) -> &'static str {
+        match self {
+            Program::Font => "fpgm",
+            Program::ControlValue => "prep",
+            Program::Glyph => "glyf",
+        }
+    }
+}
+
+/// `FDEF` 或 `IDEF` 定义的函数体，`start` 为第一条指令的位置
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Definition {
+    program: Program,
+    start: usize,
+}
+
+/// `fpgm` 和 `prep` 中定义的函数和指令
+#[derive(Debug, Clone, Default)]
+pub(super) struct Definitions {
+    functions: HashMap<u16, Definition>,
+    instructions: HashMap<u8, Definition>,
+}
+
+/// 执行限制
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Limits {
+    pub(super) stack: usize,
+    pub(super) function_defs: usize,
+    pub(super) instruction_defs: usize,
+    /// 每次执行程序最多执行的指令数
+    pub(super) instructions: usize,
+}
+
+/// 缩放参数
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Size {
+    pub(super) ppem: u16,
+    /// 字体设计单位到 26.6 的缩放比例，16.16 定点数
+    pub(super) scale: i32,
+    /// 字形区域未缩放坐标的缩放比例，复合字形的未缩放坐标已经是 26.6，比例为 1
+    pub(super) glyph_scale: i32,
+}
+
+/// 函数调用的最大嵌套层数
+const MAX_CALL_DEPTH: usize = 32;
+
+/// 实例的可变状态，`prep` 执行后的结果是每个字形程序的初始状态
+#[derive(Debug, Clone)]
+pub(super) struct Instance {
+    /// 缩放后的控制值表，26.6 定点数
+    pub(super) cvt: Vec<F26Dot6>,
+    pub(super) storage: Vec<i32>,
+    pub(super) twilight: Zone,
+    pub(super) graphics_state: GraphicsState,
+}
+
+struct CallRecord {
+    program: Program,
+    return_pc: usize,
+    definition: Definition,
+    count: i32,
+}
+
+/// `TrueType` 指令解释器
+///
+/// 所有点、控制值和存储区的索引都会检查范围，栈的大小、调用层数和指令数都有上限，
+/// 超出时返回错误。
+pub(super) struct Engine<'a> {
+    code: [&'a [u8]; 3],
+    definitions: Cow<'a, Definitions>,
+    limits: Limits,
+    size: Size,
+    pub(super) instance: Instance,
+    pub(super) glyph: Zone,
+    stack: Vec<i32>,
+    calls: Vec<CallRecord>,
+    program: Program,
+    pc: usize,
+    next_pc: usize,
+    executed: usize,
+}
+
+/// `pc` 处指令的长度，包括压栈指令的数据
+fn instruction_len(code: &[u8], pc: usize) -> Option<usize> {
+    let opcode = *code.get(pc)?;
+    Some(match opcode {
+        0x40 => 2 + *code.get(pc + 1)? as usize,
+        0x41 => 2 + 2 * *code.get(pc + 1)? as usize,
+        0xB0..=0xB7 => 2 + (opcode - 0xB0) as usize,
+        0xB8..=0xBF => 1 + 2 * (opcode - 0xB7) as usize,
+        _ => 1,
+    })
+}
+
+impl<'a> Engine<'a> {
+    pub(super) fn new(
+        code: [&'a [u8]; 3],
+        definitions: Cow<'a, Definitions>,
+        limits: Limits,
+        size: Size,
+        instance: Instance,
+        glyph: Zone,
+    ) -> Self {
+        Self {
+            code,
+            definitions,
+            limits,
+            size,
+            instance,
+            glyph,
+            stack: vec![],
+            calls: vec![],
+            program: Program::Font,
+            pc: 0,
+            next_pc: 0,
+            executed: 0,
+        }
+    }
+
+    pub(super) fn into_definitions(self) -> Definitions {
+        self.definitions.into_owned()
+    }
+
+    /// 从头执行程序，错误信息以程序名和指令位置开头，如 `prep[12]: stack underflow`
+    pub(super) fn run(&mut self, program: Program) -> Result<(), String> {
+        self.program = program;
+        self.pc = 0;
+        self.stack.clear();
+        self.calls.clear();
+        self.executed = 0;
+        loop {
+            let code = self.code[self.program as usize];
+            if self.pc >= code.len() {
+                if self.calls.is_empty() {
+                    return Ok(());
+                }
+                return Err(format!(
+                    "{}[{}]: function without ENDF",
+                    self.program.name(),
+                    self.pc
+                ));
+            }
+            self.step(code)
+                .map_err(|e| format!("{}[{}]: {}", self.program.name(), self.pc, e))?;
+            self.pc = self.next_pc;
+        }
+    }
+
+    fn step(&mut self, code: &'a [u8]) -> Result<(), String> {
+        self.executed += 1;
+        if self.executed > self.limits.instructions {
+            return Err(format!(
+                "instruction limit {} exceeded",
+                self.limits.instructions
+            ));
+        }
+        let len = instruction_len(code, self.pc)
+            .filter(|len| self.pc + len <= code.len())
+            .ok_or("unexpected end of program")?;
+        self.next_pc = self.pc + len;
+        let opcode = code[self.pc];
+        match opcode {
+            0x40 | 0xB0..=0xB7 => {
+                let start = self.pc + if opcode == 0x40 { 2 } else { 1 };
+                for &byte in &code[start..self.next_pc] {
+                    self.push(byte as i32)?;
+                }
+            }
+            0x41 | 0xB8..=0xBF => {
+                let start = self.pc + if opcode == 0x41 { 2 } else { 1 };
+                for word in code[start..self.next_pc].chunks_exact(2) {
+                    self.push(i16::from_be_bytes([word[0], word[1]]) as i32)?;
+                }
+            }
+            _ => self.execute(opcode)?,
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, value: i32) -> Result<(), String> {
+        if self.stack.len() >= self.limits.stack {
+            return Err(format!("stack overflow, limit {}", self.limits.stack));
+        }
+        self.stack.push(value);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<i32, String> {
+        self.stack
+            .pop()
+            .ok_or_else(|| "stack underflow".to_string())
+    }
+
+    fn pop_bool(&mut self) -> Result<bool, String> {
+        Ok(self.pop()? != 0)
+    }
+
+    fn zone(&self, zone: usize) -> &Zone {
+        match zone {
+            0 => &self.instance.twilight,
+            _ => &self.glyph,
+        }
+    }
+
+    fn zone_mut(&mut self, zone: usize) -> &mut Zone {
+        match zone {
+            0 => &mut self.instance.twilight,
+            _ => &mut self.glyph,
+        }
+    }
+
+    fn gs(&self) -> &GraphicsState {
+        &self.instance.graphics_state
+    }
+
+    fn gs_mut(&mut self) -> &mut GraphicsState {
+        &mut self.instance.graphics_state
+    }
+
+    /// 区域指针 `zp` 指向的区域编号
+    fn zp(&self, zp: usize) -> usize {
+        self.gs().zone_pointers[zp]
+    }
+
+    /// 检查区域指针 `zp` 指向的区域中是否有点 `index`
+    fn point(&self, zp: usize, index: i32) -> Result<usize, String> {
+        let zone = self.zp(zp);
+        let len = self.zone(zone).len();
+        match usize::try_from(index) {
+            Ok(index) if index < len => Ok(index),
+            _ => Err(format!(
+                "point {} out of range 0..{} in zone {}",
+                index, len, zone
+            )),
+        }
+    }
+
+    fn reference_point(&self, rp: usize, zp: usize) -> Result<usize, String> {
+        self.point(zp, self.gs().reference_points[rp] as i32)
+    }
+
+    fn current(&self, zp: usize, point: usize) -> [i32; 2] {
+        self.zone(self.zp(zp)).current[point]
+    }
+
+    fn original(&self, zp: usize, point: usize) -> [i32; 2] {
+        self.zone(self.zp(zp)).original[point]
+    }
+
+    fn cvt_index(&self, index: i32) -> Result<usize, String> {
+        let len = self.instance.cvt.len();
+        match usize::try_from(index) {
+            Ok(index) if index < len => Ok(index),
+            _ => Err(format!("cvt {} out of range 0..{}", index, len)),
+        }
+    }
+
+    fn project(&self, d: [i32; 2]) -> F26Dot6 {
+        let [x, y] = self.gs().projection;
+        dot_14(d[0], d[1], x, y)
+    }
+
+    fn dual_project(&self, d: [i32; 2]) -> F26Dot6 {
+        let [x, y] = self.gs().dual_projection;
+        dot_14(d[0], d[1], x, y)
+    }
+
+    fn round(&self, distance: F26Dot6) -> F26Dot6 {
+        self.gs().round_state.round(distance)
+    }
+
+    /// 自由向量与投影向量的点积，过小时视为 1 以免移动距离溢出
+    fn freedom_dot_projection(&self) -> i32 {
+        let gs = self.gs();
+        let [px, py] = gs.projection;
+        let [fx, fy] = gs.freedom;
+        let dot = if fx == ONE_14 {
+            px
+        } else if fy == ONE_14 {
+            py
+        } else {
+            ((px as i64 * fx as i64 + py as i64 * fy as i64) >> 14) as i32
+        };
+        match dot.abs() < 0x400 {
+            true => ONE_14,
+            false => dot,
+        }
+    }
+
+    /// 沿自由向量移动点，使其在投影向量上移动 `distance`，并标记为已触碰
+    fn move_point(&mut self, zp: usize, point: usize, distance: F26Dot6) {
+        let [fx, fy] = self.gs().freedom;
+        let dot = self.freedom_dot_projection();
+        let zone = self.zone_mut(self.zp(zp));
+        if fx != 0 {
+            zone.current[point][0] =
+                zone.current[point][0].wrapping_add(mul_div(distance, fx, dot));
+            zone.flags[point] |= TOUCHED_X;
+        }
+        if fy != 0 {
+            zone.current[point][1] =
+                zone.current[point][1].wrapping_add(mul_div(distance, fy, dot));
+            zone.flags[point] |= TOUCHED_Y;
+        }
+    }
+
+    /// 与 [Engine::move_point] 相同，但移动原始坐标且不标记
+    fn move_original(&mut self, zp: usize, point: usize, distance: F26Dot6) {
+        let [fx, fy] = self.gs().freedom;
+        let dot = self.freedom_dot_projection();
+        let zone = self.zone_mut(self.zp(zp));
+        if fx != 0 {
+            zone.original[point][0] =
+                zone.original[point][0].wrapping_add(mul_div(distance, fx, dot));
+        }
+        if fy != 0 {
+            zone.original[point][1] =
+                zone.original[point][1].wrapping_add(mul_div(distance, fy, dot));
+        }
+    }
+
+    /// 按位移 (`dx`, `dy`) 移动 `zp2` 中的点，用于 `SHP`、`SHC`、`SHZ` 和 `SHPIX`
+    fn shift_point(&mut self, point: usize, d: [i32; 2], touch: bool) {
+        let [fx, fy] = self.gs().freedom;
+        let zone = self.zone_mut(self.zp(2));
+        for (axis, (f, flag)) in [(fx, TOUCHED_X), (fy, TOUCHED_Y)].into_iter().enumerate() {
+            if f != 0 {
+                zone.current[point][axis] = zone.current[point][axis].wrapping_add(d[axis]);
+                if touch {
+                    zone.flags[point] |= flag;
+                }
+            }
+        }
+    }
+
+    /// 原始轮廓上两点在对偶投影向量上的距离，都在字形区域时由未缩放坐标计算
+    fn original_distance(&self, (zp1, p1): (usize, usize), (zp2, p2): (usize, usize)) -> F26Dot6 {
+        let (zone1, zone2) = (self.zp(zp1), self.zp(zp2));
+        if zone1 == 0 || zone2 == 0 {
+            let (a, b) = (self.original(zp1, p1), self.original(zp2, p2));
+            return self.dual_project(sub(a, b));
+        }
+        let (a, b) = (self.glyph.unscaled[p1], self.glyph.unscaled[p2]);
+        mul_fix(self.dual_project(sub(a, b)), self.size.glyph_scale)
+    }
+
+    /// `SHP`、`SHC`、`SHZ` 的参考点及其位移
+    fn displacement(&self, opcode: u8) -> Result<(usize, usize, [i32; 2]), String> {
+        let (zp, point) = match opcode & 1 {
+            0 => (1, self.reference_point(2, 1)?),
+            _ => (0, self.reference_point(1, 0)?),
+        };
+        let d = self.project(sub(self.current(zp, point), self.original(zp, point)));
+        let [fx, fy] = self.gs().freedom;
+        let dot = self.freedom_dot_projection();
+        Ok((
+            self.zp(zp),
+            point,
+            [mul_div(d, fx, dot), mul_div(d, fy, dot)],
+        ))
+    }
+
+    /// 由两点确定的向量，`perpendicular` 时逆时针旋转 90°，两点重合时为 x 轴
+    fn line_vector(&self, a: [i32; 2], b: [i32; 2], perpendicular: bool) -> [i32; 2] {
+        let [dx, dy] = sub(a, b);
+        match (dx, dy, perpendicular) {
+            (0, 0, _) => [ONE_14, 0],
+            (dx, dy, true) => normalize(dy.wrapping_neg(), dx).unwrap_or([ONE_14, 0]),
+            (dx, dy, false) => normalize(dx, dy).unwrap_or([ONE_14, 0]),
+        }
+    }
+
+    fn jump(&mut self, offset: i32) -> Result<(), String> {
+        let target = self.pc as i64 + offset as i64;
+        let len = self.code[self.program as usize].len() as i64;
+        if !(0..=len).contains(&target) {
+            return Err(format!("jump to {} out of range 0..={}", target, len));
+        }
+        self.next_pc = target as usize;
+        Ok(())
+    }
+
+    /// 从 `pc` 处的指令向后扫描，返回下一条指令的位置和操作码
+    fn next_instruction(&self, pc: usize) -> Result<(usize, u8), String> {
+        let code = self.code[self.program as usize];
+        let next = pc + instruction_len(code, pc).ok_or("unexpected end of program")?;
+        match code.get(next) {
+            Some(&opcode) => Ok((next, opcode)),
+            None => Err("unexpected end of program".to_string()),
+        }
+    }
+
+    /// 跳到与当前 `IF` 或 `ELSE` 匹配的 `ELSE` 或 `EIF` 之后，`stop_at_else` 为 `false` 时只匹配 `EIF`
+    fn skip_branch(&mut self, stop_at_else: bool) -> Result<(), String> {
+        let mut depth = 1;
+        let mut pc = self.pc;
+        loop {
+            let (next, opcode) = self.next_instruction(pc)?;
+            pc = next;
+            match opcode {
+                0x58 => depth += 1,
+                0x1B if stop_at_else && depth == 1 => break,
+                0x59 => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.next_pc = pc + 1;
+        Ok(())
+    }
+
+    /// 记录 `FDEF` 或 `IDEF` 的函数体并跳到 `ENDF` 之后
+    fn define(&mut self) -> Result<Definition, String> {
+        if self.program == Program::Glyph {
+            return Err("FDEF or IDEF in glyph program".to_string());
+        }
+        let definition = Definition {
+            program: self.program,
+            start: self.pc + 1,
+        };
+        let mut pc = self.pc;
+        loop {
+            let (next, opcode) = self.next_instruction(pc)?;
+            pc = next;
+            match opcode {
+                0x2C | 0x89 => return Err("nested FDEF or IDEF".to_string()),
+                0x2D => break,
+                _ => {}
+            }
+        }
+        self.next_pc = pc + 1;
+        Ok(definition)
+    }
+
+    fn call(&mut self, definition: Definition, count: i32) -> Result<(), String> {
+        if count <= 0 {
+            return Ok(());
+        }
+        if self.calls.len() >= MAX_CALL_DEPTH {
+            return Err(format!("call depth exceeds {}", MAX_CALL_DEPTH));
+        }
+        self.calls.push(CallRecord {
+            program: self.program,
+            return_pc: self.next_pc,
+            definition,
+            count,
+        });
+        self.program = definition.program;
+        self.next_pc = definition.start;
+        Ok(())
+    }
+
+    fn function(&self, number: i32) -> Result<Definition, String> {
+        u16::try_from(number)
+            .ok()
+            .and_then(|number| self.definitions.functions.get(&number))
+            .copied()
+            .ok_or_else(|| format!("undefined function {}", number))
+    }
+
+    /// 对 `loop` 个出栈的点依次执行 `f`，之后把 `loop` 重置为 1
+    fn for_each_point(
+        &mut self,
+        zp: usize,
+        mut f: impl FnMut(&mut Self, usize) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let count = self.gs().loop_count;
+        self.gs_mut().loop_count = 1;
+        for _ in 0..count {
+            let index = self.pop()?;
+            let point = self.point(zp, index)?;
+            f(self, point)?;
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, opcode: u8) -> Result<(), String> {
+        match opcode {
+            // SVTCA、SPVTCA、SFVTCA
+            0x00..=0x05 => {
+                let axis = match opcode & 1 {
+                    0 => [0, ONE_14],
+                    _ => [ONE_14, 0],
+                };
+                let gs = self.gs_mut();
+                if opcode < 0x04 {
+                    gs.projection = axis;
+                    gs.dual_projection = axis;
+                }
+                if !(0x02..0x04).contains(&opcode) {
+                    gs.freedom = axis;
+                }
+            }
+            // SPVTL、SFVTL
+            0x06..=0x09 => {
+                let p1 = self.pop()?;
+                let p2 = self.pop()?;
+                let p1 = self.point(2, p1)?;
+                let p2 = self.point(1, p2)?;
+                let vector =
+                    self.line_vector(self.current(1, p2), self.current(2, p1), opcode & 1 != 0);
+                let gs = self.gs_mut();
+                match opcode < 0x08 {
+                    true => (gs.projection, gs.dual_projection) = (vector, vector),
+                    false => gs.freedom = vector,
+                }
+            }
+            // SPVFS、SFVFS
+            0x0A | 0x0B => {
+                let y = self.pop()? as i16 as i32;
+                let x = self.pop()? as i16 as i32;
+                if let Some(vector) = normalize(x, y) {
+                    let gs = self.gs_mut();
+                    match opcode {
+                        0x0A => (gs.projection, gs.dual_projection) = (vector, vector),
+                        _ => gs.freedom = vector,
+                    }
+                }
+            }
+            // GPV、GFV
+            0x0C | 0x0D => {
+                let [x, y] = match opcode {
+                    0x0C => self.gs().projection,
+                    _ => self.gs().freedom,
+                };
+                self.push(x)?;
+                self.push(y)?;
+            }
+            // SFVTPV
+            0x0E => {
+                let gs = self.gs_mut();
+                gs.freedom = gs.projection;
+            }
+            // ISECT
+            0x0F => self.intersect()?,
+            // SRP0、SRP1、SRP2
+            0x10..=0x12 => {
+                let point = self.pop()? as u16 as usize;
+                self.gs_mut().reference_points[(opcode - 0x10) as usize] = point;
+            }
+            // SZP0、SZP1、SZP2、SZPS
+            0x13..=0x16 => {
+                let zone = match self.pop()? {
+                    zone @ (0 | 1) => zone as usize,
+                    zone => return Err(format!("invalid zone {}", zone)),
+                };
+                let gs = self.gs_mut();
+                match opcode {
+                    0x16 => gs.zone_pointers = [zone; 3],
+                    _ => gs.zone_pointers[(opcode - 0x13) as usize] = zone,
+                }
+            }
+            // SLOOP
+            0x17 => {
+                let count = self.pop()?;
+                if count < 0 {
+                    return Err(format!("negative loop count {}", count));
+                }
+                self.gs_mut().loop_count = count.min(0xFFFF) as u32;
+            }
+            // RTG、RTHG、RTDG、ROFF、RUTG、RDTG
+            0x18 | 0x19 | 0x3D | 0x7A | 0x7C | 0x7D => {
+                self.gs_mut().round_state.mode = match opcode {
+                    0x18 => RoundMode::ToGrid,
+                    0x19 => RoundMode::ToHalfGrid,
+                    0x3D => RoundMode::ToDoubleGrid,
+                    0x7A => RoundMode::Off,
+                    0x7C => RoundMode::UpToGrid,
+                    _ => RoundMode::DownToGrid,
+                }
+            }
+            // SMD
+            0x1A => self.gs_mut().minimum_distance = self.pop()?,
+            // ELSE
+            0x1B => self.skip_branch(false)?,
+            // JMPR
+            0x1C => {
+                let offset = self.pop()?;
+                self.jump(offset)?;
+            }
+            // SCVTCI、SSWCI、SSW
+            0x1D => self.gs_mut().control_value_cut_in = self.pop()?,
+            0x1E => self.gs_mut().single_width_cut_in = self.pop()?,
+            0x1F => {
+                let value = mul_fix(self.pop()?, self.size.scale);
+                self.gs_mut().single_width_value = value;
+            }
+            // DUP
+            0x20 => {
+                let value = self.pop()?;
+                self.push(value)?;
+                self.push(value)?;
+            }
+            // POP
+            0x21 => {
+                self.pop()?;
+            }
+            // CLEAR
+            0x22 => self.stack.clear(),
+            // SWAP
+            0x23 => {
+                let a = self.pop()?;
+                let b = self.pop()?;
+                self.push(a)?;
+                self.push(b)?;
+            }
+            // DEPTH
+            0x24 => self.push(self.stack.len() as i32)?,
+            // CINDEX、MINDEX
+            0x25 | 0x26 => {
+                let k = self.pop()?;
+                let index = usize::try_from(k)
+                    .ok()
+                    .filter(|&k| k >= 1 && k <= self.stack.len())
+                    .map(|k| self.stack.len() - k)
+                    .ok_or_else(|| format!("stack element {} out of range", k))?;
+                let value = match opcode {
+                    0x25 => self.stack[index],
+                    _ => self.stack.remove(index),
+                };
+                self.push(value)?;
+            }
+            // ALIGNPTS
+            0x27 => {
+                let p2 = self.pop()?;
+                let p1 = self.pop()?;
+                let p2 = self.point(0, p2)?;
+                let p1 = self.point(1, p1)?;
+                let distance = self.project(sub(self.current(0, p2), self.current(1, p1))) / 2;
+                self.move_point(1, p1, distance);
+                self.move_point(0, p2, distance.wrapping_neg());
+            }
+            // UTP
+            0x29 => {
+                let point = self.pop()?;
+                let point = self.point(0, point)?;
+                let [fx, fy] = self.gs().freedom;
+                let mut mask = !0;
+                if fx != 0 {
+                    mask &= !TOUCHED_X;
+                }
+                if fy != 0 {
+                    mask &= !TOUCHED_Y;
+                }
+                let zone = self.zone_mut(self.zp(0));
+                zone.flags[point] &= mask;
+            }
+            // LOOPCALL、CALL
+            0x2A | 0x2B => {
+                let definition = self.pop().and_then(|f| self.function(f))?;
+                let count = match opcode {
+                    0x2A => self.pop()?,
+                    _ => 1,
+                };
+                self.call(definition, count)?;
+            }
+            // FDEF
+            0x2C => {
+                let number = self.pop()?;
+                let number = u16::try_from(number)
+                    .map_err(|_| format!("function number {} out of range", number))?;
+                let definition = self.define()?;
+                let limit = self.limits.function_defs;
+                let functions = &mut self.definitions.to_mut().functions;
+                if !functions.contains_key(&number) && functions.len() >= limit {
+                    return Err(format!("more than {} function definitions", limit));
+                }
+                functions.insert(number, definition);
+            }
+            // ENDF
+            0x2D => {
+                let mut record = self.calls.pop().ok_or("ENDF outside of a function")?;
+                record.count -= 1;
+                if record.count > 0 {
+                    self.next_pc = record.definition.start;
+                    self.calls.push(record);
+                } else {
+                    self.program = record.program;
+                    self.next_pc = record.return_pc;
+                }
+            }
+            // MDAP
+            0x2E | 0x2F => {
+                let point = self.pop()?;
+                let point = self.point(0, point)?;
+                let distance = match opcode & 1 {
+                    0 => 0,
+                    _ => {
+                        let distance = self.project(self.current(0, point));
+                        self.round(distance).wrapping_sub(distance)
+                    }
+                };
+                self.move_point(0, point, distance);
+                let gs = self.gs_mut();
+                gs.reference_points[0] = point;
+                gs.reference_points[1] = point;
+            }
+            // IUP[y]、IUP[x]
+            0x30 | 0x31 => self.glyph.interpolate_untouched((opcode & 1) as usize ^ 1),
+            // SHP
+            0x32 | 0x33 => {
+                let (_, _, d) = self.displacement(opcode)?;
+                self.for_each_point(2, |engine, point| {
+                    engine.shift_point(point, d, true);
+                    Ok(())
+                })?;
+            }
+            // SHC
+            0x34 | 0x35 => {
+                let contour = self.pop()?;
+                let (zone, reference, d) = self.displacement(opcode)?;
+                let target = self.zp(2);
+                let points = match target {
+                    0 if contour == 0 => 0..self.zone(0).len(),
+                    0 => return Err(format!("contour {} out of range 0..1", contour)),
+                    _ => {
+                        let ends = &self.glyph.end_points;
+                        let index = usize::try_from(contour)
+                            .ok()
+                            .filter(|&c| c < ends.len())
+                            .ok_or_else(|| {
+                                format!("contour {} out of range 0..{}", contour, ends.len())
+                            })?;
+                        let start = match index {
+                            0 => 0,
+                            _ => ends[index - 1] + 1,
+                        };
+                        start..ends[index] + 1
+                    }
+                };
+                for point in points {
+                    if zone != target || point != reference {
+                        self.shift_point(point, d, true);
+                    }
+                }
+            }
+            // SHZ
+            0x36 | 0x37 => {
+                let zone = self.pop()?;
+                if !(0..=1).contains(&zone) {
+                    return Err(format!("invalid zone {}", zone));
+                }
+                let (reference_zone, reference, d) = self.displacement(opcode)?;
+                // 移动 zp2 中的点，不移动幻影点
+                let target = self.zp(2);
+                let limit = match target {
+                    0 => self.zone(0).len(),
+                    _ => self.glyph.end_points.last().map_or(0, |&end| end + 1),
+                };
+                for point in 0..limit {
+                    if reference_zone != target || point != reference {
+                        self.shift_point(point, d, false);
+                    }
+                }
+            }
+            // SHPIX
+            0x38 => {
+                let amount = self.pop()?;
+                let [fx, fy] = self.gs().freedom;
+                let d = [mul_14(amount, fx), mul_14(amount, fy)];
+                self.for_each_point(2, |engine, point| {
+                    engine.shift_point(point, d, true);
+                    Ok(())
+                })?;
+            }
+            // IP
+            0x39 => self.interpolate_points()?,
+            // MSIRP
+            0x3A | 0x3B => {
+                let distance = self.pop()?;
+                let point = self.pop()?;
+                let point = self.point(1, point)?;
+                let rp0 = self.reference_point(0, 0)?;
+                if self.zp(1) == 0 {
+                    let origin = self.original(0, rp0);
+                    let zone = self.zone_mut(0);
+                    zone.original[point] = origin;
+                    self.move_original(1, point, distance);
+                    let zone = self.zone_mut(0);
+                    zone.current[point] = zone.original[point];
+                }
+                let current = self.project(sub(self.current(1, point), self.current(0, rp0)));
+                self.move_point(1, point, distance.wrapping_sub(current));
+                self.set_reference_points(point, opcode & 1 != 0);
+            }
+            // ALIGNRP
+            0x3C => {
+                let rp0 = self.reference_point(0, 0)?;
+                self.for_each_point(1, |engine, point| {
+                    let distance =
+                        engine.project(sub(engine.current(1, point), engine.current(0, rp0)));
+                    engine.move_point(1, point, distance.wrapping_neg());
+                    Ok(())
+                })?;
+            }
+            // MIAP
+            0x3E | 0x3F => {
+                let cvt = self.pop()?;
+                let point = self.pop()?;
+                let point = self.point(0, point)?;
+                let mut distance = self.instance.cvt[self.cvt_index(cvt)?];
+                if self.zp(0) == 0 {
+                    let [fx, fy] = self.gs().freedom;
+                    let zone = self.zone_mut(0);
+                    zone.original[point] = [mul_14(distance, fx), mul_14(distance, fy)];
+                    zone.current[point] = zone.original[point];
+                }
+                let original = self.project(self.current(0, point));
+                if opcode & 1 != 0 {
+                    if distance.wrapping_sub(original).wrapping_abs()
+                        > self.gs().control_value_cut_in
+                    {
+                        distance = original;
+                    }
+                    distance = self.round(distance);
+                }
+                self.move_point(0, point, distance.wrapping_sub(original));
+                let gs = self.gs_mut();
+                gs.reference_points[0] = point;
+                gs.reference_points[1] = point;
+            }
+            // WS
+            0x42 => {
+                let value = self.pop()?;
+                let index = self.pop()?;
+                let len = self.instance.storage.len();
+                let slot = usize::try_from(index)
+                    .ok()
+                    .and_then(|i| self.instance.storage.get_mut(i))
+                    .ok_or_else(|| format!("storage {} out of range 0..{}", index, len))?;
+                *slot = value;
+            }
+            // RS
+            0x43 => {
+                let index = self.pop()?;
+                let storage = &self.instance.storage;
+                let value = usize::try_from(index)
+                    .ok()
+                    .and_then(|i| storage.get(i))
+                    .copied()
+                    .ok_or_else(|| {
+                        format!("storage {} out of range 0..{}", index, storage.len())
+                    })?;
+                self.push(value)?;
+            }
+            // WCVTP、WCVTF
+            0x44 | 0x70 => {
+                let value = self.pop()?;
+                let index = self.pop()?;
+                let index = self.cvt_index(index)?;
+                self.instance.cvt[index] = match opcode {
+                    0x44 => value,
+                    _ => mul_fix(value, self.size.scale),
+                };
+            }
+            // RCVT
+            0x45 => {
+                let index = self.pop()?;
+                let index = self.cvt_index(index)?;
+                self.push(self.instance.cvt[index])?;
+            }
+            // GC
+            0x46 | 0x47 => {
+                let point = self.pop()?;
+                let point = self.point(2, point)?;
+                let value = match opcode {
+                    0x46 => self.project(self.current(2, point)),
+                    _ => self.dual_project(self.original(2, point)),
+                };
+                self.push(value)?;
+            }
+            // SCFS
+            0x48 => {
+                let value = self.pop()?;
+                let point = self.pop()?;
+                let point = self.point(2, point)?;
+                let current = self.project(self.current(2, point));
+                self.move_point(2, point, value.wrapping_sub(current));
+                if self.zp(2) == 0 {
+                    let zone = self.zone_mut(0);
+                    zone.original[point] = zone.current[point];
+                }
+            }
+            // MD
+            0x49 | 0x4A => {
+                let k = self.pop()?;
+                let l = self.pop()?;
+                let k = self.point(1, k)?;
+                let l = self.point(0, l)?;
+                let distance = match opcode {
+                    0x49 => self.project(sub(self.current(0, l), self.current(1, k))),
+                    _ => self.original_distance((0, l), (1, k)),
+                };
+                self.push(distance)?;
+            }
+            // MPPEM、MPS
+            0x4B | 0x4C => self.push(self.size.ppem as i32)?,
+            // FLIPON、FLIPOFF
+            0x4D => self.gs_mut().auto_flip = true,
+            0x4E => self.gs_mut().auto_flip = false,
+            // DEBUG、SANGW、AA
+            0x4F | 0x7E | 0x7F => {
+                self.pop()?;
+            }
+            // LT、LTEQ、GT、GTEQ、EQ、NEQ
+            0x50..=0x55 => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                let result = match opcode {
+                    0x50 => a < b,
+                    0x51 => a <= b,
+                    0x52 => a > b,
+                    0x53 => a >= b,
+                    0x54 => a == b,
+                    _ => a != b,
+                };
+                self.push(result as i32)?;
+            }
+            // ODD、EVEN
+            0x56 | 0x57 => {
+                let value = self.pop()?;
+                let value = self.round(value) & 127;
+                let result = match opcode {
+                    0x56 => value == 64,
+                    _ => value == 0,
+                };
+                self.push(result as i32)?;
+            }
+            // IF
+            0x58 => {
+                if !self.pop_bool()? {
+                    self.skip_branch(true)?;
+                }
+            }
+            // EIF
+            0x59 => {}
+            // AND、OR
+            0x5A | 0x5B => {
+                let b = self.pop_bool()?;
+                let a = self.pop_bool()?;
+                let result = match opcode {
+                    0x5A => a && b,
+                    _ => a || b,
+                };
+                self.push(result as i32)?;
+            }
+            // NOT
+            0x5C => {
+                let value = self.pop_bool()?;
+                self.push(!value as i32)?;
+            }
+            // DELTAP1、DELTAP2、DELTAP3
+            0x5D | 0x71 | 0x72 => self.delta(opcode, false)?,
+            // DELTAC1、DELTAC2、DELTAC3
+            0x73..=0x75 => self.delta(opcode, true)?,
+            // SDB、SDS
+            0x5E => self.gs_mut().delta_base = self.pop()?,
+            0x5F => {
+                let shift = self.pop()?;
+                if !(0..=6).contains(&shift) {
+                    return Err(format!("delta shift {} out of range 0..=6", shift));
+                }
+                self.gs_mut().delta_shift = shift;
+            }
+            // ADD、SUB、DIV、MUL、MAX、MIN
+            0x60..=0x63 | 0x8B | 0x8C => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                let result = match opcode {
+                    0x60 => a.wrapping_add(b),
+                    0x61 => a.wrapping_sub(b),
+                    0x62 if b == 0 => return Err("division by zero".to_string()),
+                    0x62 => mul_div_no_round(a, 64, b),
+                    0x63 => mul_div(a, b, 64),
+                    0x8B => a.max(b),
+                    _ => a.min(b),
+                };
+                self.push(result)?;
+            }
+            // ABS、NEG、FLOOR、CEILING、ROUND、NROUND
+            0x64..=0x6F => {
+                let value = self.pop()?;
+                let result = match opcode {
+                    0x64 => value.wrapping_abs(),
+                    0x65 => value.wrapping_neg(),
+                    0x66 => floor(value),
+                    0x67 => ceil(value),
+                    0x68..=0x6B => self.round(value),
+                    _ => value,
+                };
+                self.push(result)?;
+            }
+            // SROUND、S45ROUND
+            0x76 | 0x77 => {
+                let selector = self.pop()?;
+                let mode = match opcode {
+                    0x76 => RoundMode::Super,
+                    _ => RoundMode::Super45,
+                };
+                self.gs_mut().round_state.set_super(selector, mode);
+            }
+            // JROT、JROF
+            0x78 | 0x79 => {
+                let condition = self.pop_bool()?;
+                let offset = self.pop()?;
+                if condition == (opcode == 0x78) {
+                    self.jump(offset)?;
+                }
+            }
+            // FLIPPT
+            0x80 => self.for_each_point(0, |engine, point| {
+                let zone = engine.zone_mut(engine.zp(0));
+                zone.flags[point] ^= ON_CURVE;
+                Ok(())
+            })?,
+            // FLIPRGON、FLIPRGOFF
+            0x81 | 0x82 => {
+                let high = self.pop()?;
+                let low = self.pop()?;
+                let high = self.point(0, high)?;
+                let low = self.point(0, low)?;
+                let zone = self.zone_mut(self.zp(0));
+                for flags in zone.flags.iter_mut().take(high + 1).skip(low) {
+                    match opcode {
+                        0x81 => *flags |= ON_CURVE,
+                        _ => *flags &= !ON_CURVE,
+                    }
+                }
+            }
+            // SCANCTRL、SCANTYPE
+            0x85 => self.gs_mut().scan_control = self.pop()?,
+            0x8D => self.gs_mut().scan_type = self.pop()?,
+            // SDPVTL
+            0x86 | 0x87 => {
+                let p1 = self.pop()?;
+                let p2 = self.pop()?;
+                let p1 = self.point(2, p1)?;
+                let p2 = self.point(1, p2)?;
+                let perpendicular = opcode & 1 != 0;
+                let dual =
+                    self.line_vector(self.original(1, p2), self.original(2, p1), perpendicular);
+                let projection =
+                    self.line_vector(self.current(1, p2), self.current(2, p1), perpendicular);
+                let gs = self.gs_mut();
+                gs.dual_projection = dual;
+                gs.projection = projection;
+            }
+            // GETINFO
+            0x88 => {
+                let selector = self.pop()?;
+                let mut result = 0;
+                // 解释器版本
+                if selector & 1 != 0 {
+                    result = 35;
+                }
+                // 灰度渲染
+                if selector & 32 != 0 {
+                    result |= 1 << 12;
+                }
+                self.push(result)?;
+            }
+            // IDEF
+            0x89 => {
+                let opcode = self.pop()?;
+                let opcode = u8::try_from(opcode)
+                    .map_err(|_| format!("instruction opcode {} out of range", opcode))?;
+                let definition = self.define()?;
+                let limit = self.limits.instruction_defs;
+                let instructions = &mut self.definitions.to_mut().instructions;
+                if !instructions.contains_key(&opcode) && instructions.len() >= limit {
+                    return Err(format!("more than {} instruction definitions", limit));
+                }
+                instructions.insert(opcode, definition);
+            }
+            // ROLL
+            0x8A => {
+                let a = self.pop()?;
+                let b = self.pop()?;
+                let c = self.pop()?;
+                self.push(b)?;
+                self.push(a)?;
+                self.push(c)?;
+            }
+            // INSTCTRL，只在 `prep` 中生效
+            0x8E => {
+                let selector = self.pop()?;
+                let value = self.pop()?;
+                if self.program == Program::ControlValue && (1..=3).contains(&selector) {
+                    let bit = 1 << (selector - 1);
+                    let gs = self.gs_mut();
+                    gs.instruct_control &= !bit;
+                    if value != 0 {
+                        gs.instruct_control |= bit;
+                    }
+                }
+            }
+            // MDRP
+            0xC0..=0xDF => self.move_direct_relative(opcode)?,
+            // MIRP
+            0xE0..=0xFF => self.move_indirect_relative(opcode)?,
+            _ => match self.definitions.instructions.get(&opcode) {
+                Some(&definition) => self.call(definition, 1)?,
+                None => return Err(format!("invalid opcode 0x{:02X}", opcode)),
+            },
+        }
+        Ok(())
+    }
+
+    /// `rp1` 设为 `rp0`，`rp2` 设为 `point`，`set_rp0` 时 `rp0` 也设为 `point`
+    fn set_reference_points(&mut self, point: usize, set_rp0: bool) {
+        let gs = self.gs_mut();
+        gs.reference_points[1] = gs.reference_points[0];
+        gs.reference_points[2] = point;
+        if set_rp0 {
+            gs.reference_points[0] = point;
+        }
+    }
+
+    /// 按最小距离调整舍入后的距离，符号由原始距离决定
+    fn apply_minimum_distance(&self, original: F26Dot6, distance: F26Dot6) -> F26Dot6 {
+        let minimum = self.gs().minimum_distance;
+        match original >= 0 {
+            true => distance.max(minimum),
+            false => distance.min(minimum.wrapping_neg()),
+        }
+    }
+
+    /// 距离接近单一宽度时使用单一宽度
+    fn single_width(&self, distance: F26Dot6) -> F26Dot6 {
+        let gs = self.gs();
+        let value = gs.single_width_value;
+        match distance.wrapping_sub(value).wrapping_abs() < gs.single_width_cut_in {
+            true if distance >= 0 => value,
+            true => value.wrapping_neg(),
+            false => distance,
+        }
+    }
+
+    /// MDRP[abcde]：保持 `point` 与 `rp0` 在原始轮廓上的距离
+    fn move_direct_relative(&mut self, opcode: u8) -> Result<(), String> {
+        let point = self.pop()?;
+        let point = self.point(1, point)?;
+        let rp0 = self.reference_point(0, 0)?;
+        let original = self.single_width(self.original_distance((1, point), (0, rp0)));
+        let mut distance = match opcode & 4 {
+            0 => original,
+            _ => self.round(original),
+        };
+        if opcode & 8 != 0 {
+            distance = self.apply_minimum_distance(original, distance);
+        }
+        let current = self.project(sub(self.current(1, point), self.current(0, rp0)));
+        self.move_point(1, point, distance.wrapping_sub(current));
+        self.set_reference_points(point, opcode & 16 != 0);
+        Ok(())
+    }
+
+    /// MIRP[abcde]：按控制值设置 `point` 与 `rp0` 的距离
+    fn move_indirect_relative(&mut self, opcode: u8) -> Result<(), String> {
+        let cvt = self.pop()?;
+        let point = self.pop()?;
+        let point = self.point(1, point)?;
+        let rp0 = self.reference_point(0, 0)?;
+        // 控制值 -1 视为 0
+        let mut cvt_distance = match cvt {
+            -1 => 0,
+            cvt => self.single_width(self.instance.cvt[self.cvt_index(cvt)?]),
+        };
+        if self.zp(1) == 0 {
+            let origin = self.original(0, rp0);
+            let [fx, fy] = self.gs().freedom;
+            let zone = self.zone_mut(0);
+            zone.original[point] = [
+                origin[0].wrapping_add(mul_14(cvt_distance, fx)),
+                origin[1].wrapping_add(mul_14(cvt_distance, fy)),
+            ];
+            zone.current[point] = zone.original[point];
+        }
+        let original = self.dual_project(sub(self.original(1, point), self.original(0, rp0)));
+        let current = self.project(sub(self.current(1, point), self.current(0, rp0)));
+        if self.gs().auto_flip && (original ^ cvt_distance) < 0 {
+            cvt_distance = cvt_distance.wrapping_neg();
+        }
+        let mut distance = match opcode & 4 {
+            0 => cvt_distance,
+            _ => {
+                // 两点在同一区域时才比较控制值与原始距离
+                if self.zp(0) == self.zp(1)
+                    && cvt_distance.wrapping_sub(original).wrapping_abs()
+                        > self.gs().control_value_cut_in
+                {
+                    cvt_distance = original;
+                }
+                self.round(cvt_distance)
+            }
+        };
+        if opcode & 8 != 0 {
+            distance = self.apply_minimum_distance(original, distance);
+        }
+        self.move_point(1, point, distance.wrapping_sub(current));
+        self.set_reference_points(point, opcode & 16 != 0);
+        Ok(())
+    }
+
+    /// IP：按 `rp1`、`rp2` 在原始轮廓和当前轮廓上的距离比例移动点
+    fn interpolate_points(&mut self) -> Result<(), String> {
+        let twilight = (0..3).any(|zp| self.zp(zp) == 0);
+        let rp1 = self.reference_point(1, 0)?;
+        let rp2 = self.reference_point(2, 1)?;
+        // 都在字形区域时用未缩放坐标计算比例
+        let original = |engine: &Self, zp: usize, point: usize| match twilight {
+            true => engine.original(zp, point),
+            false => engine.glyph.unscaled[point],
+        };
+        let original_base = original(self, 0, rp1);
+        let current_base = self.current(0, rp1);
+        let original_range = self.dual_project(sub(original(self, 1, rp2), original_base));
+        let current_range = self.project(sub(self.current(1, rp2), current_base));
+        self.for_each_point(2, |engine, point| {
+            let original_distance =
+                engine.dual_project(sub(original(engine, 2, point), original_base));
+            let current_distance = engine.project(sub(engine.current(2, point), current_base));
+            let distance = match (original_distance, original_range) {
+                (0, _) => 0,
+                (d, 0) => d,
+                (d, range) => mul_div(d, current_range, range),
+            };
+            engine.move_point(2, point, distance.wrapping_sub(current_distance));
+            Ok(())
+        })
+    }
+
+    /// ISECT：把 `zp2` 中的点移到直线 `a0a1`（`zp1`）与 `b0b1`（`zp0`）的交点
+    fn intersect(&mut self) -> Result<(), String> {
+        let b1 = self.pop()?;
+        let b0 = self.pop()?;
+        let a1 = self.pop()?;
+        let a0 = self.pop()?;
+        let point = self.pop()?;
+        let (b0, b1) = (self.point(0, b0)?, self.point(0, b1)?);
+        let (a0, a1) = (self.point(1, a0)?, self.point(1, a1)?);
+        let point = self.point(2, point)?;
+        let (a0, a1) = (self.current(1, a0), self.current(1, a1));
+        let (b0, b1) = (self.current(0, b0), self.current(0, b1));
+        let [dbx, dby] = sub(b1, b0);
+        let [dax, day] = sub(a1, a0);
+        let [dx, dy] = sub(b0, a0);
+        let discriminant =
+            mul_div(dax, dby.wrapping_neg(), 0x40).wrapping_add(mul_div(day, dbx, 0x40));
+        let dot_product = mul_div(dax, dbx, 0x40).wrapping_add(mul_div(day, dby, 0x40));
+        // 夹角小于约 3° 时视为平行，取两条线段中点的中点
+        let position = match 19i64 * (discriminant as i64).abs() > (dot_product as i64).abs() {
+            true => {
+                let value =
+                    mul_div(dx, dby.wrapping_neg(), 0x40).wrapping_add(mul_div(dy, dbx, 0x40));
+                [
+                    a0[0].wrapping_add(mul_div(value, dax, discriminant)),
+                    a0[1].wrapping_add(mul_div(value, day, discriminant)),
+                ]
+            }
+            false => {
+                let mid = |axis: usize| {
+                    ((a0[axis] as i64 + a1[axis] as i64 + b0[axis] as i64 + b1[axis] as i64) / 4)
+                        as i32
+                };
+                [mid(0), mid(1)]
+            }
+        };
+        let zone = self.zone_mut(self.zp(2));
+        zone.current[point] = position;
+        zone.flags[point] |= TOUCHED_X | TOUCHED_Y;
+        Ok(())
+    }
+
+    /// DELTAP 和 DELTAC：在指定的 `ppem` 下微调点或控制值
+    ///
+    /// 超出范围的点被忽略，一些常见字体中存在这样的指令。
+    fn delta(&mut self, opcode: u8, cvt: bool) -> Result<(), String> {
+        let count = self.pop()?;
+        let range = match opcode {
+            0x5D | 0x73 => 0,
+            0x71 | 0x74 => 16,
+            _ => 32,
+        };
+        for _ in 0..count.max(0) {
+            let target = self.pop()?;
+            let argument = self.pop()?;
+            let ppem = (((argument & 0xF0) >> 4) + range).wrapping_add(self.gs().delta_base);
+            if ppem != self.size.ppem as i32 {
+                continue;
+            }
+            let mut step = (argument & 0xF) - 8;
+            if step >= 0 {
+                step += 1;
+            }
+            let delta = step * (1 << (6 - self.gs().delta_shift));
+            if cvt {
+                let index = self.cvt_index(target)?;
+                self.instance.cvt[index] = self.instance.cvt[index].wrapping_add(delta);
+            } else if let Ok(point) = self.point(0, target) {
+                self.move_point(0, point, delta);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn sub(a: [i32; 2], b: [i32; 2]) -> [i32; 2] {
+    [a[0].wrapping_sub(b[0]), a[1].wrapping_sub(b[1])]
+}
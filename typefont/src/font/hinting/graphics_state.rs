@@ -0,0 +1,173 @@
+use super::math::{F26Dot6, ONE_14, ceil, floor, round};
+
+/// 舍入模式，由 `RTG`、`RTHG`、`SROUND` 等指令设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RoundMode {
+    ToHalfGrid,
+    ToGrid,
+    ToDoubleGrid,
+    DownToGrid,
+    UpToGrid,
+    Off,
+    /// `SROUND`
+    Super,
+    /// `S45ROUND`，周期为 `1/√2` 像素的倍数
+    Super45,
+}
+
+/// 舍入状态，`period`、`phase` 和 `threshold` 只在 `Super` 和 `Super45` 模式下使用
+#[derive(Debug, Clone, Copy)]
+pub(super) struct RoundState {
+    pub(super) mode: RoundMode,
+    period: F26Dot6,
+    phase: F26Dot6,
+    threshold: F26Dot6,
+}
+
+impl Default for RoundState {
+    fn default() -> Self {
+        Self {
+            mode: RoundMode::ToGrid,
+            period: 64,
+            phase: 0,
+            threshold: 32,
+        }
+    }
+}
+
+impl RoundState {
+    /// 按 `SROUND`/`S45ROUND` 的参数设置周期、相位和阈值
+    pub(super) fn set_super(&mut self, selector: i32, mode: RoundMode) {
+        // 以 1/256 像素为单位计算，S45ROUND 的周期为 64/√2
+        let grid_period = match mode {
+            RoundMode::Super45 => 0x2D41,
+            _ => 0x4000,
+        };
+        let period = match selector & 0xC0 {
+            0x00 => grid_period / 2,
+            0x80 => grid_period * 2,
+            _ => grid_period,
+        };
+        let phase = match selector & 0x30 {
+            0x00 => 0,
+            0x10 => period / 4,
+            0x20 => period / 2,
+            _ => period * 3 / 4,
+        };
+        let threshold = match selector & 0x0F {
+            0 => period - 1,
+            n => (n - 4) * period / 8,
+        };
+        self.mode = mode;
+        self.period = period >> 8;
+        self.phase = phase >> 8;
+        self.threshold = threshold >> 8;
+    }
+
+    /// 按当前模式舍入距离，结果与原值符号相反时取 0 或最近的非零值
+    pub(super) fn round(&self, distance: F26Dot6) -> F26Dot6 {
+        let positive = distance >= 0;
+        let magnitude = distance.wrapping_abs();
+        let value = match self.mode {
+            RoundMode::Off => return distance,
+            RoundMode::ToGrid => round(magnitude),
+            RoundMode::ToHalfGrid => floor(magnitude).wrapping_add(32),
+            RoundMode::ToDoubleGrid => magnitude.wrapping_add(16) & !31,
+            RoundMode::DownToGrid => floor(magnitude),
+            RoundMode::UpToGrid => ceil(magnitude),
+            RoundMode::Super => {
+                let value = magnitude.wrapping_add(self.threshold - self.phase) & -self.period;
+                value.wrapping_add(self.phase)
+            }
+            RoundMode::Super45 => {
+                let value = magnitude.wrapping_add(self.threshold - self.phase) / self.period;
+                value.wrapping_mul(self.period).wrapping_add(self.phase)
+            }
+        };
+        let value = match self.mode {
+            RoundMode::ToHalfGrid if value < 0 => 32,
+            RoundMode::Super | RoundMode::Super45 if value < 0 => self.phase,
+            _ if value < 0 => 0,
+            _ => value,
+        };
+        match positive {
+            true => value,
+            false => value.wrapping_neg(),
+        }
+    }
+}
+
+/// 图形状态
+///
+/// 每个字形程序从 `prep` 执行后的图形状态开始，
+/// `instruct_control` 的第 2 位置位时改为从默认状态开始。
+#[derive(Debug, Clone)]
+pub(super) struct GraphicsState {
+    /// 投影向量，2.14 定点数的单位向量
+    pub(super) projection: [i32; 2],
+    /// 自由向量，点沿此方向移动
+    pub(super) freedom: [i32; 2],
+    /// 对偶投影向量，用于测量原始轮廓
+    pub(super) dual_projection: [i32; 2],
+    /// 参考点 `rp0`、`rp1`、`rp2`
+    pub(super) reference_points: [usize; 3],
+    /// 区域指针 `zp0`、`zp1`、`zp2`，0 为暗区，1 为字形区域
+    pub(super) zone_pointers: [usize; 3],
+    pub(super) loop_count: u32,
+    pub(super) minimum_distance: F26Dot6,
+    pub(super) round_state: RoundState,
+    pub(super) control_value_cut_in: F26Dot6,
+    pub(super) single_width_cut_in: F26Dot6,
+    pub(super) single_width_value: F26Dot6,
+    pub(super) auto_flip: bool,
+    pub(super) delta_base: i32,
+    pub(super) delta_shift: i32,
+    /// 第 1 位禁止执行字形程序，第 2 位让字形程序使用默认图形状态
+    pub(super) instruct_control: u8,
+    pub(super) scan_control: i32,
+    pub(super) scan_type: i32,
+}
+
+impl Default for GraphicsState {
+    fn default() -> Self {
+        Self {
+            projection: [ONE_14, 0],
+            freedom: [ONE_14, 0],
+            dual_projection: [ONE_14, 0],
+            reference_points: [0; 3],
+            zone_pointers: [1; 3],
+            loop_count: 1,
+            minimum_distance: 64,
+            round_state: RoundState::default(),
+            // 17/16 像素
+            control_value_cut_in: 68,
+            single_width_cut_in: 0,
+            single_width_value: 0,
+            auto_flip: true,
+            delta_base: 9,
+            delta_shift: 3,
+            instruct_control: 0,
+            scan_control: 0,
+            scan_type: 0,
+        }
+    }
+}
+
+impl GraphicsState {
+    /// 恢复 `prep` 不能修改的状态：向量、参考点、区域指针和循环次数
+    pub(super) fn reset_after_prep(&mut self) {
+        let default = Self::default();
+        self.projection = default.projection;
+        self.freedom = default.freedom;
+        self.dual_projection = default.dual_projection;
+        self.reference_points = default.reference_points;
+        self.zone_pointers = default.zone_pointers;
+        self.loop_count = default.loop_count;
+    }
+
+    /// 字形程序开始时重置的状态
+    pub(super) fn reset_for_glyph(&mut self) {
+        self.reset_after_prep();
+        self.round_state.mode = RoundMode::ToGrid;
+    }
+}
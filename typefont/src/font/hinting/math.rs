@@ -0,0 +1,131 @@
+// 定点数运算
+//
+// 舍入方式与常见的 `TrueType` 光栅化器一致，溢出时回绕而不是报错，
+// 字体数据中的任意值都不会导致崩溃。
+
+/// 26.6 定点数，单位为像素
+pub type F26Dot6 = i32;
+
+/// 2.14 定点数表示的 1
+pub(super) const ONE_14: i32 = 0x4000;
+
+/// 16.16 定点数表示的 1
+pub(super) const ONE_16: i32 = 0x10000;
+
+/// `a * b`，`b` 为 16.16 定点数，四舍五入
+pub(super) fn mul_fix(a: i32, b: i32) -> i32 {
+    let ab = a as i64 * b as i64;
+    ((ab + 0x8000 - (ab < 0) as i64) >> 16) as i32
+}
+
+/// `a / b` 的 16.16 定点数结果，四舍五入，除数为 0 时返回最大值
+pub(super) fn div_fix(a: i32, b: i32) -> i32 {
+    let negative = (a < 0) != (b < 0);
+    let (a, b) = (a.unsigned_abs() as u64, b.unsigned_abs() as u64);
+    let q = match b {
+        0 => 0x7FFF_FFFF,
+        _ => (((a << 16) + (b >> 1)) / b) as u32 as i32,
+    };
+    if negative { q.wrapping_neg() } else { q }
+}
+
+/// `a * b / c`，四舍五入，除数为 0 时返回带符号的最大值
+pub(super) fn mul_div(a: i32, b: i32, c: i32) -> i32 {
+    mul_div_with(a, b, c, true)
+}
+
+/// `a * b / c`，向零取整
+pub(super) fn mul_div_no_round(a: i32, b: i32, c: i32) -> i32 {
+    mul_div_with(a, b, c, false)
+}
+
+fn mul_div_with(a: i32, b: i32, c: i32, round: bool) -> i32 {
+    let negative = (a < 0) ^ (b < 0) ^ (c < 0);
+    let (a, b, c) = (
+        a.unsigned_abs() as u64,
+        b.unsigned_abs() as u64,
+        c.unsigned_abs() as u64,
+    );
+    let d = match c {
+        0 => 0x7FFF_FFFF,
+        _ => {
+            let half = if round { c >> 1 } else { 0 };
+            (a.wrapping_mul(b).wrapping_add(half) / c) as i32
+        }
+    };
+    if negative { d.wrapping_neg() } else { d }
+}
+
+/// `a * b`，`b` 为 2.14 定点数
+pub(super) fn mul_14(a: i32, b: i32) -> i32 {
+    let ab = a as i64 * b as i64;
+    ((ab + 0x2000 - (ab < 0) as i64) >> 14) as i32
+}
+
+/// 向量 (`ax`, `ay`) 与 2.14 定点数向量 (`bx`, `by`) 的点积
+pub(super) fn dot_14(ax: i32, ay: i32, bx: i32, by: i32) -> i32 {
+    let s = ax as i64 * bx as i64 + ay as i64 * by as i64;
+    ((s + 0x2000 - (s < 0) as i64) >> 14) as i32
+}
+
+/// 把向量归一化为 2.14 定点数的单位向量，零向量返回 `None`
+///
+/// 先把长度预缩放到 1 附近，再用牛顿迭代求长度的倒数，从下方逼近，
+/// 结果与常见光栅化器逐位一致。
+pub(super) fn normalize(x: i32, y: i32) -> Option<[i32; 2]> {
+    let (mut ax, mut ay) = (x.unsigned_abs(), y.unsigned_abs());
+    let sign = |v: i32, u: u32| match v < 0 {
+        true => (u as i32).wrapping_neg(),
+        false => u as i32,
+    };
+    match (ax, ay) {
+        (0, 0) => return None,
+        (0, _) => return Some([0, sign(y, ONE_14 as u32)]),
+        (_, 0) => return Some([sign(x, ONE_14 as u32), 0]),
+        _ => {}
+    }
+    let estimate = |ax: u32, ay: u32| match ax > ay {
+        true => ax.wrapping_add(ay >> 1),
+        false => ay.wrapping_add(ax >> 1),
+    };
+    // 预缩放使估计长度位于 16.16 定点数的 2/3 到 4/3 之间
+    let mut length = estimate(ax, ay);
+    let mut shift = length.leading_zeros() as i32;
+    shift -= 15 + (length >= (0xAAAA_AAAAu32 >> shift)) as i32;
+    if shift > 0 {
+        ax <<= shift;
+        ay <<= shift;
+        length = estimate(ax, ay);
+    } else {
+        ax >>= -shift;
+        ay >>= -shift;
+        length >>= -shift;
+    }
+    let mut b = ONE_16.wrapping_sub(length as i32);
+    let (xi, yi) = (ax as i32, ay as i32);
+    let (mut u, mut v);
+    loop {
+        u = xi.wrapping_add(xi.wrapping_mul(b) >> 16) as u32;
+        v = yi.wrapping_add(yi.wrapping_mul(b) >> 16) as u32;
+        let square = u.wrapping_mul(u).wrapping_add(v.wrapping_mul(v)) as i32;
+        let z = square.wrapping_neg() / 0x200;
+        let z = z.wrapping_mul((ONE_16 + b) >> 8) / ONE_16;
+        b = b.wrapping_add(z);
+        if z <= 0 {
+            break;
+        }
+    }
+    Some([sign(x, u) / 4, sign(y, v) / 4])
+}
+
+pub(super) fn floor(value: F26Dot6) -> F26Dot6 {
+    value & !63
+}
+
+pub(super) fn ceil(value: F26Dot6) -> F26Dot6 {
+    value.wrapping_add(63) & !63
+}
+
+pub(super) fn round(value: F26Dot6) -> F26Dot6 {
+    value.wrapping_add(32) & !63
+}
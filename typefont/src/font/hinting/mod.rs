@@ -0,0 +1,500 @@
+use crate::font::GlyphId;
+use crate::font::open_type::OpenType;
+use crate::font::outline::{OutlinePen, draw_contour};
+use crate::font::table::glyf::{
+    ComponentPlacement, ComponentTransform, Glyf, GlyfGlyphDescription,
+};
+use crate::font::table::hmtx::Hmtx;
+use crate::font::table::maxp::Maxp;
+use crate::io::error::IOError;
+use crate::types::F2D14;
+use engine::{Definitions, Engine, Instance, Limits, Program, Size};
+use graphics_state::GraphicsState;
+use math::{ONE_16, div_fix, mul_fix, round};
+use std::borrow::Cow;
+use zone::{ON_CURVE, Zone};
+
+mod engine;
+mod graphics_state;
+mod math;
+mod zone;
+
+pub use math::F26Dot6;
+
+/// 网格拟合选项
+#[derive(Debug, Clone, Copy)]
+pub struct HintOptions {
+    /// 每 em 的像素数
+    ppem: u16,
+    /// 每次执行 `fpgm`、`prep` 或字形程序最多执行的指令数，超出时返回错误
+    instruction_limit: usize,
+}
+
+impl HintOptions {
+    pub fn new(ppem: u16) -> Self {
+        Self {
+            ppem,
+            instruction_limit: 1_000_000,
+        }
+    }
+
+    pub fn with_instruction_limit(mut self, instruction_limit: usize) -> Self {
+        self.instruction_limit = instruction_limit;
+        self
+    }
+
+    pub fn ppem(&self) -> u16 {
+        self.ppem
+    }
+
+    pub fn instruction_limit(&self) -> usize {
+        self.instruction_limit
+    }
+}
+
+/// # `TrueType` 字节码解释器
+///
+/// 创建时按 `ppem` 缩放 `cvt` 表并依次执行 `fpgm` 和 `prep`，
+/// 之后每个字形从 `prep` 执行后的状态开始执行自己的指令，得到网格拟合后的轮廓。
+/// 控制值表、存储区和暗区在字形之间互不影响。
+///
+/// 存储区、暗区、函数和指令定义的数量以及栈的深度由 `maxp` 表限定，
+/// 索引越界、栈溢出、调用嵌套过深或指令数超出 [HintOptions::instruction_limit] 时返回错误，
+/// 字体数据不会导致崩溃或死循环。
+///
+/// 只支持 `glyf` 轮廓，指令的语义与常见光栅化器的 v35 解释器一致。
+pub struct Hinter<'a> {
+    glyf: &'a Glyf,
+    hmtx: &'a Hmtx,
+    fpgm: &'a [u8],
+    prep: &'a [u8],
+    definitions: Definitions,
+    /// `prep` 执行后的状态
+    instance: Instance,
+    limits: Limits,
+    size: Size,
+    /// 垂直幻影点使用的 `hhea.ascender` 和 `hhea.descender`
+    ascender: i32,
+    descender: i32,
+    max_component_depth: u16,
+}
+
+impl<'a> Hinter<'a> {
+    pub fn new(font: &'a OpenType, options: &HintOptions) -> Result<Self, IOError> {
+        let missing = |tag: &str| IOError::UnableOperate(format!("font has no {} table", tag));
+        let head = font.head()?.ok_or_else(|| missing("head"))?;
+        let hhea = font.hhea()?.ok_or_else(|| missing("hhea"))?;
+        let maxp = font
+            .maxp()?
+            .and_then(Maxp::as_v1_0)
+            .ok_or_else(|| missing("version 1.0 maxp"))?;
+        let glyf = font.glyf()?.ok_or_else(|| missing("glyf"))?;
+        let hmtx = font.hmtx()?.ok_or_else(|| missing("hmtx"))?;
+        let fpgm = font.fpgm()?.map_or(&[][..], |fpgm| fpgm.data());
+        let prep = font.prep()?.map_or(&[][..], |prep| prep.data());
+
+        let scale = div_fix(options.ppem as i32 * 64, head.units_per_em() as i32);
+        let cvt = font.cvt()?.map_or(vec![], |cvt| {
+            cvt.data()
+                .iter()
+                .map(|&value| mul_fix(value as i32, scale))
+                .collect()
+        });
+        let limits = Limits {
+            // 与常见光栅化器一样多留 32 个元素，一些字体的 `max_stack_elements` 偏小
+            stack: maxp.max_stack_elements() as usize + 32,
+            function_defs: maxp.max_function_defs() as usize,
+            instruction_defs: maxp.max_instruction_defs() as usize,
+            instructions: options.instruction_limit,
+        };
+        let size = Size {
+            ppem: options.ppem,
+            scale,
+            glyph_scale: scale,
+        };
+        // 暗区多留 4 个点，与常见光栅化器一致
+        let twilight = Zone::twilight(maxp.max_twilight_points() as usize + 4);
+        let instance = Instance {
+            cvt,
+            storage: vec![0; maxp.max_storage() as usize],
+            twilight: twilight.clone(),
+            graphics_state: GraphicsState::default(),
+        };
+
+        let code = [fpgm, prep, &[][..]];
+        let mut engine = Engine::new(
+            code,
+            Cow::Owned(Definitions::default()),
+            limits,
+            size,
+            instance,
+            Zone::default(),
+        );
+        let run = |engine: &mut Engine, program| {
+            engine
+                .run(program)
+                .map_err(|e| IOError::UnableOperate(format!("ppem {}: {}", options.ppem, e)))
+        };
+        run(&mut engine, Program::Font)?;
+        engine.instance.graphics_state = GraphicsState::default();
+        engine.instance.twilight = twilight;
+        run(&mut engine, Program::ControlValue)?;
+        engine.instance.graphics_state.reset_after_prep();
+        let instance = engine.instance.clone();
+        let definitions = engine.into_definitions();
+
+        Ok(Self {
+            glyf,
+            hmtx,
+            fpgm,
+            prep,
+            definitions,
+            instance,
+            limits,
+            size,
+            ascender: hhea.ascender() as i32,
+            descender: hhea.descender() as i32,
+            max_component_depth: maxp.max_component_depth(),
+        })
+    }
+
+    pub fn ppem(&self) -> u16 {
+        self.size.ppem
+    }
+
+    /// 网格拟合后的字形，原点位于水平方向的第一个幻影点
+    ///
+    /// 复合字形的组件分别执行自己的指令后再组合，之后执行复合字形的指令。
+    /// 组件循环引用、嵌套层数超出 `maxp.max_component_depth` 或指令执行出错时返回错误。
+    pub fn hint_glyph(&self, glyph_id: GlyphId) -> Result<HintedGlyph, IOError> {
+        let outline = self
+            .load(glyph_id, &mut vec![])
+            .map_err(|e| IOError::UnableOperate(format!("glyph {}: {}", glyph_id, e)))?;
+        let [pp1, pp2, _, _] = outline.phantoms;
+        let points = outline
+            .points
+            .iter()
+            .zip(&outline.flags)
+            .map(|(&[x, y], &flags)| HintedPoint {
+                x: x.wrapping_sub(pp1[0]),
+                y,
+                on_curve: flags & ON_CURVE != 0,
+            })
+            .collect();
+        Ok(HintedGlyph {
+            points,
+            end_points: outline.end_points,
+            advance_width: round(pp2[0].wrapping_sub(pp1[0])),
+        })
+    }
+
+    /// 网格拟合后的轮廓，`path` 为正在展开的复合字形
+    fn load(&self, glyph_id: GlyphId, path: &mut Vec<GlyphId>) -> Result<Outline, String> {
+        if glyph_id as usize >= self.glyf.len() {
+            return Err(format!(
+                "glyph {} out of range 0..{}",
+                glyph_id,
+                self.glyf.len()
+            ));
+        }
+        let glyph = self.glyf.glyph(glyph_id).map_err(|e| e.to_string())?;
+        let x_min = glyph.map_or(0, |glyph| glyph.header().x_min() as i32);
+        let lsb = self.hmtx.lsb(glyph_id).unwrap_or(0) as i32;
+        let advance = self.hmtx.advance_width(glyph_id).unwrap_or(0) as i32;
+        let phantoms = [
+            [x_min - lsb, 0],
+            [x_min - lsb + advance, 0],
+            [0, self.ascender],
+            [0, self.descender],
+        ];
+
+        let composite = match glyph.map(|glyph| glyph.glyph_description()) {
+            None => return self.hint(vec![], vec![], vec![], phantoms, &[]),
+            Some(GlyfGlyphDescription::Format1(simple)) => {
+                let flags = simple
+                    .points()
+                    .iter()
+                    .map(|point| point.on_curve() as u8)
+                    .collect();
+                let unscaled = simple
+                    .points()
+                    .iter()
+                    .map(|point| [point.x() as i32, point.y() as i32])
+                    .collect();
+                let end_points = simple
+                    .end_pts_of_contours()
+                    .iter()
+                    .map(|&end| end as usize)
+                    .collect();
+                return self.hint(unscaled, flags, end_points, phantoms, simple.instructions());
+            }
+            Some(GlyfGlyphDescription::Format2(composite)) => composite,
+        };
+        if path.contains(&glyph_id) {
+            return Err(format!("component cycle {:?} -> {}", path, glyph_id));
+        }
+        if path.len() >= self.max_component_depth as usize {
+            return Err(format!(
+                "component depth of glyph {} exceeds {}",
+                path.first().unwrap_or(&glyph_id),
+                self.max_component_depth
+            ));
+        }
+
+        path.push(glyph_id);
+        let scale = self.size.scale;
+        let mut phantoms = phantoms.map(|[x, y]| [mul_fix(x, scale), mul_fix(y, scale)]);
+        let mut outline = Outline::default();
+        for component in composite.components() {
+            let mut child = self.load(component.glyph_index(), path)?;
+            let matrix = matrix(&component.transform());
+            if let Some(matrix) = matrix {
+                for point in &mut child.points {
+                    *point = transform(&matrix, *point);
+                }
+            }
+            let [dx, dy] = match component.placement() {
+                ComponentPlacement::Offset { x, y } => {
+                    let offset = [x as i32, y as i32];
+                    let offset = match matrix {
+                        Some(matrix) if component.scaled_component_offset() => {
+                            transform(&matrix, offset)
+                        }
+                        _ => offset,
+                    };
+                    let offset = offset.map(|v| mul_fix(v, scale));
+                    match component.round_xy_to_grid() {
+                        true => offset.map(round),
+                        false => offset,
+                    }
+                }
+                ComponentPlacement::Anchor {
+                    parent,
+                    child: point,
+                } => {
+                    let find = |points: &[[i32; 2]], index: u16| {
+                        points.get(index as usize).copied().ok_or_else(|| {
+                            format!("anchor point {} of glyph {} out of range", index, glyph_id)
+                        })
+                    };
+                    let parent = find(&outline.points, parent)?;
+                    let point = find(&child.points, point)?;
+                    [
+                        parent[0].wrapping_sub(point[0]),
+                        parent[1].wrapping_sub(point[1]),
+                    ]
+                }
+            };
+            let base = outline.points.len();
+            outline.points.extend(
+                child
+                    .points
+                    .iter()
+                    .map(|&[x, y]| [x.wrapping_add(dx), y.wrapping_add(dy)]),
+            );
+            outline.flags.extend(child.flags);
+            outline
+                .end_points
+                .extend(child.end_points.iter().map(|&end| base + end));
+            if component.use_my_metrics() {
+                phantoms = child.phantoms;
+            }
+        }
+        path.pop();
+
+        // 没有指令时幻影点不舍入，与常见光栅化器一致
+        if composite.instructions().is_empty() {
+            outline.phantoms = phantoms;
+            return Ok(outline);
+        }
+        // 复合字形的指令作用于组件网格拟合后的坐标
+        let flags = outline
+            .flags
+            .iter()
+            .map(|&flags| flags & ON_CURVE)
+            .collect();
+        let size = Size {
+            glyph_scale: ONE_16,
+            ..self.size
+        };
+        let zone = zone(outline.points, flags, outline.end_points, phantoms, |p| p);
+        self.run(zone, size, composite.instructions())
+    }
+
+    /// 执行简单字形的指令，`unscaled` 为字体设计单位的坐标
+    fn hint(
+        &self,
+        unscaled: Vec<[i32; 2]>,
+        flags: Vec<u8>,
+        end_points: Vec<usize>,
+        phantoms: [[i32; 2]; 4],
+        instructions: &[u8],
+    ) -> Result<Outline, String> {
+        let scale = self.size.scale;
+        let zone = zone(unscaled, flags, end_points, phantoms, |[x, y]| {
+            [mul_fix(x, scale), mul_fix(y, scale)]
+        });
+        self.run(zone, self.size, instructions)
+    }
+
+    /// 舍入幻影点后执行字形程序
+    fn run(&self, mut zone: Zone, size: Size, instructions: &[u8]) -> Result<Outline, String> {
+        let n = zone.len() - 4;
+        zone.current[n][0] = round(zone.current[n][0]);
+        zone.current[n + 1][0] = round(zone.current[n + 1][0]);
+        zone.current[n + 2][1] = round(zone.current[n + 2][1]);
+        zone.current[n + 3][1] = round(zone.current[n + 3][1]);
+
+        let mut instance = self.instance.clone();
+        let control = instance.graphics_state.instruct_control;
+        if !instructions.is_empty() && control & 1 == 0 {
+            if control & 2 != 0 {
+                instance.graphics_state = GraphicsState::default();
+            }
+            instance.graphics_state.reset_for_glyph();
+            let code = [self.fpgm, self.prep, instructions];
+            let definitions = Cow::Borrowed(&self.definitions);
+            let mut engine = Engine::new(code, definitions, self.limits, size, instance, zone);
+            engine.run(Program::Glyph)?;
+            zone = engine.glyph;
+        }
+
+        let mut points = zone.current;
+        let phantoms = [points[n], points[n + 1], points[n + 2], points[n + 3]];
+        points.truncate(n);
+        let mut flags = zone.flags;
+        flags.truncate(n);
+        Ok(Outline {
+            points,
+            flags,
+            end_points: zone.end_points,
+            phantoms,
+        })
+    }
+}
+
+/// 由未缩放的坐标创建字形区域，幻影点位于最后
+fn zone(
+    mut unscaled: Vec<[i32; 2]>,
+    mut flags: Vec<u8>,
+    end_points: Vec<usize>,
+    phantoms: [[i32; 2]; 4],
+    scale: impl Fn([i32; 2]) -> [i32; 2],
+) -> Zone {
+    unscaled.extend(phantoms);
+    flags.extend([0; 4]);
+    let original: Vec<_> = unscaled.iter().map(|&point| scale(point)).collect();
+    Zone {
+        unscaled,
+        current: original.clone(),
+        original,
+        flags,
+        end_points,
+    }
+}
+
+/// 组件变换的 16.16 定点数矩阵 `[xx, yx, xy, yy]`，没有变换时返回 `None`
+fn matrix(transform: &ComponentTransform) -> Option<[i32; 4]> {
+    let fixed = |value: F2D14| (value.to_bits() as i32) << 2;
+    match *transform {
+        ComponentTransform::None => None,
+        ComponentTransform::Scale(scale) => Some([fixed(scale), 0, 0, fixed(scale)]),
+        ComponentTransform::XYScale { x_scale, y_scale } => {
+            Some([fixed(x_scale), 0, 0, fixed(y_scale)])
+        }
+        ComponentTransform::TwoByTwo {
+            x_scale,
+            scale01,
+            scale10,
+            y_scale,
+        } => Some([
+            fixed(x_scale),
+            fixed(scale01),
+            fixed(scale10),
+            fixed(y_scale),
+        ]),
+    }
+}
+
+fn transform(&[xx, yx, xy, yy]: &[i32; 4], [x, y]: [i32; 2]) -> [i32; 2] {
+    [
+        mul_fix(x, xx).wrapping_add(mul_fix(y, xy)),
+        mul_fix(x, yx).wrapping_add(mul_fix(y, yy)),
+    ]
+}
+
+/// 网格拟合后的轮廓，坐标为 26.6 定点数，不含幻影点
+#[derive(Default)]
+struct Outline {
+    points: Vec<[i32; 2]>,
+    flags: Vec<u8>,
+    end_points: Vec<usize>,
+    phantoms: [[i32; 2]; 4],
+}
+
+/// 网格拟合后的点，坐标为 26.6 定点数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HintedPoint {
+    x: F26Dot6,
+    y: F26Dot6,
+    on_curve: bool,
+}
+
+impl HintedPoint {
+    pub fn x(&self) -> F26Dot6 {
+        self.x
+    }
+
+    pub fn y(&self) -> F26Dot6 {
+        self.y
+    }
+
+    pub fn on_curve(&self) -> bool {
+        self.on_curve
+    }
+}
+
+/// 网格拟合后的字形
+#[derive(Debug, Clone)]
+pub struct HintedGlyph {
+    points: Vec<HintedPoint>,
+    /// 每条轮廓最后一个点的索引
+    end_points: Vec<usize>,
+    /// 前进宽度，26.6 定点数，总是整数像素
+    advance_width: F26Dot6,
+}
+
+impl HintedGlyph {
+    pub fn points(&self) -> &Vec<HintedPoint> {
+        &self.points
+    }
+
+    pub fn end_points(&self) -> &Vec<usize> {
+        &self.end_points
+    }
+
+    pub fn advance_width(&self) -> F26Dot6 {
+        self.advance_width
+    }
+
+    /// 按 `end_points` 划分的轮廓
+    pub fn contours(&self) -> Vec<&[HintedPoint]> {
+        let mut start = 0;
+        self.end_points
+            .iter()
+            .map(|&end| {
+                let contour = &self.points[start..=end];
+                start = end + 1;
+                contour
+            })
+            .collect()
+    }
+
+    /// 把轮廓交给 `pen`，坐标单位为像素，`y` 轴向上
+    pub fn draw(&self, pen: &mut dyn OutlinePen) {
+        let point = |p: &HintedPoint| ((p.x as f32 / 64.0, p.y as f32 / 64.0), p.on_curve);
+        for contour in self.contours() {
+            draw_contour(contour, point, pen);
+        }
+    }
+}
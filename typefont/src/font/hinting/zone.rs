@@ -0,0 +1,124 @@
+/// 点的标志
+pub(super) const ON_CURVE: u8 = 1;
+pub(super) const TOUCHED_X: u8 = 2;
+pub(super) const TOUCHED_Y: u8 = 4;
+
+/// 指令操作的点集，坐标为 `[x, y]`
+///
+/// 字形区域包含字形的点和最后 4 个幻影点，暗区（twilight zone）的点由指令创建，
+/// 大小为 `maxp.max_twilight_points`。
+#[derive(Debug, Clone, Default)]
+pub(super) struct Zone {
+    /// 未缩放的坐标，用于按原始比例测量的指令；复合字形为组件网格拟合后的坐标
+    pub(super) unscaled: Vec<[i32; 2]>,
+    /// 执行指令前的坐标，26.6 定点数
+    pub(super) original: Vec<[i32; 2]>,
+    pub(super) current: Vec<[i32; 2]>,
+    pub(super) flags: Vec<u8>,
+    /// 每条轮廓最后一个点的索引，不包括幻影点
+    pub(super) end_points: Vec<usize>,
+}
+
+impl Zone {
+    pub(super) fn twilight(len: usize) -> Self {
+        Self {
+            unscaled: vec![[0, 0]; len],
+            original: vec![[0, 0]; len],
+            current: vec![[0, 0]; len],
+            flags: vec![0; len],
+            end_points: vec![],
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.current.len()
+    }
+
+    /// 在 `axis` 方向插值未被触碰的点，见 `IUP`
+    pub(super) fn interpolate_untouched(&mut self, axis: usize) {
+        let mask = [TOUCHED_X, TOUCHED_Y][axis];
+        let mut point = 0;
+        for contour in 0..self.end_points.len() {
+            let first = point;
+            let end = self.end_points[contour].min(self.len().saturating_sub(1));
+            while point <= end && self.flags[point] & mask == 0 {
+                point += 1;
+            }
+            if point > end {
+                continue;
+            }
+            let first_touched = point;
+            let mut last_touched = point;
+            point += 1;
+            while point <= end {
+                if self.flags[point] & mask != 0 {
+                    self.interpolate(axis, last_touched + 1, point - 1, last_touched, point);
+                    last_touched = point;
+                }
+                point += 1;
+            }
+            if last_touched == first_touched {
+                self.shift(axis, first, end, last_touched);
+            } else {
+                self.interpolate(axis, last_touched + 1, end, last_touched, first_touched);
+                if first_touched > 0 {
+                    self.interpolate(axis, first, first_touched - 1, last_touched, first_touched);
+                }
+            }
+        }
+    }
+
+    /// 只有一个被触碰的点 `touched` 时，轮廓上的其他点随它平移
+    fn shift(&mut self, axis: usize, first: usize, end: usize, touched: usize) {
+        let delta = self.current[touched][axis].wrapping_sub(self.original[touched][axis]);
+        if delta == 0 {
+            return;
+        }
+        for i in (first..=end).filter(|&i| i != touched) {
+            self.current[i][axis] = self.current[i][axis].wrapping_add(delta);
+        }
+    }
+
+    /// 按相邻的两个被触碰的点 `ref1`、`ref2` 插值 `first..=last`
+    ///
+    /// 位于两点原始坐标之外的点随较近的一点平移，之间的点按未缩放坐标的比例插值。
+    fn interpolate(&mut self, axis: usize, first: usize, last: usize, ref1: usize, ref2: usize) {
+        use super::math::{div_fix, mul_fix};
+
+        if first > last {
+            return;
+        }
+        let (ref1, ref2) = match self.unscaled[ref1][axis] > self.unscaled[ref2][axis] {
+            true => (ref2, ref1),
+            false => (ref1, ref2),
+        };
+        let (unscaled1, unscaled2) = (self.unscaled[ref1][axis], self.unscaled[ref2][axis]);
+        let (original1, original2) = (self.original[ref1][axis], self.original[ref2][axis]);
+        let (current1, current2) = (self.current[ref1][axis], self.current[ref2][axis]);
+        let delta1 = current1.wrapping_sub(original1);
+        let delta2 = current2.wrapping_sub(original2);
+        let scale = match current1 == current2 || unscaled1 == unscaled2 {
+            true => None,
+            false => Some(div_fix(
+                current2.wrapping_sub(current1),
+                unscaled2.wrapping_sub(unscaled1),
+            )),
+        };
+        for i in first..=last {
+            let original = self.original[i][axis];
+            self.current[i][axis] = if original <= original1 {
+                original.wrapping_add(delta1)
+            } else if original >= original2 {
+                original.wrapping_add(delta2)
+            } else {
+                match scale {
+                    Some(scale) => current1.wrapping_add(mul_fix(
+                        self.unscaled[i][axis].wrapping_sub(unscaled1),
+                        scale,
+                    )),
+                    None => current1,
+                }
+            };
+        }
+    }
+}
@@ -1,9 +1,14 @@
 use crate::io::error::IOError;
 
-pub trait ReadFrom<R> {
+pub trait ReadFrom<R: ?Sized> {
     fn read_from(reader: &mut R) -> Result<Self, IOError> where Self: Sized;
 }
 
-pub trait WriteTo<W> {
+/// 需要额外参数才能读取的结构，如依赖表长度或其他表中字段的表
+pub trait ReadWith<R: ?Sized, A> {
+    fn read_with(reader: &mut R, args: A) -> Result<Self, IOError> where Self: Sized;
+}
+
+pub trait WriteTo<W: ?Sized> {
     fn write_to(&self, writer: &mut W) -> Result<usize, IOError>;
 }
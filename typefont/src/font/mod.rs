@@ -1,8 +1,14 @@
 use bit_struct::u24;
 use crate::types::Tag as TagStruct;
 
+pub mod encoding;
+pub mod font_ref;
+pub mod hinting;
 pub mod io;
 pub mod open_type;
+pub mod outline;
+pub mod raster;
+pub mod svg;
 pub mod table;
 pub mod table_record;
 pub mod true_type;
@@ -52,3 +58,6 @@ type Offset32 = u32;
 
 // u16.u16
 type Version16Dot16 = u32;
+
+/// 字形索引，`0` 为 `.notdef`
+pub type GlyphId = u16;
@@ -1,9 +1,48 @@
-use super::table_record::TableRecord;
+use super::table_record::{ChecksumMismatch, ChecksumReport, TableRecord, calc_table_checksum};
+use super::{GlyphId, Tag};
 use crate::font::io::ReadFrom;
+use crate::font::outline::{OutlinePen, draw_contours};
 use crate::font::table::Table;
-use crate::io::error::IOError;
+use crate::font::table::cbdt::CBDT;
+use crate::font::table::cff::CFF;
+use crate::font::table::cff2::CFF2;
+use crate::font::table::cmap::{Cmap, VariationResult};
+use crate::font::table::cvt::Cvt;
+use crate::font::table::dsig::DSIG;
+use crate::font::table::fpgm::Fpgm;
+use crate::font::table::gasp::Gasp;
+use crate::font::table::glyf::{DEFAULT_MAX_COMPONENT_DEPTH, Glyf, Glyph, GlyphPoint};
+use crate::font::table::head::Head;
+use crate::font::table::hhea::Hhea;
+use crate::font::table::hmtx::Hmtx;
+use crate::font::table::loca::Loca;
+use crate::font::table::ltsh::LTSH;
+use crate::font::table::maxp::Maxp;
+use crate::font::table::name::Name;
+use crate::font::table::os2::Os2;
+use crate::font::table::pclt::PCLT;
+use crate::font::table::post::Post;
+use crate::font::table::prep::Prep;
+use crate::font::table::vhea::Vhea;
+use crate::font::table::vorg::VORG;
+use crate::io::error::{ErrorContext, IOError};
+#[cfg(not(feature = "mmap"))]
 use crate::io::file_reader::FileReader;
-use crate::io::reader::{Reader, ReaderBoxed};
+#[cfg(feature = "mmap")]
+use crate::io::mmap_reader::MmapReader;
+use crate::io::reader::ReaderBoxed;
+use crate::io::slice_reader::SliceReader;
+use std::cell::{OnceCell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::vec_writer::VecWriter;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Patch, Write, Writer};
+#[cfg(feature = "dsig")]
+use crate::font::table::dsig::SignatureCoverage;
 
 const SFNT_TTF: u32 = 0x00010000;
 const SFNT_OTF: u32 = 0x4F54544F;
@@ -141,16 +180,34 @@ pub struct TableDirectory {
 /// 然而，一个集合文件可以包括一个甚至多个可变字体，
 /// 并且可以结合可变字体和非可变字体。
 ///
+/// 表在首次访问时才读取并缓存，打开字体时只读取表目录。
 pub struct OpenType {
     table_directory: TableDirectory,
-    tables: Vec<Table>,
+    /// 表目录在文件中的偏移量，单独的字体为 `0`
+    directory_offset: usize,
+    /// 字体集合中的字体共享同一个读取器
+    reader: Rc<RefCell<ReaderBoxed<'static>>>,
+    /// 与 `table_directory.table_records` 一一对应
+    tables: Vec<OnceCell<Rc<Table>>>,
+    cache: TableCache,
+    /// 由 [OpenType::set_table] 替换或添加的表，优先于文件中的表
+    overrides: HashMap<Tag, Table>,
 }
 
-impl ReadFrom<ReaderBoxed> for TableDirectory {
+/// 已读取的表，键为表标签、偏移量、长度以及依赖的表的偏移量。
+/// 字体集合中的字体共享同一个缓存，因此指向同一数据的表只读取一次。
+pub(crate) type TableCache = Rc<RefCell<HashMap<Vec<u32>, Rc<Table>>>>;
+
+impl ReadFrom<ReaderBoxed<'_>> for TableDirectory {
     fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
         let sfnt = reader.read_u32()?;
         if sfnt != SFNT_TTF && sfnt != SFNT_OTF {
-            return Err(IOError::UnableCast);
+            return Err(IOError::unexpected(
+                "sfntVersion",
+                format!("0x{:08X} or 0x{:08X}", SFNT_TTF, SFNT_OTF),
+                format!("0x{:08X}", sfnt),
+                0,
+            ));
         }
         let num_tables = reader.read_u16()?;
         let search_range = reader.read_u16()?;
@@ -173,23 +230,578 @@ impl ReadFrom<ReaderBoxed> for TableDirectory {
     }
 }
 
-impl ReadFrom<ReaderBoxed> for OpenType {
-    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
-        let table_directory = TableDirectory::read_from(reader)?;
-        // TODO
+impl TableDirectory {
+    /// 创建表目录，表记录按标签升序排列，二分查找参数由表数量推导
+    ///
+    /// 表记录超过 `u16::MAX` 个时返回错误，超过 4095 个时 `search_range` 和 `range_shift`
+    /// 超出 `u16` 的范围，取 `u16::MAX`。
+    pub fn new(sfnt_version: u32, mut table_records: Vec<TableRecord>) -> Result<Self, IOError> {
+        table_records.sort_by(|a, b| a.tag().cmp(b.tag()));
+        Self::with_records(sfnt_version, table_records)
+    }
+
+    /// 与 [TableDirectory::new] 相同，但保持表记录的顺序
+    pub(crate) fn with_records(
+        sfnt_version: u32,
+        table_records: Vec<TableRecord>,
+    ) -> Result<Self, IOError> {
+        let num_tables = u16::try_from(table_records.len()).map_err(|_| {
+            IOError::UnableOperate(format!(
+                "{} tables exceed the limit of {}",
+                table_records.len(),
+                u16::MAX
+            ))
+        })?;
+        let entry_selector = match num_tables {
+            0 => 0,
+            n => 15 - n.leading_zeros() as u16,
+        };
+        let search_range = (1u32 << entry_selector) * 16;
+        let range_shift = (num_tables as u32 * 16).saturating_sub(search_range);
+        Ok(Self {
+            sfnt_version,
+            num_tables,
+            search_range: search_range.min(u16::MAX as u32) as u16,
+            entry_selector,
+            range_shift: range_shift.min(u16::MAX as u32) as u16,
+            table_records,
+        })
+    }
+
+    pub fn sfnt_version(&self) -> u32 {
+        self.sfnt_version
+    }
+
+    pub fn num_tables(&self) -> u16 {
+        self.num_tables
+    }
+
+    pub fn search_range(&self) -> u16 {
+        self.search_range
+    }
+
+    pub fn entry_selector(&self) -> u16 {
+        self.entry_selector
+    }
+
+    pub fn range_shift(&self) -> u16 {
+        self.range_shift
+    }
+
+    pub fn table_records(&self) -> &Vec<TableRecord> {
+        &self.table_records
+    }
+}
+
+impl TableDirectory {
+    /// 二分查找 `tag` 对应的表记录的索引
+    ///
+    /// 表记录应按标签升序排列，不符合规范的字体退化为顺序查找
+    pub fn find(&self, tag: &Tag) -> Option<usize> {
+        match self.table_records.binary_search_by(|r| r.tag().cmp(tag)) {
+            Ok(i) => Some(i),
+            Err(_) => self.table_records.iter().position(|r| r.tag() == tag),
+        }
+    }
+}
+
+macro_rules! table_getter {
+    ($($fun:ident($tag:literal) -> $variant:ident($t:ty)),* $(,)?) => {
+        $(
+        /// 表不存在或暂不支持解析时返回 `None`
+        pub fn $fun(&self) -> Result<Option<&$t>, IOError> {
+            match self.table(&Tag::from(*$tag))? {
+                Some(Table::$variant(table)) => Ok(Some(table)),
+                _ => Ok(None),
+            }
+        }
+        )*
+    };
+}
+
+impl OpenType {
+    /// 读取表目录，表在访问时才读取
+    pub fn new(reader: ReaderBoxed<'static>) -> Result<Self, IOError> {
+        Self::read_at(Rc::new(RefCell::new(reader)), 0, TableCache::default())
+    }
+
+    /// 读取位于 `directory_offset` 的表目录，用于字体集合中的字体
+    pub(crate) fn read_at(
+        reader: Rc<RefCell<ReaderBoxed<'static>>>,
+        directory_offset: usize,
+        cache: TableCache,
+    ) -> Result<Self, IOError> {
+        let table_directory = {
+            let mut reader = reader.borrow_mut();
+            reader.seek(directory_offset)?;
+            TableDirectory::read_from(&mut reader)
+                .at(reader.position() - directory_offset)
+                .context("tableDirectory", directory_offset)?
+        };
+        let tables = table_directory
+            .table_records
+            .iter()
+            .map(|_| OnceCell::new())
+            .collect();
         Ok(Self {
             table_directory,
-            tables: vec![],
+            directory_offset,
+            reader,
+            tables,
+            cache,
+            overrides: HashMap::new(),
         })
     }
+
+    /// 从内存数据读取，如 `Vec<u8>`、`Arc<[u8]>`、`&'static [u8]`
+    pub fn from_bytes<B: AsRef<[u8]> + 'static>(bytes: B) -> Result<Self, IOError> {
+        Self::new(Box::new(SliceReader::new(bytes)))
+    }
+
+    pub fn table_directory(&self) -> &TableDirectory {
+        &self.table_directory
+    }
+
+    /// 是否为字体集合中的字体，集合中的字体不使用 `head.checksum_adjustment`
+    pub fn in_collection(&self) -> bool {
+        self.directory_offset != 0
+    }
+
+    /// 获取 `tag` 对应的表，首次访问时读取并缓存
+    pub fn table(&self, tag: &Tag) -> Result<Option<&Table>, IOError> {
+        match self.table_directory.find(tag) {
+            Some(index) => self.table_at(index).map(Some),
+            None => Ok(self.overrides.get(tag)),
+        }
+    }
+
+    /// 替换 `tag` 对应的表，字体中没有该表时添加
+    ///
+    /// 之后的 [OpenType::table] 和写入都使用新的表，表记录在写入时重新生成。
+    /// 表目录、[OpenType::table_bytes] 和 [OpenType::verify_checksums] 仍然对应文件中的原始数据，
+    /// 已经读取的依赖该表的表（见 [Table::dependencies]）不会重新读取。
+    pub fn set_table(&mut self, tag: Tag, table: Table) {
+        self.overrides.insert(tag, table);
+    }
+
+    /// 读取所有表，按表目录顺序排列，不包括 [OpenType::set_table] 添加的表
+    pub fn tables(&self) -> Result<Vec<&Table>, IOError> {
+        (0..self.tables.len()).map(|i| self.table_at(i)).collect()
+    }
+
+    /// 读取 `tag` 对应的表的原始数据，不经过解析和缓存
+    pub fn table_bytes(&self, tag: &Tag) -> Result<Option<Vec<u8>>, IOError> {
+        match self.table_directory.find(tag) {
+            Some(index) => self
+                .record_bytes(&self.table_directory.table_records[index])
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn record_bytes(&self, record: &TableRecord) -> Result<Vec<u8>, IOError> {
+        let offset = record.offset() as usize;
+        let length = record.length() as usize;
+        let mut reader = self.reader.borrow_mut();
+        reader
+            .sub_reader(offset, length)
+            .and_then(|mut reader| reader.read_bytes_expected(length))
+            .in_table(record.tag(), offset)
+    }
+
+    /// 使用 [Cmap::best_unicode_subtable] 查找字符对应的字形索引
+    ///
+    /// 字体没有 `cmap` 表或 `cmap` 表无法读取时返回 `None`，读取错误可以通过 [OpenType::cmap] 获取
+    pub fn glyph_index(&self, c: char) -> Option<GlyphId> {
+        self.cmap().ok().flatten()?.glyph_index(c)
+    }
+
+    /// 查找变体序列 <`base`, `selector`> 对应的字形，见 [Cmap::glyph_index_for_variation]
+    ///
+    /// 字体没有 `cmap` 表或 `cmap` 表无法读取时返回 [VariationResult::NotFound]
+    pub fn glyph_index_for_variation(&self, base: char, selector: char) -> VariationResult {
+        match self.cmap() {
+            Ok(Some(cmap)) => cmap.glyph_index_for_variation(base, selector),
+            _ => VariationResult::NotFound,
+        }
+    }
+
+    /// `glyf` 表中的字形，字体没有 `glyf` 表或字形没有轮廓时返回 `None`，见 [Glyf::glyph]
+    pub fn glyph(&self, glyph_id: GlyphId) -> Result<Option<&Glyph>, IOError> {
+        match self.glyf()? {
+            Some(glyf) => glyf.glyph(glyph_id),
+            None => Ok(None),
+        }
+    }
+
+    /// 展开复合字形后的轮廓，嵌套层数以 `maxp.max_component_depth` 为限，见 [Glyf::outline]
+    ///
+    /// 没有版本 1.0 的 `maxp` 表时以 [DEFAULT_MAX_COMPONENT_DEPTH] 为限，字体没有 `glyf` 表时返回 `None`
+    pub fn glyph_outline(
+        &self,
+        glyph_id: GlyphId,
+    ) -> Result<Option<Vec<Vec<GlyphPoint>>>, IOError> {
+        let Some(glyf) = self.glyf()? else {
+            return Ok(None);
+        };
+        let max_depth = self
+            .maxp()?
+            .and_then(Maxp::as_v1_0)
+            .map_or(DEFAULT_MAX_COMPONENT_DEPTH, |maxp| {
+                maxp.max_component_depth()
+            });
+        glyf.outline(glyph_id, max_depth).map(Some)
+    }
+
+    /// 把字形轮廓交给 `pen`，轮廓依次取自 `glyf`、`CFF ` 或 `CFF2` 表
+    ///
+    /// `glyf` 轮廓为二次曲线，`CFF` 轮廓为三次曲线，需要统一为三次曲线时使用 [CubicPen](crate::font::outline::CubicPen)。
+    /// 字体没有轮廓表时返回 [IOError::UnableOperate]。
+    pub fn draw_glyph(&self, glyph_id: GlyphId, pen: &mut dyn OutlinePen) -> Result<(), IOError> {
+        if let Some(contours) = self.glyph_outline(glyph_id)? {
+            draw_contours(&contours, pen);
+            return Ok(());
+        }
+        if let Some(cff) = self.cff()? {
+            return cff.draw(glyph_id, pen);
+        }
+        match self.cff2()? {
+            Some(cff2) => cff2.draw(glyph_id, pen),
+            None => Err(IOError::UnableOperate(
+                "font has no glyf, CFF or CFF2 table".to_string(),
+            )),
+        }
+    }
+
+    /// 校验每个表的校验和以及 `head.checksum_adjustment`
+    ///
+    /// 计算 `head` 表的校验和时 `checksum_adjustment` 视为 0，
+    /// 整个字体的校验和由表目录和各表的计算值求和得到，与表在文件中的排列无关。
+    /// 字体集合中的字体不校验 `checksum_adjustment`。
+    pub fn verify_checksums(&self) -> Result<ChecksumReport, IOError> {
+        let directory_len = 12 + 16 * self.table_directory.table_records.len();
+        let directory = {
+            let mut reader = self.reader.borrow_mut();
+            reader.seek(self.directory_offset)?;
+            reader.read_bytes_expected(directory_len)?
+        };
+        let mut sum = calc_table_checksum(&directory);
+        let mut mismatches = vec![];
+        let mut checksum_adjustment = None;
+        for record in &self.table_directory.table_records {
+            let mut bytes = self.record_bytes(record)?;
+            if record.tag().to_bytes() == *b"head" && bytes.len() >= 12 {
+                let recorded = u32::from_be_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+                checksum_adjustment = Some(recorded);
+                bytes[8..12].fill(0);
+            }
+            let computed = calc_table_checksum(&bytes);
+            if computed != record.checksum() {
+                mismatches.push(ChecksumMismatch::new(
+                    record.tag().clone(),
+                    record.checksum(),
+                    computed,
+                ));
+            }
+            sum = sum.wrapping_add(computed);
+        }
+        let checksum_adjustment = match self.in_collection() {
+            true => None,
+            false => checksum_adjustment.map(|recorded| (recorded, 0xB1B0AFBAu32.wrapping_sub(sum))),
+        };
+        Ok(ChecksumReport::new(mismatches, checksum_adjustment))
+    }
+
+    fn table_at(&self, index: usize) -> Result<&Table, IOError> {
+        let record = &self.table_directory.table_records[index];
+        if let Some(table) = self.overrides.get(record.tag()) {
+            return Ok(table);
+        }
+        if let Some(table) = self.tables[index].get() {
+            return Ok(table);
+        }
+        let mut key = vec![
+            u32::from_be_bytes(record.tag().to_bytes()),
+            record.offset(),
+            record.length(),
+        ];
+
+        // 依赖的表需要在借用 `reader` 之前读取
+        let mut dependencies = vec![];
+        for &tag in Table::dependencies(record.tag()) {
+            let tag = Tag::from(*tag);
+            if let Some(table) = self.table(&tag)? {
+                dependencies.push(table);
+            }
+            if let Some(index) = self.table_directory.find(&tag) {
+                key.push(self.table_directory.table_records[index].offset());
+            }
+        }
+        // 依赖替换后的表读取的结果只属于这个字体，不放入共享的缓存
+        let shared = Table::dependencies(record.tag())
+            .iter()
+            .all(|&tag| !self.overrides.contains_key(&Tag::from(*tag)));
+
+        let cached = match shared {
+            true => self.cache.borrow().get(&key).cloned(),
+            false => None,
+        };
+        let table = match cached {
+            Some(table) => table,
+            None => {
+                let mut reader = self.reader.borrow_mut();
+                let offset = record.offset() as usize;
+                let table = reader
+                    .sub_reader(offset, record.length() as usize)
+                    .and_then(|mut reader| {
+                        let table = Table::read(&mut reader, record, &dependencies);
+                        table.at(reader.position())
+                    })
+                    .in_table(record.tag(), offset)?;
+                let table = Rc::new(table);
+                if shared {
+                    self.cache.borrow_mut().insert(key, table.clone());
+                }
+                table
+            }
+        };
+        Ok(self.tables[index].get_or_init(|| table))
+    }
+
+    table_getter!(
+        cbdt(b"CBDT") -> CBDT(CBDT),
+        cff(b"CFF ") -> CFF(CFF),
+        cff2(b"CFF2") -> CFF2(CFF2),
+        cmap(b"cmap") -> Cmap(Cmap),
+        cvt(b"cvt ") -> Cvt(Cvt),
+        dsig(b"DSIG") -> DSIG(DSIG),
+        fpgm(b"fpgm") -> Fpgm(Fpgm),
+        gasp(b"gasp") -> Gasp(Gasp),
+        glyf(b"glyf") -> Glyf(Glyf),
+        head(b"head") -> Head(Head),
+        hhea(b"hhea") -> Hhea(Hhea),
+        hmtx(b"hmtx") -> Hmtx(Hmtx),
+        loca(b"loca") -> Loca(Loca),
+        ltsh(b"LTSH") -> LTSH(LTSH),
+        maxp(b"maxp") -> Maxp(Maxp),
+        name(b"name") -> Name(Name),
+        os2(b"OS/2") -> OS2(Os2),
+        pclt(b"PCLT") -> PCLT(PCLT),
+        post(b"post") -> Post(Post),
+        prep(b"prep") -> Prep(Prep),
+        vhea(b"vhea") -> Vhea(Vhea),
+        vorg(b"VORG") -> VORG(VORG),
+    );
 }
 
-pub fn read_font(path: &str) -> Result<OpenType, IOError> {
-    let mut reader: ReaderBoxed = Box::new(match FileReader::open(path) {
-        Ok(reader) => reader,
-        Err(e) => {
-            return Err(IOError::UnableOperate(e.to_string()));
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for TableDirectory {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(self.sfnt_version)?;
+        writer.write(self.num_tables)?;
+        writer.write(self.search_range)?;
+        writer.write(self.entry_selector)?;
+        writer.write(self.range_shift)?;
+        for record in &self.table_records {
+            record.write_to(writer)?;
+        }
+        Ok(writer.position() - start)
+    }
+}
+
+/// 序列化后的表，按标签排序
+#[cfg(feature = "writer")]
+pub(crate) type SerializedTables = Vec<(Tag, Vec<u8>)>;
+
+#[cfg(feature = "writer")]
+impl OpenType {
+    /// 序列化所有表，包括 [OpenType::set_table] 替换和添加的表，按标签排序，
+    /// `head.checksum_adjustment` 置为 0
+    ///
+    /// 无法解析的表使用文件中的原始数据，不影响其余表的写入。
+    pub(crate) fn serialize_tables(&self) -> Result<SerializedTables, IOError> {
+        let serialize = |table: &Table, tag: &Tag, offset: usize| {
+            let mut data = VecWriter::new();
+            table.write_to(&mut data).in_table(tag, offset)?;
+            Ok::<_, IOError>(data.into_inner())
+        };
+        let mut tables = vec![];
+        for (index, record) in self.table_directory.table_records.iter().enumerate() {
+            let bytes = match self.table_at(index) {
+                Ok(table) => serialize(table, record.tag(), record.offset() as usize)?,
+                Err(_) => self.record_bytes(record)?,
+            };
+            tables.push((record.tag().clone(), bytes));
+        }
+        for (tag, table) in &self.overrides {
+            if self.table_directory.find(tag).is_none() {
+                tables.push((tag.clone(), serialize(table, tag, 0)?));
+            }
         }
-    });
-    OpenType::read_from(&mut reader)
+        for (tag, bytes) in &mut tables {
+            // 计算校验和时 `checksum_adjustment` 视为 0
+            if tag.to_bytes() == *b"head" && bytes.len() >= 12 {
+                bytes[8..12].fill(0);
+            }
+        }
+        tables.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(tables)
+    }
+}
+
+/// 写入完整的字体，读取所有表后重新生成表目录，无法解析的表按原始数据写入：
+/// 表记录按标签排序，表按 4 字节对齐并以 0 填充，
+/// 重新计算每个表的校验和，最后回填 `head.checksum_adjustment`。
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for OpenType {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        self.write_tables(self.serialize_tables()?, writer)
+    }
+}
+
+#[cfg(feature = "writer")]
+impl OpenType {
+    /// 写入去除 `DSIG` 表的字体，其余与 [WriteTo::write_to] 相同
+    pub fn strip_dsig(&self, writer: &mut dyn Writer) -> Result<usize, IOError> {
+        let tables = self
+            .serialize_tables()?
+            .into_iter()
+            .filter(|(tag, _)| tag.to_bytes() != *b"DSIG")
+            .collect();
+        self.write_tables(tables, writer)
+    }
+
+    /// 检查 `DSIG` 中的签名摘要是否与去除 `DSIG` 后的字体数据一致
+    ///
+    /// 摘要按文件中的原始数据计算：只去除 `DSIG` 表的数据和表记录，之后的数据前移，
+    /// 其余表记录的顺序、表数据和 `head.checksum_adjustment` 保持不变，
+    /// 因此与重新生成表目录的 [OpenType::strip_dsig] 不同，不要求表能原样写回。
+    ///
+    /// 字体集合中的字体不单独签名，返回错误，见 `Ttc::dsig_coverage`。
+    #[cfg(feature = "dsig")]
+    pub fn dsig_coverage(&self) -> Result<SignatureCoverage, IOError> {
+        let dsig = match self.dsig()? {
+            Some(dsig) => dsig,
+            None => return Ok(SignatureCoverage::Unsigned),
+        };
+        if self.in_collection() {
+            return Err(IOError::UnableOperate(
+                "DSIG of a font in a collection is not used".to_string(),
+            ));
+        }
+        Ok(dsig.coverage(&self.bytes_without_dsig()?))
+    }
+
+    /// 去除 `DSIG` 表的数据和表记录后的原始文件数据，见 [OpenType::dsig_coverage]
+    #[cfg(feature = "dsig")]
+    fn bytes_without_dsig(&self) -> Result<Vec<u8>, IOError> {
+        let bytes = {
+            let mut reader = self.reader.borrow_mut();
+            let size = reader.size();
+            reader.seek(0)?;
+            reader.read_bytes_expected(size)?
+        };
+        let records = &self.table_directory.table_records;
+        let directory_len = 12 + 16 * records.len();
+        let (dsig_start, dsig_end) = records
+            .iter()
+            .find(|record| record.tag().to_bytes() == *b"DSIG")
+            .map_or((bytes.len(), bytes.len()), |record| {
+                let start = (record.offset() as usize).min(bytes.len());
+                let end = start + (record.length() as usize).next_multiple_of(4);
+                (start, end.min(bytes.len()))
+            });
+        if dsig_start < directory_len || directory_len > bytes.len() {
+            return Err(IOError::unexpected(
+                "DSIG/offset",
+                format!(">= 0x{:X}", directory_len),
+                format!("0x{:X}", dsig_start),
+                0,
+            ));
+        }
+        // 表记录少了 16 字节，位于 `DSIG` 之后的表再前移 `DSIG` 的长度
+        let shift = |offset: u32| {
+            let removed = match offset as usize >= dsig_end {
+                true => 16 + dsig_end - dsig_start,
+                false => 16,
+            };
+            offset.saturating_sub(removed as u32)
+        };
+        let records = records
+            .iter()
+            .filter(|record| record.tag().to_bytes() != *b"DSIG")
+            .map(|record| {
+                TableRecord::new(
+                    record.tag().clone(),
+                    record.checksum(),
+                    shift(record.offset()),
+                    record.length(),
+                )
+            })
+            .collect();
+        let directory = TableDirectory::with_records(self.table_directory.sfnt_version, records)?;
+        let mut writer = VecWriter::new();
+        directory.write_to(&mut writer)?;
+        let mut data = writer.into_inner();
+        data.extend_from_slice(&bytes[directory_len..dsig_start]);
+        data.extend_from_slice(&bytes[dsig_end..]);
+        Ok(data)
+    }
+
+    fn write_tables(
+        &self,
+        tables: SerializedTables,
+        writer: &mut dyn Writer,
+    ) -> Result<usize, IOError> {
+        let start = writer.position();
+        let mut offset = start + 12 + 16 * tables.len();
+        let mut records = Vec::with_capacity(tables.len());
+        for (tag, bytes) in &tables {
+            records.push(TableRecord::new(
+                tag.clone(),
+                calc_table_checksum(bytes),
+                offset as u32,
+                bytes.len() as u32,
+            ));
+            offset += bytes.len().next_multiple_of(4);
+        }
+        let directory = TableDirectory::new(self.table_directory.sfnt_version, records)?;
+        let mut data = VecWriter::new();
+        directory.write_to(&mut data)?;
+        let mut sum = calc_table_checksum(data.data());
+        writer.write(data.data())?;
+
+        let mut head_offset = None;
+        for ((tag, bytes), record) in tables.iter().zip(&directory.table_records) {
+            if tag.to_bytes() == *b"head" {
+                head_offset = Some(writer.position());
+            }
+            writer.write(bytes)?;
+            writer.write(vec![0u8; bytes.len().next_multiple_of(4) - bytes.len()])?;
+            sum = sum.wrapping_add(record.checksum());
+        }
+        if let Some(head_offset) = head_offset {
+            writer.patch_at(head_offset + 8, 0xB1B0AFBAu32.wrapping_sub(sum))?;
+        }
+        Ok(writer.position() - start)
+    }
+}
+
+/// 打开字体文件，启用 `mmap` 特性时通过内存映射读取
+pub(crate) fn open_file(path: &str) -> Result<ReaderBoxed<'static>, IOError> {
+    #[cfg(feature = "mmap")]
+    let reader = MmapReader::open(path);
+    #[cfg(not(feature = "mmap"))]
+    let reader = FileReader::open(path);
+
+    match reader {
+        Ok(reader) => Ok(Box::new(reader)),
+        Err(e) => Err(IOError::UnableOperate(e.to_string())),
+    }
+}
+
+/// 读取字体文件，启用 `mmap` 特性时通过内存映射读取
+pub fn read_font(path: &str) -> Result<OpenType, IOError> {
+    OpenType::new(open_file(path)?)
 }
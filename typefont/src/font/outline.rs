@@ -0,0 +1,375 @@
+use crate::font::table::glyf::GlyphPoint;
+
+/// 接收字形轮廓的画笔
+///
+/// 每条轮廓以 [OutlinePen::move_to] 开始、以 [OutlinePen::close] 结束，
+/// `close` 隐含一条回到起点的直线。坐标为字体设计单位，`y` 轴向上。
+pub trait OutlinePen {
+    fn move_to(&mut self, x: f32, y: f32);
+
+    fn line_to(&mut self, x: f32, y: f32);
+
+    /// 二次贝塞尔曲线，(`cx`, `cy`) 为控制点
+    fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32);
+
+    /// 三次贝塞尔曲线，(`c1x`, `c1y`)、(`c2x`, `c2y`) 为控制点
+    fn curve_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32);
+
+    fn close(&mut self);
+}
+
+/// 绘制 `TrueType` 轮廓
+///
+/// 相邻的两个曲线外点之间隐含一个位于中点的曲线上点。
+/// 全部由曲线外点组成的轮廓从最后一个点与第一个点的中点开始。
+pub fn draw_contours(contours: &[Vec<GlyphPoint>], pen: &mut dyn OutlinePen) {
+    let point = |p: &GlyphPoint| ((p.x() as f32, p.y() as f32), p.on_curve());
+    for contour in contours {
+        draw_contour(contour, point, pen);
+    }
+}
+
+/// 绘制一条 `TrueType` 轮廓，`point` 返回点的坐标以及是否在曲线上
+pub(crate) fn draw_contour<P>(
+    contour: &[P],
+    point: impl Fn(&P) -> ((f32, f32), bool),
+    pen: &mut dyn OutlinePen,
+) {
+    let mid = |a: (f32, f32), b: (f32, f32)| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    let Some(last) = contour.last() else {
+        return;
+    };
+    // 从第一个曲线上点开始，绕回到它之前的点
+    let first = contour.iter().position(|p| point(p).1);
+    let (start, skip) = match first {
+        Some(i) => (point(&contour[i]).0, i + 1),
+        None => (mid(point(last).0, point(&contour[0]).0), 0),
+    };
+    let rest = contour[skip..].iter().chain(&contour[..first.unwrap_or(0)]);
+    pen.move_to(start.0, start.1);
+    let mut control: Option<(f32, f32)> = None;
+    for p in rest {
+        let (p_xy, on_curve) = point(p);
+        match (control, on_curve) {
+            (None, true) => pen.line_to(p_xy.0, p_xy.1),
+            (None, false) => control = Some(p_xy),
+            (Some(c), true) => {
+                pen.quad_to(c.0, c.1, p_xy.0, p_xy.1);
+                control = None;
+            }
+            (Some(c), false) => {
+                let m = mid(c, p_xy);
+                pen.quad_to(c.0, c.1, m.0, m.1);
+                control = Some(p_xy);
+            }
+        }
+    }
+    if let Some(c) = control {
+        pen.quad_to(c.0, c.1, start.0, start.1);
+    }
+    pen.close();
+}
+
+/// 轮廓的包围盒
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    x_min: f32,
+    y_min: f32,
+    x_max: f32,
+    y_max: f32,
+}
+
+impl Bounds {
+    pub fn new(x_min: f32, y_min: f32, x_max: f32, y_max: f32) -> Self {
+        Self {
+            x_min,
+            y_min,
+            x_max,
+            y_max,
+        }
+    }
+
+    pub fn x_min(&self) -> f32 {
+        self.x_min
+    }
+
+    pub fn y_min(&self) -> f32 {
+        self.y_min
+    }
+
+    pub fn x_max(&self) -> f32 {
+        self.x_max
+    }
+
+    pub fn y_max(&self) -> f32 {
+        self.y_max
+    }
+
+    fn add(bounds: &mut Option<Bounds>, x: f32, y: f32) {
+        *bounds = Some(match *bounds {
+            None => Bounds::new(x, y, x, y),
+            Some(b) => Bounds::new(
+                b.x_min.min(x),
+                b.y_min.min(y),
+                b.x_max.max(x),
+                b.y_max.max(y),
+            ),
+        });
+    }
+}
+
+/// 计算包含所有点（包括控制点）的包围盒
+#[derive(Default)]
+pub struct ControlBoundsPen {
+    bounds: Option<Bounds>,
+}
+
+impl ControlBoundsPen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 没有绘制任何点时为 `None`
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+}
+
+impl OutlinePen for ControlBoundsPen {
+    fn move_to(&mut self, x: f32, y: f32) {
+        Bounds::add(&mut self.bounds, x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        Bounds::add(&mut self.bounds, x, y);
+    }
+
+    fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        Bounds::add(&mut self.bounds, cx, cy);
+        Bounds::add(&mut self.bounds, x, y);
+    }
+
+    fn curve_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
+        Bounds::add(&mut self.bounds, c1x, c1y);
+        Bounds::add(&mut self.bounds, c2x, c2y);
+        Bounds::add(&mut self.bounds, x, y);
+    }
+
+    fn close(&mut self) {}
+}
+
+/// 计算轮廓的精确包围盒，曲线只计入端点和极值点
+#[derive(Default)]
+pub struct BoundsPen {
+    bounds: Option<Bounds>,
+    current: (f32, f32),
+}
+
+impl BoundsPen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 没有绘制任何点时为 `None`
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+}
+
+/// 二次曲线在一个坐标轴上导数为 0 的参数
+fn quad_extremum(p0: f32, p1: f32, p2: f32) -> Option<f32> {
+    let denominator = p0 - 2.0 * p1 + p2;
+    let t = (p0 - p1) / denominator;
+    (denominator != 0.0 && t > 0.0 && t < 1.0).then_some(t)
+}
+
+/// 三次曲线在一个坐标轴上导数为 0 的参数
+fn cubic_extrema(p0: f32, p1: f32, p2: f32, p3: f32) -> Vec<f32> {
+    let a = -p0 + 3.0 * p1 - 3.0 * p2 + p3;
+    let b = 2.0 * (p0 - 2.0 * p1 + p2);
+    let c = p1 - p0;
+    let roots = if a.abs() < f32::EPSILON {
+        match b.abs() < f32::EPSILON {
+            true => vec![],
+            false => vec![-c / b],
+        }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        match discriminant {
+            d if d < 0.0 => vec![],
+            d => {
+                let sqrt = d.sqrt();
+                vec![(-b + sqrt) / (2.0 * a), (-b - sqrt) / (2.0 * a)]
+            }
+        }
+    };
+    roots.into_iter().filter(|&t| t > 0.0 && t < 1.0).collect()
+}
+
+impl OutlinePen for BoundsPen {
+    fn move_to(&mut self, x: f32, y: f32) {
+        Bounds::add(&mut self.bounds, x, y);
+        self.current = (x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        Bounds::add(&mut self.bounds, x, y);
+        self.current = (x, y);
+    }
+
+    fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        let (x0, y0) = self.current;
+        let at = |t: f32, p0: f32, p1: f32, p2: f32| {
+            let s = 1.0 - t;
+            s * s * p0 + 2.0 * s * t * p1 + t * t * p2
+        };
+        let ts = [quad_extremum(x0, cx, x), quad_extremum(y0, cy, y)];
+        for t in ts.into_iter().flatten() {
+            Bounds::add(&mut self.bounds, at(t, x0, cx, x), at(t, y0, cy, y));
+        }
+        Bounds::add(&mut self.bounds, x, y);
+        self.current = (x, y);
+    }
+
+    fn curve_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
+        let (x0, y0) = self.current;
+        let at = |t: f32, p0: f32, p1: f32, p2: f32, p3: f32| {
+            let s = 1.0 - t;
+            s * s * s * p0 + 3.0 * s * s * t * p1 + 3.0 * s * t * t * p2 + t * t * t * p3
+        };
+        let mut ts = cubic_extrema(x0, c1x, c2x, x);
+        ts.extend(cubic_extrema(y0, c1y, c2y, y));
+        for t in ts {
+            Bounds::add(
+                &mut self.bounds,
+                at(t, x0, c1x, c2x, x),
+                at(t, y0, c1y, c2y, y),
+            );
+        }
+        Bounds::add(&mut self.bounds, x, y);
+        self.current = (x, y);
+    }
+
+    fn close(&mut self) {}
+}
+
+/// 记录的绘制命令
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    QuadTo(f32, f32, f32, f32),
+    CurveTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+/// 记录绘制命令，可以重放到其他画笔
+#[derive(Default)]
+pub struct RecordingPen {
+    commands: Vec<PathCommand>,
+}
+
+impl RecordingPen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn commands(&self) -> &Vec<PathCommand> {
+        &self.commands
+    }
+
+    pub fn into_commands(self) -> Vec<PathCommand> {
+        self.commands
+    }
+
+    /// 按记录的顺序把命令发送给 `pen`
+    pub fn replay(&self, pen: &mut dyn OutlinePen) {
+        for &command in &self.commands {
+            match command {
+                PathCommand::MoveTo(x, y) => pen.move_to(x, y),
+                PathCommand::LineTo(x, y) => pen.line_to(x, y),
+                PathCommand::QuadTo(cx, cy, x, y) => pen.quad_to(cx, cy, x, y),
+                PathCommand::CurveTo(c1x, c1y, c2x, c2y, x, y) => {
+                    pen.curve_to(c1x, c1y, c2x, c2y, x, y)
+                }
+                PathCommand::Close => pen.close(),
+            }
+        }
+    }
+}
+
+impl OutlinePen for RecordingPen {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.commands.push(PathCommand::MoveTo(x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.commands.push(PathCommand::LineTo(x, y));
+    }
+
+    fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        self.commands.push(PathCommand::QuadTo(cx, cy, x, y));
+    }
+
+    fn curve_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
+        self.commands
+            .push(PathCommand::CurveTo(c1x, c1y, c2x, c2y, x, y));
+    }
+
+    fn close(&mut self) {
+        self.commands.push(PathCommand::Close);
+    }
+}
+
+/// 把二次曲线转换为等价的三次曲线后交给内部画笔，其余命令原样转发
+pub struct CubicPen<P> {
+    pen: P,
+    current: (f32, f32),
+}
+
+impl<P: OutlinePen> CubicPen<P> {
+    pub fn new(pen: P) -> Self {
+        Self {
+            pen,
+            current: (0.0, 0.0),
+        }
+    }
+
+    pub fn into_inner(self) -> P {
+        self.pen
+    }
+}
+
+impl<P: OutlinePen> OutlinePen for CubicPen<P> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.pen.move_to(x, y);
+        self.current = (x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.pen.line_to(x, y);
+        self.current = (x, y);
+    }
+
+    fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        let (x0, y0) = self.current;
+        self.pen.curve_to(
+            x0 + 2.0 / 3.0 * (cx - x0),
+            y0 + 2.0 / 3.0 * (cy - y0),
+            x + 2.0 / 3.0 * (cx - x),
+            y + 2.0 / 3.0 * (cy - y),
+            x,
+            y,
+        );
+        self.current = (x, y);
+    }
+
+    fn curve_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
+        self.pen.curve_to(c1x, c1y, c2x, c2y, x, y);
+        self.current = (x, y);
+    }
+
+    fn close(&mut self) {
+        self.pen.close();
+    }
+}
@@ -0,0 +1,436 @@
+use crate::font::GlyphId;
+use crate::font::hinting::Hinter;
+use crate::font::open_type::OpenType;
+use crate::font::outline::OutlinePen;
+use crate::io::error::IOError;
+
+/// 仿射变换，`x' = xx * x + xy * y + dx`，`y' = yx * x + yy * y + dy`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    xx: f32,
+    yx: f32,
+    xy: f32,
+    yy: f32,
+    dx: f32,
+    dy: f32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform {
+    pub fn new(xx: f32, yx: f32, xy: f32, yy: f32, dx: f32, dy: f32) -> Self {
+        Self {
+            xx,
+            yx,
+            xy,
+            yy,
+            dx,
+            dy,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::scale(1.0, 1.0)
+    }
+
+    pub fn scale(sx: f32, sy: f32) -> Self {
+        Self::new(sx, 0.0, 0.0, sy, 0.0, 0.0)
+    }
+
+    pub fn translate(dx: f32, dy: f32) -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, dx, dy)
+    }
+
+    /// 先应用 `self` 再应用 `next` 的变换
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform {
+            xx: next.xx * self.xx + next.xy * self.yx,
+            yx: next.yx * self.xx + next.yy * self.yx,
+            xy: next.xx * self.xy + next.xy * self.yy,
+            yy: next.yx * self.xy + next.yy * self.yy,
+            dx: next.xx * self.dx + next.xy * self.dy + next.dx,
+            dy: next.yx * self.dx + next.yy * self.dy + next.dy,
+        }
+    }
+
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.xx * x + self.xy * y + self.dx,
+            self.yx * x + self.yy * y + self.dy,
+        )
+    }
+}
+
+/// 光栅化选项
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RasterOptions {
+    /// 亚像素偏移量，单位为像素，`y` 轴向上
+    offset: (f32, f32),
+    /// 缩放到 `ppem` 之后应用的变换，单位为像素，`y` 轴向上
+    transform: Transform,
+}
+
+impl RasterOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_offset(mut self, dx: f32, dy: f32) -> Self {
+        self.offset = (dx, dy);
+        self
+    }
+
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    pub fn offset(&self) -> (f32, f32) {
+        self.offset
+    }
+
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+}
+
+/// 位图最多包含的像素数，超出时光栅化返回错误
+pub const MAX_BITMAP_PIXELS: usize = 1 << 26;
+
+/// 曲线最多细分的段数，足以覆盖 [MAX_BITMAP_PIXELS] 以内的位图
+const MAX_SEGMENTS: usize = 256;
+
+/// 8 位覆盖率位图，逐行从上到下存储
+pub struct Bitmap {
+    width: usize,
+    height: usize,
+    /// 位图左边缘相对于字形原点的像素数，向右为正
+    left: i32,
+    /// 位图上边缘相对于基线的像素数，向上为正
+    top: i32,
+    data: Vec<u8>,
+}
+
+impl Bitmap {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn left(&self) -> i32 {
+        self.left
+    }
+
+    pub fn top(&self) -> i32 {
+        self.top
+    }
+
+    pub fn data(&self) -> &Vec<u8> {
+        &self.data
+    }
+
+    /// 第 `y` 行第 `x` 列的覆盖率，超出范围时为 0
+    pub fn pixel(&self, x: usize, y: usize) -> u8 {
+        match x < self.width && y < self.height {
+            true => self.data[y * self.width + x],
+            false => 0,
+        }
+    }
+}
+
+/// 把轮廓转换为抗锯齿位图的画笔
+///
+/// 曲线在设备空间中细分为线段，每条线段按其在像素中扫过的带符号面积累加，
+/// 逐行累加后取绝对值并截断到 1 作为覆盖率。同向重叠的轮廓按非零环绕规则填充。
+pub struct Rasterizer {
+    /// 字体设计单位到像素的变换，`y` 轴向上
+    transform: Transform,
+    lines: Vec<((f32, f32), (f32, f32))>,
+    current: (f32, f32),
+    start: (f32, f32),
+}
+
+impl Rasterizer {
+    pub fn new(transform: Transform) -> Self {
+        Self {
+            transform,
+            lines: vec![],
+            current: (0.0, 0.0),
+            start: (0.0, 0.0),
+        }
+    }
+
+    fn push_line(&mut self, to: (f32, f32)) {
+        if self.current != to {
+            self.lines.push((self.current, to));
+        }
+        self.current = to;
+    }
+
+    /// 输出位图，大小为所有线段的像素包围盒
+    ///
+    /// 坐标不是有限值或位图超过 [MAX_BITMAP_PIXELS] 个像素时返回错误。
+    pub fn into_bitmap(mut self) -> Result<Bitmap, IOError> {
+        // 未闭合的轮廓视为闭合
+        self.push_line(self.start);
+        let points = self.lines.iter().flat_map(|&(p0, p1)| [p0, p1]);
+        let (mut x_min, mut y_min) = (f32::MAX, f32::MAX);
+        let (mut x_max, mut y_max) = (f32::MIN, f32::MIN);
+        for (x, y) in points {
+            if !x.is_finite() || !y.is_finite() {
+                return Err(IOError::UnableOperate(format!(
+                    "outline point ({}, {}) is not finite",
+                    x, y
+                )));
+            }
+            (x_min, y_min) = (x_min.min(x), y_min.min(y));
+            (x_max, y_max) = (x_max.max(x), y_max.max(y));
+        }
+        if self.lines.is_empty() {
+            return Ok(Bitmap {
+                width: 0,
+                height: 0,
+                left: 0,
+                top: 0,
+                data: vec![],
+            });
+        }
+        let left = pixel_edge(x_min.floor())?;
+        let top = pixel_edge(y_max.ceil())?;
+        let width = pixel_edge(x_max.ceil())?.checked_sub(left);
+        let height = top.checked_sub(pixel_edge(y_min.floor())?);
+        let (width, height) = match (width, height) {
+            (Some(width), Some(height)) => (width as usize, height as usize),
+            _ => return Err(too_large(x_max - x_min, y_max - y_min)),
+        };
+        if width
+            .checked_mul(height)
+            .is_none_or(|pixels| pixels > MAX_BITMAP_PIXELS)
+        {
+            return Err(too_large(x_max - x_min, y_max - y_min));
+        }
+
+        let mut accumulation = vec![0f32; width * height + 4];
+        for &((x0, y0), (x1, y1)) in &self.lines {
+            // 位图坐标，`y` 轴向下
+            let p0 = (x0 - left as f32, top as f32 - y0);
+            let p1 = (x1 - left as f32, top as f32 - y1);
+            accumulate_line(&mut accumulation, width, height, p0, p1);
+        }
+        let mut coverage = 0f32;
+        let data = accumulation[..width * height]
+            .iter()
+            .map(|&area| {
+                coverage += area;
+                (coverage.abs().min(1.0) * 255.0).round() as u8
+            })
+            .collect();
+        Ok(Bitmap {
+            width,
+            height,
+            left,
+            top,
+            data,
+        })
+    }
+}
+
+/// 把取整后的像素坐标转换为整数
+fn pixel_edge(value: f32) -> Result<i32, IOError> {
+    match value >= i32::MIN as f32 && value < i32::MAX as f32 {
+        true => Ok(value as i32),
+        false => Err(IOError::UnableOperate(format!(
+            "bitmap edge {} is out of range",
+            value
+        ))),
+    }
+}
+
+fn too_large(width: f32, height: f32) -> IOError {
+    IOError::UnableOperate(format!(
+        "bitmap of {}x{} pixels exceeds {} pixels",
+        width.ceil(),
+        height.ceil(),
+        MAX_BITMAP_PIXELS
+    ))
+}
+
+/// 累加线段在每个像素中的带符号面积，`buffer` 按行连续存储，行末溢出的部分计入下一行开头
+fn accumulate_line(
+    buffer: &mut [f32],
+    width: usize,
+    height: usize,
+    p0: (f32, f32),
+    p1: (f32, f32),
+) {
+    if p0.1 == p1.1 {
+        return;
+    }
+    let (direction, p0, p1) = match p0.1 < p1.1 {
+        true => (1.0, p0, p1),
+        false => (-1.0, p1, p0),
+    };
+    let dxdy = (p1.0 - p0.0) / (p1.1 - p0.1);
+    let mut x = p0.0;
+    let y_start = p0.1.max(0.0) as usize;
+    if p0.1 < 0.0 {
+        x -= p0.1 * dxdy;
+    }
+    let y_end = (p1.1.ceil() as usize).min(height);
+    for y in y_start..y_end {
+        let row = y * width;
+        let dy = ((y + 1) as f32).min(p1.1) - (y as f32).max(p0.1);
+        let x_next = x + dxdy * dy;
+        let d = dy * direction;
+        let (x0, x1) = match x < x_next {
+            true => (x, x_next),
+            false => (x_next, x),
+        };
+        let x0_floor = x0.floor();
+        let x0i = x0_floor.max(0.0) as usize;
+        let x1_ceil = x1.ceil();
+        let x1i = x1_ceil.max(0.0) as usize;
+        if x1i <= x0i + 1 {
+            // 线段在一个像素内，按与像素左边缘的平均距离分配
+            let mid = 0.5 * (x + x_next) - x0_floor;
+            buffer[row + x0i] += d - d * mid;
+            buffer[row + x0i + 1] += d * mid;
+        } else {
+            let s = (x1 - x0).recip();
+            let x0_fraction = x0 - x0_floor;
+            let a0 = 0.5 * s * (1.0 - x0_fraction) * (1.0 - x0_fraction);
+            let x1_fraction = x1 - x1_ceil + 1.0;
+            let am = 0.5 * s * x1_fraction * x1_fraction;
+            buffer[row + x0i] += d * a0;
+            if x1i == x0i + 2 {
+                buffer[row + x0i + 1] += d * (1.0 - a0 - am);
+            } else {
+                let a1 = s * (1.5 - x0_fraction);
+                buffer[row + x0i + 1] += d * (a1 - a0);
+                for xi in x0i + 2..x1i - 1 {
+                    buffer[row + xi] += d * s;
+                }
+                let a2 = a1 + (x1i - x0i - 3) as f32 * s;
+                buffer[row + x1i - 1] += d * (1.0 - a2 - am);
+            }
+            buffer[row + x1i] += d * am;
+        }
+        x = x_next;
+    }
+}
+
+/// 曲线细分的段数，`deviation` 为控制多边形偏离直线的程度（像素的平方）
+fn segments(deviation: f32) -> usize {
+    match deviation < 0.333 {
+        true => 1,
+        false => (1 + (3.0 * deviation).sqrt().sqrt().floor() as usize).min(MAX_SEGMENTS),
+    }
+}
+
+impl OutlinePen for Rasterizer {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.push_line(self.start);
+        self.current = self.transform.apply(x, y);
+        self.start = self.current;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let to = self.transform.apply(x, y);
+        self.push_line(to);
+    }
+
+    fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        let p0 = self.current;
+        let p1 = self.transform.apply(cx, cy);
+        let p2 = self.transform.apply(x, y);
+        let (ddx, ddy) = (p0.0 - 2.0 * p1.0 + p2.0, p0.1 - 2.0 * p1.1 + p2.1);
+        let n = segments(ddx * ddx + ddy * ddy);
+        for i in 1..=n {
+            let t = i as f32 / n as f32;
+            let s = 1.0 - t;
+            self.push_line((
+                s * s * p0.0 + 2.0 * s * t * p1.0 + t * t * p2.0,
+                s * s * p0.1 + 2.0 * s * t * p1.1 + t * t * p2.1,
+            ));
+        }
+    }
+
+    fn curve_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
+        let p0 = self.current;
+        let p1 = self.transform.apply(c1x, c1y);
+        let p2 = self.transform.apply(c2x, c2y);
+        let p3 = self.transform.apply(x, y);
+        let deviation = |a: (f32, f32), b: (f32, f32), c: (f32, f32)| {
+            let (dx, dy) = (a.0 - 2.0 * b.0 + c.0, a.1 - 2.0 * b.1 + c.1);
+            dx * dx + dy * dy
+        };
+        let n = segments(deviation(p0, p1, p2).max(deviation(p1, p2, p3)));
+        for i in 1..=n {
+            let t = i as f32 / n as f32;
+            let s = 1.0 - t;
+            let (a, b, c, d) = (s * s * s, 3.0 * s * s * t, 3.0 * s * t * t, t * t * t);
+            self.push_line((
+                a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+                a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+            ));
+        }
+    }
+
+    fn close(&mut self) {
+        self.push_line(self.start);
+    }
+}
+
+/// 以 `ppem`（每 em 的像素数）光栅化字形
+///
+/// 轮廓先按 `ppem / head.units_per_em` 缩放，再依次应用选项中的变换和亚像素偏移量。
+/// `ppem` 必须是正的有限值，位图大小的限制见 [Rasterizer::into_bitmap]。
+pub fn rasterize_glyph(
+    font: &OpenType,
+    glyph_id: GlyphId,
+    ppem: f32,
+    options: &RasterOptions,
+) -> Result<Bitmap, IOError> {
+    let head = font
+        .head()?
+        .ok_or_else(|| IOError::UnableOperate("font has no head table".to_string()))?;
+    if !ppem.is_finite() || ppem <= 0.0 {
+        return Err(IOError::UnableOperate(format!(
+            "ppem must be positive and finite, found {}",
+            ppem
+        )));
+    }
+    if head.units_per_em() == 0 {
+        return Err(IOError::UnableOperate("head.unitsPerEm is 0".to_string()));
+    }
+    let scale = ppem / head.units_per_em() as f32;
+    let transform = Transform::scale(scale, scale)
+        .then(&options.transform)
+        .then(&Transform::translate(options.offset.0, options.offset.1));
+    let mut rasterizer = Rasterizer::new(transform);
+    font.draw_glyph(glyph_id, &mut rasterizer)?;
+    rasterizer.into_bitmap()
+}
+
+/// 光栅化网格拟合后的字形，`ppem` 由 `hinter` 决定
+///
+/// 选项中的变换和偏移量作用于以像素为单位的轮廓，非整数像素的偏移量会破坏网格对齐。
+pub fn rasterize_hinted_glyph(
+    hinter: &Hinter,
+    glyph_id: GlyphId,
+    options: &RasterOptions,
+) -> Result<Bitmap, IOError> {
+    let transform = options
+        .transform
+        .then(&Transform::translate(options.offset.0, options.offset.1));
+    let mut rasterizer = Rasterizer::new(transform);
+    hinter.hint_glyph(glyph_id)?.draw(&mut rasterizer);
+    rasterizer.into_bitmap()
+}
@@ -0,0 +1,251 @@
+use crate::font::GlyphId;
+use crate::font::open_type::OpenType;
+use crate::font::outline::OutlinePen;
+use crate::io::error::IOError;
+use std::fmt::Write;
+
+/// SVG 输出选项
+#[derive(Debug, Clone, Copy)]
+pub struct SvgOptions {
+    /// 坐标保留的小数位数，末尾的 0 会被去掉
+    precision: usize,
+    /// 使用小写的相对坐标命令
+    relative: bool,
+    /// 设置后输出以像素为单位的 `width` 和 `height`，按 `head.units_per_em` 缩放
+    font_size: Option<f32>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            precision: 2,
+            relative: false,
+            font_size: None,
+        }
+    }
+}
+
+impl SvgOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn with_relative(mut self, relative: bool) -> Self {
+        self.relative = relative;
+        self
+    }
+
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = Some(font_size);
+        self
+    }
+
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+
+    pub fn relative(&self) -> bool {
+        self.relative
+    }
+
+    pub fn font_size(&self) -> Option<f32> {
+        self.font_size
+    }
+
+    /// 按 `precision` 取整，与输出的数字一致
+    fn round(&self, value: f32) -> f32 {
+        format!("{:.*}", self.precision, value)
+            .parse()
+            .unwrap_or(value)
+    }
+
+    fn number(&self, value: f32) -> String {
+        let text = format!("{:.*}", self.precision, value);
+        let text = match text.contains('.') {
+            true => text.trim_end_matches('0').trim_end_matches('.'),
+            false => &text,
+        };
+        match text {
+            "-0" => "0".to_string(),
+            text => text.to_string(),
+        }
+    }
+}
+
+/// 生成 SVG 路径数据的画笔
+///
+/// `y` 轴翻转为向下，所有点先平移 (`dx`, `dy`)（字体设计单位，`y` 轴向上）。
+pub struct SvgPathPen {
+    options: SvgOptions,
+    dx: f32,
+    dy: f32,
+    data: String,
+    /// SVG 坐标系中的当前点和子路径起点，用于相对命令，
+    /// 已按精度取整，使相对坐标的累加与绝对坐标一致
+    current: (f32, f32),
+    start: (f32, f32),
+}
+
+impl SvgPathPen {
+    pub fn new(options: SvgOptions) -> Self {
+        Self::with_offset(options, 0.0, 0.0)
+    }
+
+    pub fn with_offset(options: SvgOptions, dx: f32, dy: f32) -> Self {
+        Self {
+            options,
+            dx,
+            dy,
+            data: String::new(),
+            current: (0.0, 0.0),
+            start: (0.0, 0.0),
+        }
+    }
+
+    /// `<path>` 的 `d` 属性值
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    pub fn into_data(self) -> String {
+        self.data
+    }
+
+    /// 写入命令，`points` 为字体坐标，最后一个点成为当前点
+    fn command(&mut self, command: char, points: &[(f32, f32)]) {
+        let options = self.options;
+        let points: Vec<_> = points
+            .iter()
+            .map(|&(x, y)| (x + self.dx, -(y + self.dy)))
+            .map(|(x, y)| match options.relative {
+                true => (options.round(x), options.round(y)),
+                false => (x, y),
+            })
+            .collect();
+        let (command, origin) = match self.options.relative {
+            true => (command.to_ascii_lowercase(), self.current),
+            false => (command, (0.0, 0.0)),
+        };
+        self.data.push(command);
+        for (i, &(x, y)) in points.iter().enumerate() {
+            if i > 0 {
+                self.data.push(' ');
+            }
+            let x = self.options.number(x - origin.0);
+            let y = self.options.number(y - origin.1);
+            let _ = write!(self.data, "{} {}", x, y);
+        }
+        if let Some(&last) = points.last() {
+            self.current = last;
+        }
+    }
+}
+
+impl OutlinePen for SvgPathPen {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.command('M', &[(x, y)]);
+        self.start = self.current;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.command('L', &[(x, y)]);
+    }
+
+    fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        self.command('Q', &[(cx, cy), (x, y)]);
+    }
+
+    fn curve_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
+        self.command('C', &[(c1x, c1y), (c2x, c2y), (x, y)]);
+    }
+
+    fn close(&mut self) {
+        self.data.push('Z');
+        self.current = self.start;
+    }
+}
+
+/// 字形的 SVG 路径数据，坐标为翻转 `y` 轴后的字体设计单位
+pub fn glyph_path(
+    font: &OpenType,
+    glyph_id: GlyphId,
+    options: &SvgOptions,
+) -> Result<String, IOError> {
+    let mut pen = SvgPathPen::new(*options);
+    font.draw_glyph(glyph_id, &mut pen)?;
+    Ok(pen.into_data())
+}
+
+/// 只包含一个字形的 SVG 文档，宽度为字形的前进宽度
+pub fn glyph_document(
+    font: &OpenType,
+    glyph_id: GlyphId,
+    options: &SvgOptions,
+) -> Result<String, IOError> {
+    glyphs_document(font, &[glyph_id], options)
+}
+
+/// 一行文本的 SVG 文档
+///
+/// 字符通过 `cmap` 映射为字形，没有映射的字符使用 `.notdef`，
+/// 字形按 `hmtx` 的前进宽度依次排列，不处理换行、字距和字形替换。
+pub fn text_document(font: &OpenType, text: &str, options: &SvgOptions) -> Result<String, IOError> {
+    let glyph_ids: Vec<_> = text
+        .chars()
+        .map(|c| font.glyph_index(c).unwrap_or(0))
+        .collect();
+    glyphs_document(font, &glyph_ids, options)
+}
+
+/// 依次排列字形的 SVG 文档
+///
+/// `viewBox` 的高度由 `hhea` 的 `ascender` 和 `descender` 决定，宽度为前进宽度之和。
+pub fn glyphs_document(
+    font: &OpenType,
+    glyph_ids: &[GlyphId],
+    options: &SvgOptions,
+) -> Result<String, IOError> {
+    let missing = |tag: &str| IOError::UnableOperate(format!("font has no {} table", tag));
+    let head = font.head()?.ok_or_else(|| missing("head"))?;
+    let hhea = font.hhea()?.ok_or_else(|| missing("hhea"))?;
+    let hmtx = font.hmtx()?.ok_or_else(|| missing("hmtx"))?;
+
+    let mut paths = String::new();
+    let mut x = 0f32;
+    for &glyph_id in glyph_ids {
+        let mut pen = SvgPathPen::with_offset(*options, x, 0.0);
+        font.draw_glyph(glyph_id, &mut pen)?;
+        if !pen.data().is_empty() {
+            let _ = writeln!(paths, "<path d=\"{}\"/>", pen.data());
+        }
+        x += hmtx.advance_width(glyph_id).unwrap_or(0) as f32;
+    }
+
+    let ascender = hhea.ascender() as f32;
+    let height = ascender - hhea.descender() as f32;
+    let n = |value| options.number(value);
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 {} {} {}\"",
+        n(-ascender),
+        n(x),
+        n(height)
+    );
+    if let Some(font_size) = options.font_size {
+        let scale = font_size / head.units_per_em() as f32;
+        let _ = write!(
+            out,
+            " width=\"{}\" height=\"{}\"",
+            n(x * scale),
+            n(height * scale)
+        );
+    }
+    out.push_str(">\n");
+    out.push_str(&paths);
+    out.push_str("</svg>\n");
+    Ok(out)
+}
@@ -1,4 +1,12 @@
+use super::glyph::GlyphBitmapDataRef;
+use crate::font::io::ReadWith;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 pub struct CBDT {
     header: CbdtHeader,
@@ -18,3 +26,80 @@ pub struct CbdtHeader {
     /// 0
     minor_version: u16,
 }
+
+impl CBDT {
+    pub fn major_version(&self) -> u16 {
+        self.header.major_version
+    }
+
+    pub fn minor_version(&self) -> u16 {
+        self.header.minor_version
+    }
+
+    /// 位图数据，`CBLC` 中的偏移量相对于表开头，即此数据前还有 4 字节表头
+    pub fn bmp_data(&self) -> &Vec<u8> {
+        &self.bmp_data
+    }
+}
+
+/// 参数为表长度
+impl ReadWith<ReaderBoxed<'_>, usize> for CBDT {
+    fn read_with(reader: &mut ReaderBoxed, length: usize) -> Result<Self, IOError> {
+        let header = CbdtHeader {
+            major_version: reader.read_u16()?,
+            minor_version: reader.read_u16()?,
+        };
+        Ok(Self {
+            header,
+            bmp_data: reader.read_bytes_expected(length.saturating_sub(4))?,
+        })
+    }
+}
+
+/// `CBDT` 表的借用视图
+#[derive(Clone, Copy)]
+pub struct CbdtRef<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> CbdtRef<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, IOError> {
+        if data.len() < 4 {
+            return Err(IOError::NoEnoughData);
+        }
+        Ok(Self { data })
+    }
+
+    pub fn major_version(&self) -> u16 {
+        u16::from_be_bytes([self.data[0], self.data[1]])
+    }
+
+    pub fn minor_version(&self) -> u16 {
+        u16::from_be_bytes([self.data[2], self.data[3]])
+    }
+
+    /// 位图数据，不含 4 字节表头
+    pub fn bmp_data(&self) -> &'a [u8] {
+        &self.data[4..]
+    }
+
+    /// 读取位图，`offset` 为相对于表开头的偏移量，
+    /// 即 `CBLC` 中的 `image_data_offset` 加上字形的偏移量
+    pub fn bitmap(&self, offset: usize, image_format: u16) -> Result<GlyphBitmapDataRef<'a>, IOError> {
+        match self.data.get(offset..) {
+            Some(data) => GlyphBitmapDataRef::read(data, image_format),
+            None => Err(IOError::NoEnoughData),
+        }
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for CBDT {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(self.header.major_version)?;
+        writer.write(self.header.minor_version)?;
+        writer.write(&self.bmp_data)?;
+        Ok(writer.position() - start)
+    }
+}
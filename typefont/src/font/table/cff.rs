@@ -1,6 +1,984 @@
+use crate::font::GlyphId;
+use crate::font::io::ReadWith;
+use crate::font::outline::OutlinePen;
 use crate::impl_named;
+use crate::io::error::{ErrorContext, IOError};
+use crate::io::reader::{Reader, ReaderBoxed};
+use crate::io::slice_reader::SliceReader;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
-/// 保留
-pub struct CFF {}
+/// # CFF — 紧凑字体格式（版本 1）
+///
+/// 字形由 `Type 2` 字形程序描述的三次贝塞尔曲线组成。
+/// 只解析绘制字形需要的结构，写入时原样写入原始数据。
+pub struct CFF {
+    major_version: u8,
+    minor_version: u8,
+    /// `Name INDEX` 中的字体名，`OpenType` 字体中只有一个
+    font_name: String,
+    global_subrs: Vec<Vec<u8>>,
+    char_strings: Vec<Vec<u8>>,
+    /// 非 CID 字体只有一个
+    private_dicts: Vec<PrivateDict>,
+    /// 每个字形使用的 `private_dicts` 索引，非 CID 字体为空
+    fd_select: Vec<u16>,
+    /// 每个字形的 SID（CID 字体中为 CID），预定义的 Expert 字符集为空
+    charset: Vec<u16>,
+    data: Vec<u8>,
+}
 
 impl_named!(CFF, "CFF");
+
+/// `Private DICT` 中绘制字形需要的值
+pub struct PrivateDict {
+    local_subrs: Vec<Vec<u8>>,
+    default_width_x: f64,
+    nominal_width_x: f64,
+    /// CFF2 中字形程序默认使用的 `ItemVariationData` 索引
+    vsindex: u16,
+}
+
+/// DICT 操作符，`12 x` 两字节操作符记为 `0x0C00 | x`
+pub(crate) mod dict_operator {
+    pub const CHARSET: u16 = 15;
+    pub const CHAR_STRINGS: u16 = 17;
+    pub const PRIVATE: u16 = 18;
+    pub const SUBRS: u16 = 19;
+    pub const DEFAULT_WIDTH_X: u16 = 20;
+    pub const NOMINAL_WIDTH_X: u16 = 21;
+    pub const VSINDEX: u16 = 22;
+    pub const VSTORE: u16 = 24;
+    pub const CHARSTRING_TYPE: u16 = 0x0C06;
+    pub const ROS: u16 = 0x0C1E;
+    pub const FD_ARRAY: u16 = 0x0C24;
+    pub const FD_SELECT: u16 = 0x0C25;
+}
+
+pub(crate) type Dict = Vec<(u16, Vec<f64>)>;
+
+/// `endchar` 带有的 `seac` 参数 `adx ady bchar achar`
+pub(crate) type Seac = [f64; 4];
+
+impl CFF {
+    pub fn major_version(&self) -> u8 {
+        self.major_version
+    }
+
+    pub fn minor_version(&self) -> u8 {
+        self.minor_version
+    }
+
+    pub fn font_name(&self) -> &str {
+        &self.font_name
+    }
+
+    /// 字形数量
+    pub fn len(&self) -> usize {
+        self.char_strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.char_strings.is_empty()
+    }
+
+    pub fn char_string(&self, glyph_id: GlyphId) -> Option<&[u8]> {
+        self.char_strings.get(glyph_id as usize).map(Vec::as_slice)
+    }
+
+    /// 字形使用的 `Private DICT`
+    pub fn private_dict(&self, glyph_id: GlyphId) -> Option<&PrivateDict> {
+        match self.fd_select.is_empty() {
+            true => self.private_dicts.first(),
+            false => self
+                .private_dicts
+                .get(*self.fd_select.get(glyph_id as usize)? as usize),
+        }
+    }
+
+    pub fn charset(&self) -> &Vec<u16> {
+        &self.charset
+    }
+
+    /// 执行字形程序，把轮廓交给 `pen`
+    ///
+    /// `endchar` 带有 `seac` 参数时组合重音字符：`bchar`、`achar` 为 `Standard Encoding` 代码，
+    /// 通过 `charset` 找到对应的字形，先画基本字符，再画平移 `(adx - sbx, ady)` 后的重音字符。
+    /// `Type 2` 字形程序没有 `sbx`，即平移 `(adx, ady)`。CID 字体不支持 `seac`。
+    ///
+    /// 不支持已废弃的算术运算操作符。
+    pub fn draw(&self, glyph_id: GlyphId, pen: &mut dyn OutlinePen) -> Result<(), IOError> {
+        let Some([adx, ady, bchar, achar]) = self.draw_at(glyph_id, (0.0, 0.0), pen)? else {
+            return Ok(());
+        };
+        let error = |e| IOError::UnableOperate(format!("seac of glyph {}: {}", glyph_id, e));
+        let base = self.standard_glyph(bchar).map_err(error)?;
+        let accent = self.standard_glyph(achar).map_err(error)?;
+        for (component, origin) in [(base, (0.0, 0.0)), (accent, (adx, ady))] {
+            if self.draw_at(component, origin, pen)?.is_some() {
+                return Err(error(format!("component {} uses seac", component)));
+            }
+        }
+        Ok(())
+    }
+
+    /// 从 `origin` 开始执行字形程序，返回 `endchar` 的 `seac` 参数
+    fn draw_at(
+        &self,
+        glyph_id: GlyphId,
+        (x, y): (f64, f64),
+        pen: &mut dyn OutlinePen,
+    ) -> Result<Option<Seac>, IOError> {
+        let (Some(char_string), Some(private)) =
+            (self.char_string(glyph_id), self.private_dict(glyph_id))
+        else {
+            return Err(IOError::UnableOperate(format!(
+                "glyph {} out of range 0..{}",
+                glyph_id,
+                self.len()
+            )));
+        };
+        CharStringDrawer::new(&self.global_subrs, private, None, pen)
+            .origin(x, y)
+            .draw(char_string)
+            .map_err(|e| IOError::UnableOperate(format!("charstring of glyph {}: {}", glyph_id, e)))
+    }
+
+    /// `Standard Encoding` 代码对应的字形
+    fn standard_glyph(&self, code: f64) -> Result<GlyphId, String> {
+        let sid = match (0.0..=255.0).contains(&code) && code.fract() == 0.0 {
+            true => standard_encoding_sid(code as u8),
+            false => 0,
+        };
+        self.charset
+            .iter()
+            .position(|&s| s == sid)
+            .filter(|_| sid != 0 && self.fd_select.is_empty())
+            .map(|glyph_id| glyph_id as GlyphId)
+            .ok_or_else(|| format!("character code {} not in charset", code))
+    }
+}
+
+/// `Standard Encoding` 中字符代码对应的 SID，未定义的代码为 0
+fn standard_encoding_sid(code: u8) -> u16 {
+    let code = code as u16;
+    match code {
+        32..=126 => code - 31,
+        161..=175 => code - 65,
+        177..=180 => code - 66,
+        182..=189 => code - 67,
+        191 => 123,
+        193..=200 => code - 69,
+        202 | 203 => code - 70,
+        205..=208 => code - 71,
+        225 => 138,
+        227 => 139,
+        232..=235 => code - 92,
+        241 => 144,
+        245 => 145,
+        248..=251 => code - 102,
+        _ => 0,
+    }
+}
+
+impl PrivateDict {
+    pub fn local_subrs(&self) -> &Vec<Vec<u8>> {
+        &self.local_subrs
+    }
+
+    pub fn default_width_x(&self) -> f64 {
+        self.default_width_x
+    }
+
+    pub fn nominal_width_x(&self) -> f64 {
+        self.nominal_width_x
+    }
+
+    pub fn vsindex(&self) -> u16 {
+        self.vsindex
+    }
+
+    /// 读取 `data` 中 `offset` 处长度为 `size` 的 `Private DICT` 及其 `Subrs`
+    pub(crate) fn read(
+        data: &[u8],
+        offset: usize,
+        size: usize,
+        region_counts: Option<&[u16]>,
+    ) -> Result<Self, IOError> {
+        let dict = offset
+            .checked_add(size)
+            .and_then(|end| data.get(offset..end))
+            .ok_or(IOError::NoEnoughData)
+            .and_then(|dict| parse_dict(dict, region_counts))
+            .at(0)
+            .context("privateDict", offset)?;
+        let local_subrs = match dict_get(&dict, dict_operator::SUBRS) {
+            Some(&[subrs]) => {
+                let Some(subrs) = offset.checked_add(subrs as usize) else {
+                    return Err(IOError::unexpected(
+                        "Subrs",
+                        "offset within the table",
+                        subrs,
+                        0,
+                    ))
+                    .context("privateDict", offset);
+                };
+                let mut reader = SliceReader::new(data);
+                reader.seek(subrs)?;
+                read_index(&mut reader, region_counts.map_or(2, |_| 4))
+                    .at(reader.position())
+                    .context("subrs", 0)?
+            }
+            _ => vec![],
+        };
+        let value = |operator| match dict_get(&dict, operator) {
+            Some([value]) => *value,
+            _ => 0.0,
+        };
+        Ok(Self {
+            local_subrs,
+            default_width_x: value(dict_operator::DEFAULT_WIDTH_X),
+            nominal_width_x: value(dict_operator::NOMINAL_WIDTH_X),
+            vsindex: value(dict_operator::VSINDEX) as u16,
+        })
+    }
+}
+
+/// 读取 INDEX，`count_size` 为 `count` 字段的字节数（CFF 为 2，CFF2 为 4）
+pub(crate) fn read_index(
+    reader: &mut SliceReader<&[u8]>,
+    count_size: usize,
+) -> Result<Vec<Vec<u8>>, IOError> {
+    let count = match count_size {
+        2 => reader.read_u16()? as usize,
+        _ => reader.read_u32()? as usize,
+    };
+    if count == 0 {
+        return Ok(vec![]);
+    }
+    let off_size_offset = reader.position();
+    let off_size = reader.read_u8()?;
+    if !(1..=4).contains(&off_size) {
+        return Err(IOError::unexpected(
+            "offSize",
+            "1..=4",
+            off_size,
+            off_size_offset,
+        ));
+    }
+    // `count` 未经检查，按剩余数据能容纳的偏移量个数分配
+    let mut offsets = Vec::with_capacity((count + 1).min(reader.remaining() / off_size as usize));
+    for i in 0..=count {
+        let offset = reader.position();
+        let value = match off_size {
+            1 => reader.read_u8()? as usize,
+            2 => reader.read_u16()? as usize,
+            3 => reader.read_u24()?.value() as usize,
+            _ => reader.read_u32()? as usize,
+        };
+        let previous = offsets.last().copied().unwrap_or(1);
+        if (i == 0 && value != 1) || value < previous {
+            return Err(IOError::unexpected(
+                &format!("offset[{}]", i),
+                format!(">= {}", previous),
+                value,
+                offset,
+            ));
+        }
+        offsets.push(value);
+    }
+    // 偏移量相对于数据开头之前的一个字节
+    let base = reader.position() - 1;
+    let mut objects = Vec::with_capacity(count);
+    for range in offsets.windows(2) {
+        reader.seek(base + range[0])?;
+        objects.push(reader.read_slice(range[1] - range[0])?.to_vec());
+    }
+    reader.seek(base + offsets[count])?;
+    Ok(objects)
+}
+
+/// 解析 DICT 数据
+///
+/// `region_counts` 为 CFF2 中每个 `ItemVariationData` 的区域数，
+/// 此时 `blend` 操作符只保留默认值，CFF 中为 `None`。
+pub(crate) fn parse_dict(data: &[u8], region_counts: Option<&[u16]>) -> Result<Dict, IOError> {
+    let mut reader = SliceReader::new(data);
+    read_dict(&mut reader, region_counts).at(reader.position())
+}
+
+fn read_dict(
+    reader: &mut SliceReader<&[u8]>,
+    region_counts: Option<&[u16]>,
+) -> Result<Dict, IOError> {
+    let mut entries = vec![];
+    let mut operands = vec![];
+    let mut vsindex = 0;
+    while !reader.eof() {
+        let offset = reader.position();
+        let b0 = reader.read_u8()?;
+        match b0 {
+            32..=246 => operands.push(b0 as f64 - 139.0),
+            247..=250 => {
+                operands.push((b0 as f64 - 247.0) * 256.0 + reader.read_u8()? as f64 + 108.0)
+            }
+            251..=254 => {
+                operands.push(-(b0 as f64 - 251.0) * 256.0 - reader.read_u8()? as f64 - 108.0)
+            }
+            28 => operands.push(reader.read_i16()? as f64),
+            29 => operands.push(reader.read_i32()? as f64),
+            30 => operands.push(
+                read_real(reader)
+                    .at(reader.position() - offset)
+                    .context("real", offset)?,
+            ),
+            // blend
+            23 if region_counts.is_some() => {
+                let region_count = region_counts
+                    .and_then(|counts| counts.get(vsindex as usize))
+                    .copied()
+                    .unwrap_or(0) as usize;
+                blend(&mut operands, region_count)
+                    .map_err(|e| IOError::unexpected("blend", "enough operands", e, offset))?;
+            }
+            0..=27 => {
+                let operator = match b0 {
+                    12 => 0x0C00 | reader.read_u8()? as u16,
+                    _ => b0 as u16,
+                };
+                if operator == dict_operator::VSINDEX
+                    && let Some(&index) = operands.first()
+                {
+                    vsindex = index as u16;
+                }
+                entries.push((operator, std::mem::take(&mut operands)));
+            }
+            _ => return Err(IOError::unexpected("operator", "0..=27", b0, offset)),
+        }
+    }
+    Ok(entries)
+}
+
+/// 实数，每 4 位表示一个数字或符号，`0xF` 结束
+fn read_real(reader: &mut SliceReader<&[u8]>) -> Result<f64, IOError> {
+    let mut text = String::new();
+    'bytes: loop {
+        let byte = reader.read_u8()?;
+        for nibble in [byte >> 4, byte & 0xF] {
+            match nibble {
+                0..=9 => text.push((b'0' + nibble) as char),
+                0xA => text.push('.'),
+                0xB => text.push('E'),
+                0xC => text.push_str("E-"),
+                0xE => text.push('-'),
+                0xF => break 'bytes,
+                _ => return Err(IOError::unexpected("nibble", "not 0xD", nibble, 0)),
+            }
+        }
+    }
+    text.parse()
+        .map_err(|_| IOError::unexpected("real", "number", text, 0))
+}
+
+/// 只保留 `blend` 的默认值，`operands` 末尾为值的数量 `n`，之前为 `n` 个默认值和 `n * region_count` 个增量
+pub(crate) fn blend<T: Copy + Into<f64>>(
+    operands: &mut Vec<T>,
+    region_count: usize,
+) -> Result<(), String> {
+    let n = operands
+        .pop()
+        .ok_or_else(|| "missing count".to_string())?
+        .into() as usize;
+    let total = n * (region_count + 1);
+    let Some(base) = operands.len().checked_sub(total) else {
+        return Err(format!("{} operands, found {}", total, operands.len()));
+    };
+    operands.truncate(base + n);
+    Ok(())
+}
+
+pub(crate) fn dict_get(dict: &Dict, operator: u16) -> Option<&[f64]> {
+    dict.iter()
+        .find(|(op, _)| *op == operator)
+        .map(|(_, operands)| operands.as_slice())
+}
+
+/// 读取 `FDSelect`，返回每个字形的 `Font DICT` 索引
+pub(crate) fn read_fd_select(
+    reader: &mut SliceReader<&[u8]>,
+    num_glyphs: usize,
+) -> Result<Vec<u16>, IOError> {
+    let format_offset = reader.position();
+    let format = reader.read_u8()?;
+    match format {
+        0 => (0..num_glyphs)
+            .map(|_| reader.read_u8().map(u16::from))
+            .collect(),
+        3 | 4 => {
+            let read_glyph = |reader: &mut SliceReader<&[u8]>| match format {
+                3 => reader.read_u16().map(|v| v as usize),
+                _ => reader.read_u32().map(|v| v as usize),
+            };
+            let n_ranges = read_glyph(reader)?;
+            let mut fd_select = Vec::with_capacity(num_glyphs);
+            let mut first = read_glyph(reader)?;
+            for i in 0..n_ranges {
+                let fd = match format {
+                    3 => reader.read_u8()? as u16,
+                    _ => reader.read_u16()?,
+                };
+                let offset = reader.position();
+                let next = read_glyph(reader)?;
+                if first != fd_select.len() || next <= first {
+                    return Err(IOError::unexpected(
+                        &format!("range[{}]", i),
+                        format!("> {}", first),
+                        next,
+                        offset,
+                    ));
+                }
+                fd_select.extend(std::iter::repeat_n(fd, next - first));
+                first = next;
+            }
+            fd_select.resize(num_glyphs, 0);
+            Ok(fd_select)
+        }
+        _ => Err(IOError::unexpected(
+            "format",
+            "0, 3 or 4",
+            format,
+            format_offset,
+        )),
+    }
+}
+
+/// 读取 `charset`，返回每个字形的 SID（CID 字体中为 CID），`.notdef` 为 0
+fn read_charset(reader: &mut SliceReader<&[u8]>, num_glyphs: usize) -> Result<Vec<u16>, IOError> {
+    let format_offset = reader.position();
+    let format = reader.read_u8()?;
+    if format > 2 {
+        return Err(IOError::unexpected(
+            "format",
+            "0, 1 or 2",
+            format,
+            format_offset,
+        ));
+    }
+    let mut charset = Vec::with_capacity(num_glyphs.min(reader.remaining() + 1));
+    charset.extend((num_glyphs > 0).then_some(0));
+    while charset.len() < num_glyphs {
+        match format {
+            0 => charset.push(reader.read_u16()?),
+            _ => {
+                let first = reader.read_u16()?;
+                let n_left = match format {
+                    1 => reader.read_u8()? as usize,
+                    _ => reader.read_u16()? as usize,
+                };
+                let count = (n_left + 1).min(num_glyphs - charset.len());
+                charset.extend((0..count as u16).map(|i| first.wrapping_add(i)));
+            }
+        }
+    }
+    Ok(charset)
+}
+
+/// `Type 2` 字形程序解释器
+///
+/// CFF2 中没有宽度、`endchar` 和 `return`，增加了 `blend` 和 `vsindex`。
+pub(crate) struct CharStringDrawer<'a> {
+    global_subrs: &'a [Vec<u8>],
+    private: &'a PrivateDict,
+    /// CFF2 中每个 `ItemVariationData` 的区域数，CFF 中为 `None`
+    region_counts: Option<&'a [u16]>,
+    pen: &'a mut dyn OutlinePen,
+    stack: Vec<f64>,
+    vsindex: u16,
+    x: f64,
+    y: f64,
+    stems: usize,
+    width_parsed: bool,
+    open: bool,
+    seac: Option<Seac>,
+}
+
+/// 子程序索引的偏移量
+fn subr_bias(count: usize) -> usize {
+    match count {
+        0..1240 => 107,
+        1240..33900 => 1131,
+        _ => 32768,
+    }
+}
+
+impl<'a> CharStringDrawer<'a> {
+    pub(crate) fn new(
+        global_subrs: &'a [Vec<u8>],
+        private: &'a PrivateDict,
+        region_counts: Option<&'a [u16]>,
+        pen: &'a mut dyn OutlinePen,
+    ) -> Self {
+        Self {
+            global_subrs,
+            private,
+            region_counts,
+            pen,
+            stack: vec![],
+            vsindex: private.vsindex,
+            x: 0.0,
+            y: 0.0,
+            stems: 0,
+            width_parsed: false,
+            open: false,
+            seac: None,
+        }
+    }
+
+    /// 从 `(x, y)` 而不是原点开始绘制
+    pub(crate) fn origin(mut self, x: f64, y: f64) -> Self {
+        (self.x, self.y) = (x, y);
+        self
+    }
+
+    /// 返回 `endchar` 的 `seac` 参数，由调用方组合重音字符
+    pub(crate) fn draw(mut self, char_string: &[u8]) -> Result<Option<Seac>, String> {
+        self.run(char_string, 0)?;
+        self.close_path();
+        Ok(self.seac)
+    }
+
+    fn cff2(&self) -> bool {
+        self.region_counts.is_some()
+    }
+
+    fn push(&mut self, value: f64) -> Result<(), String> {
+        let limit = match self.cff2() {
+            true => 513,
+            false => 48,
+        };
+        if self.stack.len() >= limit {
+            return Err(format!("stack overflow, limit {}", limit));
+        }
+        self.stack.push(value);
+        Ok(())
+    }
+
+    fn args(&self, n: usize) -> Result<(), String> {
+        match self.stack.len() >= n {
+            true => Ok(()),
+            false => Err(format!(
+                "expected {} operands, found {}",
+                n,
+                self.stack.len()
+            )),
+        }
+    }
+
+    /// 第一个清空栈的操作符可能带有宽度参数
+    fn width(&mut self, has_width: bool) {
+        if !self.width_parsed && !self.cff2() && has_width {
+            self.stack.remove(0);
+        }
+        self.width_parsed = true;
+    }
+
+    fn close_path(&mut self) {
+        if self.open {
+            self.pen.close();
+            self.open = false;
+        }
+    }
+
+    fn move_to(&mut self, dx: f64, dy: f64) {
+        self.close_path();
+        self.x += dx;
+        self.y += dy;
+        self.pen.move_to(self.x as f32, self.y as f32);
+        self.open = true;
+    }
+
+    /// 没有 `moveto` 时从当前点开始轮廓
+    fn ensure_open(&mut self) {
+        if !self.open {
+            self.pen.move_to(self.x as f32, self.y as f32);
+            self.open = true;
+        }
+    }
+
+    fn line_to(&mut self, dx: f64, dy: f64) {
+        self.ensure_open();
+        self.x += dx;
+        self.y += dy;
+        self.pen.line_to(self.x as f32, self.y as f32);
+    }
+
+    fn curve_to(&mut self, dx1: f64, dy1: f64, dx2: f64, dy2: f64, dx3: f64, dy3: f64) {
+        self.ensure_open();
+        let (x1, y1) = (self.x + dx1, self.y + dy1);
+        let (x2, y2) = (x1 + dx2, y1 + dy2);
+        (self.x, self.y) = (x2 + dx3, y2 + dy3);
+        self.pen.curve_to(
+            x1 as f32,
+            y1 as f32,
+            x2 as f32,
+            y2 as f32,
+            self.x as f32,
+            self.y as f32,
+        );
+    }
+
+    fn stem_hints(&mut self) {
+        self.width(self.stack.len() % 2 == 1);
+        self.stems += self.stack.len() / 2;
+    }
+
+    /// 执行字形程序或子程序，遇到 `endchar` 时返回 `true`
+    fn run(&mut self, code: &[u8], depth: usize) -> Result<bool, String> {
+        if depth > 10 {
+            return Err("subroutine nesting exceeds 10".to_string());
+        }
+        let mut reader = SliceReader::new(code);
+        let eof = |_| "unexpected end of charstring".to_string();
+        while !reader.eof() {
+            let b0 = reader.read_u8().map_err(eof)?;
+            match b0 {
+                32..=246 => self.push(b0 as f64 - 139.0)?,
+                247..=250 => {
+                    let b1 = reader.read_u8().map_err(eof)? as f64;
+                    self.push((b0 as f64 - 247.0) * 256.0 + b1 + 108.0)?
+                }
+                251..=254 => {
+                    let b1 = reader.read_u8().map_err(eof)? as f64;
+                    self.push(-(b0 as f64 - 251.0) * 256.0 - b1 - 108.0)?
+                }
+                28 => self.push(reader.read_i16().map_err(eof)? as f64)?,
+                255 => self.push(reader.read_i32().map_err(eof)? as f64 / 65536.0)?,
+                // hstem、vstem、hstemhm、vstemhm
+                1 | 3 | 18 | 23 => {
+                    self.stem_hints();
+                    self.stack.clear();
+                }
+                // hintmask、cntrmask，栈中的值为隐含的 vstem
+                19 | 20 => {
+                    self.stem_hints();
+                    self.stack.clear();
+                    reader.read_slice(self.stems.div_ceil(8)).map_err(eof)?;
+                }
+                // rmoveto
+                21 => {
+                    self.width(self.stack.len() > 2);
+                    self.args(2)?;
+                    self.move_to(self.stack[0], self.stack[1]);
+                    self.stack.clear();
+                }
+                // hmoveto
+                22 => {
+                    self.width(self.stack.len() > 1);
+                    self.args(1)?;
+                    self.move_to(self.stack[0], 0.0);
+                    self.stack.clear();
+                }
+                // vmoveto
+                4 => {
+                    self.width(self.stack.len() > 1);
+                    self.args(1)?;
+                    self.move_to(0.0, self.stack[0]);
+                    self.stack.clear();
+                }
+                // rlineto
+                5 => {
+                    let stack = std::mem::take(&mut self.stack);
+                    for d in stack.chunks_exact(2) {
+                        self.line_to(d[0], d[1]);
+                    }
+                }
+                // hlineto、vlineto
+                6 | 7 => {
+                    let stack = std::mem::take(&mut self.stack);
+                    for (i, &d) in stack.iter().enumerate() {
+                        match (i % 2 == 0) == (b0 == 6) {
+                            true => self.line_to(d, 0.0),
+                            false => self.line_to(0.0, d),
+                        }
+                    }
+                }
+                // rrcurveto
+                8 => {
+                    let stack = std::mem::take(&mut self.stack);
+                    for d in stack.chunks_exact(6) {
+                        self.curve_to(d[0], d[1], d[2], d[3], d[4], d[5]);
+                    }
+                }
+                // rcurveline
+                24 => {
+                    self.args(2)?;
+                    let stack = std::mem::take(&mut self.stack);
+                    let (curves, line) = stack.split_at(stack.len() - 2);
+                    for d in curves.chunks_exact(6) {
+                        self.curve_to(d[0], d[1], d[2], d[3], d[4], d[5]);
+                    }
+                    self.line_to(line[0], line[1]);
+                }
+                // rlinecurve
+                25 => {
+                    self.args(6)?;
+                    let stack = std::mem::take(&mut self.stack);
+                    let (lines, d) = stack.split_at(stack.len() - 6);
+                    for l in lines.chunks_exact(2) {
+                        self.line_to(l[0], l[1]);
+                    }
+                    self.curve_to(d[0], d[1], d[2], d[3], d[4], d[5]);
+                }
+                // vvcurveto、hhcurveto，奇数个参数时第一个为另一方向的增量
+                26 | 27 => {
+                    let stack = std::mem::take(&mut self.stack);
+                    let (mut first, rest) = match stack.len() % 2 {
+                        1 => (stack[0], &stack[1..]),
+                        _ => (0.0, &stack[..]),
+                    };
+                    for d in rest.chunks_exact(4) {
+                        match b0 {
+                            26 => self.curve_to(first, d[0], d[1], d[2], 0.0, d[3]),
+                            _ => self.curve_to(d[0], first, d[1], d[2], d[3], 0.0),
+                        }
+                        first = 0.0;
+                    }
+                }
+                // vhcurveto、hvcurveto，方向交替，最后一条曲线可以有额外的终点增量
+                30 | 31 => {
+                    let stack = std::mem::take(&mut self.stack);
+                    let mut horizontal = b0 == 31;
+                    let mut k = 0;
+                    while k + 4 <= stack.len() {
+                        let d = &stack[k..k + 4];
+                        let extra = match stack.len() - k {
+                            5 => stack[k + 4],
+                            _ => 0.0,
+                        };
+                        match horizontal {
+                            true => self.curve_to(d[0], 0.0, d[1], d[2], extra, d[3]),
+                            false => self.curve_to(0.0, d[0], d[1], d[2], d[3], extra),
+                        }
+                        horizontal = !horizontal;
+                        k += 4;
+                    }
+                }
+                // callsubr、callgsubr
+                10 | 29 => {
+                    self.args(1)?;
+                    let private: &'a PrivateDict = self.private;
+                    let subrs = match b0 {
+                        10 => private.local_subrs.as_slice(),
+                        _ => self.global_subrs,
+                    };
+                    let index =
+                        self.stack.pop().unwrap() as isize + subr_bias(subrs.len()) as isize;
+                    let Some(subr) = usize::try_from(index).ok().and_then(|i| subrs.get(i)) else {
+                        return Err(format!("subroutine {} out of range", index));
+                    };
+                    if self.run(subr, depth + 1)? {
+                        return Ok(true);
+                    }
+                }
+                // return
+                11 if !self.cff2() => return Ok(false),
+                // endchar
+                14 if !self.cff2() => {
+                    self.width(self.stack.len() == 1 || self.stack.len() == 5);
+                    if self.stack.len() >= 4 {
+                        self.seac = Some(self.stack[..4].try_into().unwrap());
+                    }
+                    self.close_path();
+                    return Ok(true);
+                }
+                // vsindex
+                15 if self.cff2() => {
+                    self.args(1)?;
+                    self.vsindex = self.stack[0] as u16;
+                    self.stack.clear();
+                }
+                // blend
+                16 if self.cff2() => {
+                    let region_count = self
+                        .region_counts
+                        .and_then(|counts| counts.get(self.vsindex as usize))
+                        .ok_or_else(|| format!("vsindex {} out of range", self.vsindex))?;
+                    blend(&mut self.stack, *region_count as usize)?;
+                }
+                12 => {
+                    let b1 = reader.read_u8().map_err(eof)?;
+                    self.flex(b1)?;
+                    self.stack.clear();
+                }
+                _ => return Err(format!("unsupported operator {}", b0)),
+            }
+        }
+        Ok(false)
+    }
+
+    /// `12 x` 操作符，只支持 `flex` 系列，已弃用的 `dotsection` 不做处理
+    fn flex(&mut self, b1: u8) -> Result<(), String> {
+        let s = &self.stack;
+        match b1 {
+            // dotsection
+            0 => {}
+            // flex
+            35 => {
+                self.args(13)?;
+                let d: [f64; 12] = s[..12].try_into().unwrap();
+                self.curve_to(d[0], d[1], d[2], d[3], d[4], d[5]);
+                self.curve_to(d[6], d[7], d[8], d[9], d[10], d[11]);
+            }
+            // hflex
+            34 => {
+                self.args(7)?;
+                let d: [f64; 7] = s[..7].try_into().unwrap();
+                self.curve_to(d[0], 0.0, d[1], d[2], d[3], 0.0);
+                self.curve_to(d[4], 0.0, d[5], -d[2], d[6], 0.0);
+            }
+            // hflex1
+            36 => {
+                self.args(9)?;
+                let d: [f64; 9] = s[..9].try_into().unwrap();
+                self.curve_to(d[0], d[1], d[2], d[3], d[4], 0.0);
+                self.curve_to(d[5], 0.0, d[6], d[7], d[8], -(d[1] + d[3] + d[7]));
+            }
+            // flex1，最后一个参数根据总增量较大的方向作为 x 或 y 增量
+            37 => {
+                self.args(11)?;
+                let d: [f64; 11] = s[..11].try_into().unwrap();
+                let dx: f64 = d[..10].iter().step_by(2).sum();
+                let dy: f64 = d[1..10].iter().step_by(2).sum();
+                self.curve_to(d[0], d[1], d[2], d[3], d[4], d[5]);
+                match dx.abs() > dy.abs() {
+                    true => self.curve_to(d[6], d[7], d[8], d[9], d[10], -dy),
+                    false => self.curve_to(d[6], d[7], d[8], d[9], -dx, d[10]),
+                }
+            }
+            _ => return Err(format!("unsupported operator 12 {}", b1)),
+        }
+        Ok(())
+    }
+}
+
+/// 参数为表长度
+impl ReadWith<ReaderBoxed<'_>, usize> for CFF {
+    fn read_with(reader: &mut ReaderBoxed, length: usize) -> Result<Self, IOError> {
+        let data = reader.read_bytes_expected(length)?;
+        let mut reader = SliceReader::new(data.as_slice());
+        let parsed = Self::parse(&data, &mut reader).at(reader.position())?;
+        Ok(Self { data, ..parsed })
+    }
+}
+
+impl CFF {
+    /// 从表数据 `data` 开头读取，返回值的 `data` 为空
+    fn parse(data: &[u8], reader: &mut SliceReader<&[u8]>) -> Result<Self, IOError> {
+        use dict_operator::*;
+
+        let major_version = reader.read_u8()?;
+        if major_version != 1 {
+            return Err(IOError::unexpected("major", 1, major_version, 0));
+        }
+        let minor_version = reader.read_u8()?;
+        let header_size = reader.read_u8()?;
+        reader.seek(header_size as usize)?;
+
+        let offset = reader.position();
+        let names = read_index(reader, 2).context("nameIndex", 0)?;
+        let Some(font_name) = names.first() else {
+            return Err(IOError::unexpected("nameIndex/count", ">= 1", 0, offset));
+        };
+        let font_name = String::from_utf8_lossy(font_name).into_owned();
+        let top_dicts = read_index(reader, 2).context("topDictIndex", 0)?;
+        read_index(reader, 2).context("stringIndex", 0)?;
+        let global_subrs = read_index(reader, 2).context("globalSubrIndex", 0)?;
+        let top_dict = top_dicts
+            .first()
+            .map(|dict| parse_dict(dict, None))
+            .transpose()
+            .context("topDict", 0)?
+            .unwrap_or_default();
+
+        if let Some(&[charstring_type]) = dict_get(&top_dict, CHARSTRING_TYPE)
+            && charstring_type != 2.0
+        {
+            return Err(IOError::unexpected(
+                "topDict/CharstringType",
+                2,
+                charstring_type,
+                0,
+            ));
+        }
+        let Some(&[char_strings]) = dict_get(&top_dict, CHAR_STRINGS) else {
+            return Err(IOError::unexpected(
+                "topDict/CharStrings",
+                "offset",
+                "none",
+                0,
+            ));
+        };
+        reader.seek(char_strings as usize)?;
+        let char_strings = read_index(reader, 2).context("charStrings", 0)?;
+
+        let read_private = |dict: &Dict| match dict_get(dict, PRIVATE) {
+            Some(&[size, offset]) => PrivateDict::read(data, offset as usize, size as usize, None),
+            _ => Err(IOError::unexpected("Private", "size and offset", "none", 0)),
+        };
+        let (private_dicts, fd_select) = match dict_get(&top_dict, ROS) {
+            // CID 字体的每个 `Font DICT` 有各自的 `Private DICT`
+            Some(_) => {
+                let (Some(&[fd_array]), Some(&[fd_select])) = (
+                    dict_get(&top_dict, FD_ARRAY),
+                    dict_get(&top_dict, FD_SELECT),
+                ) else {
+                    return Err(IOError::unexpected("topDict/FDArray", "offset", "none", 0));
+                };
+                reader.seek(fd_array as usize)?;
+                let mut private_dicts = vec![];
+                for (i, dict) in read_index(reader, 2)
+                    .context("fdArray", 0)?
+                    .iter()
+                    .enumerate()
+                {
+                    let dict = parse_dict(dict, None).and_then(|dict| read_private(&dict));
+                    private_dicts.push(dict.context(format!("fdArray[{}]", i), 0)?);
+                }
+                reader.seek(fd_select as usize)?;
+                let fd_select =
+                    read_fd_select(reader, char_strings.len()).context("fdSelect", 0)?;
+                (private_dicts, fd_select)
+            }
+            None => (vec![read_private(&top_dict).context("topDict", 0)?], vec![]),
+        };
+        let charset = match dict_get(&top_dict, CHARSET) {
+            // ISOAdobe 字符集中字形 ID 与 SID 相同，SID 最大为 228
+            None | Some(&[0.0]) => (0..char_strings.len().min(229) as u16).collect(),
+            Some(&[offset]) if offset > 2.0 => {
+                reader.seek(offset as usize)?;
+                read_charset(reader, char_strings.len()).context("charset", 0)?
+            }
+            _ => vec![],
+        };
+        Ok(Self {
+            major_version,
+            minor_version,
+            font_name,
+            global_subrs,
+            char_strings,
+            private_dicts,
+            fd_select,
+            charset,
+            data: vec![],
+        })
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for CFF {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        writer.write(&self.data)
+    }
+}
@@ -1,8 +1,34 @@
+use super::cff::{
+    CharStringDrawer, PrivateDict, dict_get, dict_operator, parse_dict, read_fd_select, read_index,
+};
+use crate::font::GlyphId;
+use crate::font::io::ReadWith;
+use crate::font::outline::OutlinePen;
 use crate::impl_named;
+use crate::io::error::{ErrorContext, IOError};
+use crate::io::reader::{Reader, ReaderBoxed};
+use crate::io::slice_reader::SliceReader;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
-// TODO
-
-pub struct CFF2 {}
+/// # CFF2 — 紧凑字体格式（版本 2）
+///
+/// 与 [CFF](super::cff::CFF) 相比去掉了宽度、字符串和字符集等数据，
+/// 字形程序可以通过 `blend` 按变体坐标插值。绘制时使用默认实例，
+/// 写入时原样写入原始数据。
+pub struct CFF2 {
+    header: CFF2Header,
+    global_subrs: Vec<Vec<u8>>,
+    char_strings: Vec<Vec<u8>>,
+    private_dicts: Vec<PrivateDict>,
+    /// 每个字形使用的 `private_dicts` 索引，只有一个 `Font DICT` 时可以为空
+    fd_select: Vec<u16>,
+    /// `VariationStore` 中每个 `ItemVariationData` 的区域数
+    region_counts: Vec<u16>,
+    data: Vec<u8>,
+}
 
 impl_named!(CFF2, "CFF2");
 
@@ -14,3 +40,198 @@ pub struct CFF2Header {
     header_size: u8,
     top_dict_size: u16,
 }
+
+impl CFF2 {
+    pub fn header(&self) -> &CFF2Header {
+        &self.header
+    }
+
+    /// 字形数量
+    pub fn len(&self) -> usize {
+        self.char_strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.char_strings.is_empty()
+    }
+
+    pub fn char_string(&self, glyph_id: GlyphId) -> Option<&[u8]> {
+        self.char_strings.get(glyph_id as usize).map(Vec::as_slice)
+    }
+
+    /// 字形使用的 `Private DICT`
+    pub fn private_dict(&self, glyph_id: GlyphId) -> Option<&PrivateDict> {
+        match self.fd_select.is_empty() {
+            true => self.private_dicts.first(),
+            false => self
+                .private_dicts
+                .get(*self.fd_select.get(glyph_id as usize)? as usize),
+        }
+    }
+
+    pub fn region_counts(&self) -> &Vec<u16> {
+        &self.region_counts
+    }
+
+    /// 执行字形程序，把默认实例的轮廓交给 `pen`
+    pub fn draw(&self, glyph_id: GlyphId, pen: &mut dyn OutlinePen) -> Result<(), IOError> {
+        let (Some(char_string), Some(private)) =
+            (self.char_string(glyph_id), self.private_dict(glyph_id))
+        else {
+            return Err(IOError::UnableOperate(format!(
+                "glyph {} out of range 0..{}",
+                glyph_id,
+                self.len()
+            )));
+        };
+        CharStringDrawer::new(&self.global_subrs, private, Some(&self.region_counts), pen)
+            .draw(char_string)
+            .map(|_| ())
+            .map_err(|e| IOError::UnableOperate(format!("charstring of glyph {}: {}", glyph_id, e)))
+    }
+}
+
+impl CFF2Header {
+    pub fn major_version(&self) -> u8 {
+        self.major_version
+    }
+
+    pub fn minor_version(&self) -> u8 {
+        self.minor_version
+    }
+
+    pub fn header_size(&self) -> u8 {
+        self.header_size
+    }
+
+    pub fn top_dict_size(&self) -> u16 {
+        self.top_dict_size
+    }
+}
+
+/// 读取 `VariationStore` 中每个 `ItemVariationData` 的区域数
+fn read_region_counts(reader: &mut SliceReader<&[u8]>) -> Result<Vec<u16>, IOError> {
+    // 前 2 字节为长度，之后是 `ItemVariationStore`
+    reader.read_u16()?;
+    let store = reader.position();
+    let format = reader.read_u16()?;
+    if format != 1 {
+        return Err(IOError::unexpected("format", 1, format, store));
+    }
+    reader.read_u32()?;
+    let count = reader.read_u16()?;
+    let mut offsets = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        offsets.push(reader.read_u32()? as usize);
+    }
+    let mut region_counts = Vec::with_capacity(count as usize);
+    for (i, offset) in offsets.into_iter().enumerate() {
+        // itemCount、wordDeltaCount 之后为 regionIndexCount
+        reader.seek(store + offset + 4)?;
+        let region_count = reader
+            .read_u16()
+            .context(format!("itemVariationData[{}]", i), 0)?;
+        region_counts.push(region_count);
+    }
+    Ok(region_counts)
+}
+
+/// 参数为表长度
+impl ReadWith<ReaderBoxed<'_>, usize> for CFF2 {
+    fn read_with(reader: &mut ReaderBoxed, length: usize) -> Result<Self, IOError> {
+        let data = reader.read_bytes_expected(length)?;
+        let mut reader = SliceReader::new(data.as_slice());
+        let parsed = Self::parse(&data, &mut reader).at(reader.position())?;
+        Ok(Self { data, ..parsed })
+    }
+}
+
+impl CFF2 {
+    /// 从表数据 `data` 开头读取，返回值的 `data` 为空
+    fn parse(data: &[u8], reader: &mut SliceReader<&[u8]>) -> Result<Self, IOError> {
+        use dict_operator::*;
+
+        let header = CFF2Header {
+            major_version: reader.read_u8()?,
+            minor_version: reader.read_u8()?,
+            header_size: reader.read_u8()?,
+            top_dict_size: reader.read_u16()?,
+        };
+        if header.major_version != 2 {
+            return Err(IOError::unexpected("major", 2, header.major_version, 0));
+        }
+        let top_dict_offset = header.header_size as usize;
+        let top_dict = data
+            .get(top_dict_offset..top_dict_offset + header.top_dict_size as usize)
+            .ok_or(IOError::NoEnoughData)
+            .and_then(|dict| parse_dict(dict, Some(&[])))
+            .at(0)
+            .context("topDict", top_dict_offset)?;
+        reader.seek(top_dict_offset + header.top_dict_size as usize)?;
+        let global_subrs = read_index(reader, 4).context("globalSubrIndex", 0)?;
+
+        let region_counts = match dict_get(&top_dict, VSTORE) {
+            Some(&[vstore]) => {
+                reader.seek(vstore as usize)?;
+                read_region_counts(reader).context("variationStore", 0)?
+            }
+            _ => vec![],
+        };
+        let Some(&[char_strings]) = dict_get(&top_dict, CHAR_STRINGS) else {
+            return Err(IOError::unexpected(
+                "topDict/CharStrings",
+                "offset",
+                "none",
+                0,
+            ));
+        };
+        reader.seek(char_strings as usize)?;
+        let char_strings = read_index(reader, 4).context("charStrings", 0)?;
+
+        let Some(&[fd_array]) = dict_get(&top_dict, FD_ARRAY) else {
+            return Err(IOError::unexpected("topDict/FDArray", "offset", "none", 0));
+        };
+        reader.seek(fd_array as usize)?;
+        let mut private_dicts = vec![];
+        for (i, dict) in read_index(reader, 4)
+            .context("fdArray", 0)?
+            .iter()
+            .enumerate()
+        {
+            let private =
+                parse_dict(dict, Some(&[])).and_then(|dict| match dict_get(&dict, PRIVATE) {
+                    Some(&[size, offset]) => PrivateDict::read(
+                        data,
+                        offset as usize,
+                        size as usize,
+                        Some(&region_counts),
+                    ),
+                    _ => Err(IOError::unexpected("Private", "size and offset", "none", 0)),
+                });
+            private_dicts.push(private.context(format!("fdArray[{}]", i), 0)?);
+        }
+        let fd_select = match dict_get(&top_dict, FD_SELECT) {
+            Some(&[fd_select]) => {
+                reader.seek(fd_select as usize)?;
+                read_fd_select(reader, char_strings.len()).context("fdSelect", 0)?
+            }
+            _ => vec![],
+        };
+        Ok(Self {
+            header,
+            global_subrs,
+            char_strings,
+            private_dicts,
+            fd_select,
+            region_counts,
+            data: vec![],
+        })
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for CFF2 {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        writer.write(&self.data)
+    }
+}
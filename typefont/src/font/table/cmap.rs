@@ -1,6 +1,16 @@
-use crate::font::Offset32;
+use crate::font::encoding::{LegacyEncoding, mac_roman_to_char};
+use crate::font::{GlyphId, Offset32};
+use crate::font::io::ReadFrom;
 use crate::impl_named;
+use crate::io::error::{ErrorContext, IOError};
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Patch, Write, Writer};
 use bit_struct::u24;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
 
 /// # cmap — 字符到字形索引映射表
 /// 2024/05/29
@@ -73,58 +83,61 @@ pub struct EncodingRecord {
     encoding_id: u16,
     /// 此编码从表开头到子表的字节偏移量。
     subtable_offset: Offset32,
-    sub_tables: Vec<CmapSubTable>,
+    /// 多个编码记录可以指向同一个子表
+    subtable: Rc<CmapSubTable>,
 }
 
 ///
 /// 平台 ID 值 240 到 255 是为用户定义的平台保留的，绝不能分配给已注册的平台。
 ///
 pub mod platform_id {
-    const UNICODE: u16 = 0;
-    const MACINTOSH: u16 = 1;
+    pub const UNICODE: u16 = 0;
+    pub const MACINTOSH: u16 = 1;
     #[deprecated]
-    const ISO: u16 = 2;
-    const WINDOWS: u16 = 3;
-    const CUSTOM: u16 = 4;
+    pub const ISO: u16 = 2;
+    pub const WINDOWS: u16 = 3;
+    pub const CUSTOM: u16 = 4;
 }
 
 /// Unicode Platform Encoding Id
 pub mod unicode_platform_encoding {
     #[deprecated]
-    const UNICODE1_0: u16 = 0;
+    pub const UNICODE1_0: u16 = 0;
     #[deprecated]
-    const UNICODE1_1: u16 = 1;
+    pub const UNICODE1_1: u16 = 1;
     #[deprecated]
-    const ISO_IEC_10646: u16 = 2;
+    pub const ISO_IEC_10646: u16 = 2;
     /// 编码 ID 3 应与 'cmap' 子表格式 4 或 6 结合使用。
-    const UNICODE2_0_BMP: u16 = 3;
+    pub const UNICODE2_0_BMP: u16 = 3;
     /// 编码 ID 4 应与子表格式 10 或 12 结合使用。
-    const UNICODE2_0_FULL: u16 = 4;
+    pub const UNICODE2_0_FULL: u16 = 4;
     /// 用于子表格式 14
     ///
     /// 字体支持的 Unicode 变体序列应在 'cmap' 表中使用格式 14 子表指定。
     /// 格式 14 子表只能在平台 ID 0 和编码 ID 5 下使用;
     /// 编码 ID 5 只能与格式 14 子表一起使用。
-    const UNICODE_VARIATION: u16 = 5;
+    pub const UNICODE_VARIATION: u16 = 5;
     /// 用于子表格式 13
     ///
     /// 编码 ID 6 只能与 'cmap' 子表格式 13 结合使用;
     /// 子表格式 13 只能在平台 ID 0 和编码 ID 6 下使用。
-    const UNICODE_FULL: u16 = 6;
+    pub const UNICODE_FULL: u16 = 6;
 }
 
 /// 较旧的 Macintosh 版本要求字体具有平台 ID 1 的“cmap”子表。
 /// 对于当前的 Apple 平台，不建议使用平台 ID 1。
-pub mod macintosh_platform_encoding {}
+pub mod macintosh_platform_encoding {
+    pub const ROMAN: u16 = 0;
+}
 
 #[deprecated]
 pub mod iso_platform_encoding {
     #[deprecated]
-    const ASCII: u16 = 0;
+    pub const ASCII: u16 = 0;
     #[deprecated]
-    const ISO_10646: u16 = 1;
+    pub const ISO_10646: u16 = 1;
     #[deprecated]
-    const ISO_8859_1: u16 = 2;
+    pub const ISO_8859_1: u16 = 2;
 }
 
 /// `Windows` 平台支持多种编码。
@@ -146,14 +159,14 @@ pub mod iso_platform_encoding {
 /// 非 `Unicode` 的符号或字符应使用 `Unicode` `cmap`子表中的 PUA 码位进行编码。
 ///
 pub mod windows_platform_encoding {
-    const SYMBOL: u16 = 0;
-    const UNICODE_BMP: u16 = 1;
-    const SHIFT_JIS: u16 = 2;
-    const PRC: u16 = 3;
-    const BIG5: u16 = 4;
-    const WANSUNG: u16 = 5;
-    const JOHAB: u16 = 6;
-    const UNICODE_FULL: u16 = 10;
+    pub const SYMBOL: u16 = 0;
+    pub const UNICODE_BMP: u16 = 1;
+    pub const SHIFT_JIS: u16 = 2;
+    pub const PRC: u16 = 3;
+    pub const BIG5: u16 = 4;
+    pub const WANSUNG: u16 = 5;
+    pub const JOHAB: u16 = 6;
+    pub const UNICODE_FULL: u16 = 10;
 }
 
 ///
@@ -227,8 +240,8 @@ pub struct HighByteMappingThrough {
     /// 将高字节映射到 `sub_headers` 数组的数组：值为 `sub_headers` 索引 × 8。
     sub_header_keys: [u16; 256],
     sub_headers: Vec<HighByteMappingThroughTableSubHeader>,
-    /// entry_count
-    glyph_id_array: [u16; 256],
+    /// 子表剩余部分均为字形索引数组
+    glyph_id_array: Vec<u16>,
 }
 
 ///
@@ -633,9 +646,9 @@ pub struct UnicodeVariationSequences {
     /// 14
     format: u16,
     /// 此子表的字节长度（包括此标头）
-    length: u16,
+    length: u32,
     /// 变体选择器记录数
-    num_var_selector_records: u16,
+    num_var_selector_records: u32,
     /// var_selector\[num_var_selector_records]
     var_selector: Vec<VariationSelector>,
 }
@@ -659,6 +672,10 @@ pub struct VariationSelector {
     default_uvs_offset: Offset32,
     /// 从`格式 14` 子表的开头到非默认 UVS 表的偏移量。可能是 0。
     non_default_uvs_offset: Offset32,
+    /// `default_uvs_offset` 为 0 时为 `None`
+    default_uvs: Option<DefaultUVSTable>,
+    /// `non_default_uvs_offset` 为 0 时为 `None`
+    non_default_uvs: Option<NonDefaultUVSTable>,
 }
 
 /// 默认 UVS 表只是 `Unicode` 标量值的范围压缩列表，
@@ -714,3 +731,1804 @@ pub struct UVSMapping {
     /// UVS 的字形 `ID`
     glyph_id: u16,
 }
+
+impl Cmap {
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    pub fn num_tables(&self) -> u16 {
+        self.num_tables
+    }
+
+    pub fn encoding_records(&self) -> &Vec<EncodingRecord> {
+        &self.encoding_records
+    }
+
+    /// 按 [EncodingRecord] 中的建议选择 `Unicode` 子表：
+    /// 优先使用支持补充平面的 (3, 10) 或 (0, 4) 子表，其次是 (3, 1) 或 (0, 3) BMP 子表，
+    /// 最后是已弃用的 Unicode 编码。格式 13 和格式 14 子表不会被选择。
+    #[allow(deprecated)]
+    pub fn best_unicode_subtable(&self) -> Option<&CmapSubTable> {
+        use platform_id::{UNICODE, WINDOWS};
+        use unicode_platform_encoding::{
+            ISO_IEC_10646, UNICODE1_0, UNICODE1_1, UNICODE2_0_BMP, UNICODE2_0_FULL,
+        };
+        use windows_platform_encoding::{UNICODE_BMP, UNICODE_FULL};
+
+        const PREFERENCE: [(u16, u16); 7] = [
+            (WINDOWS, UNICODE_FULL),
+            (UNICODE, UNICODE2_0_FULL),
+            (WINDOWS, UNICODE_BMP),
+            (UNICODE, UNICODE2_0_BMP),
+            (UNICODE, ISO_IEC_10646),
+            (UNICODE, UNICODE1_1),
+            (UNICODE, UNICODE1_0),
+        ];
+        PREFERENCE.iter().find_map(|&(platform_id, encoding_id)| {
+            self.encoding_records
+                .iter()
+                .filter(|r| r.platform_id == platform_id && r.encoding_id == encoding_id)
+                .map(|r| r.subtable())
+                .find(|subtable| !matches!(subtable.format(), 13 | 14))
+        })
+    }
+
+    /// 使用 [Cmap::best_unicode_subtable] 查找字符对应的字形索引
+    pub fn glyph_index(&self, c: char) -> Option<GlyphId> {
+        self.best_unicode_subtable()?.glyph_id(c as u32)
+    }
+
+    /// [Cmap::best_unicode_subtable] 中所有的 (码位, 字形索引)，按码位升序排列
+    pub fn mappings(&self) -> impl Iterator<Item = (u32, GlyphId)> + '_ {
+        self.best_unicode_subtable()
+            .into_iter()
+            .flat_map(|subtable| subtable.mappings())
+    }
+
+    /// 字形索引到码位的反向映射，码位按升序排列
+    pub fn codepoints_by_glyph(&self) -> BTreeMap<GlyphId, Vec<u32>> {
+        let mut map: BTreeMap<GlyphId, Vec<u32>> = BTreeMap::new();
+        for (code, glyph_id) in self.mappings() {
+            map.entry(glyph_id).or_default().push(code);
+        }
+        map
+    }
+
+    /// 平台 0、编码 5 的格式 14 子表
+    pub fn variation_subtable(&self) -> Option<&UnicodeVariationSequences> {
+        use platform_id::UNICODE;
+        use unicode_platform_encoding::UNICODE_VARIATION;
+
+        self.encoding_records
+            .iter()
+            .filter(|r| r.platform_id == UNICODE && r.encoding_id == UNICODE_VARIATION)
+            .find_map(|r| match r.subtable() {
+                CmapSubTable::Format14(subtable) => Some(subtable),
+                _ => None,
+            })
+    }
+
+    /// 查找变体序列 <`base`, `selector`> 对应的字形
+    ///
+    /// 默认 UVS 表中的序列还需要 `base` 在 [Cmap::best_unicode_subtable] 中有映射，
+    /// 否则返回 [VariationResult::NotFound]。
+    pub fn glyph_index_for_variation(&self, base: char, selector: char) -> VariationResult {
+        let result = match self.variation_subtable() {
+            Some(subtable) => subtable.lookup(base as u32, selector as u32),
+            None => VariationResult::NotFound,
+        };
+        match result {
+            VariationResult::Default if self.glyph_index(base).is_none() => {
+                VariationResult::NotFound
+            }
+            result => result,
+        }
+    }
+
+    /// 字体支持的所有变体序列 (基本字符, 变体选择器, 结果)，
+    /// 按变体选择器和基本字符升序排列，结果为 [VariationResult::Default] 或 [VariationResult::Glyph]
+    pub fn variation_sequences(&self) -> impl Iterator<Item = (u32, u32, VariationResult)> + '_ {
+        self.variation_subtable()
+            .into_iter()
+            .flat_map(|subtable| subtable.sequences())
+    }
+
+    /// 平台 3 中第一个使用传统 CJK 编码的子表
+    pub fn legacy_subtable(&self) -> Option<(LegacyEncoding, &CmapSubTable)> {
+        self.encoding_records
+            .iter()
+            .filter(|r| r.platform_id == platform_id::WINDOWS)
+            .find_map(|r| {
+                let encoding = LegacyEncoding::from_windows_encoding(r.encoding_id)?;
+                Some((encoding, r.subtable()))
+            })
+    }
+
+    /// 按 `encoding` 拆分字节序列，在平台 3 对应编码的子表中查找每个字符的字形索引，
+    /// 没有对应子表时全部为 `None`
+    pub fn legacy_glyph_indices(
+        &self,
+        encoding: LegacyEncoding,
+        bytes: &[u8],
+    ) -> Vec<Option<GlyphId>> {
+        let subtable = self
+            .encoding_records
+            .iter()
+            .find(|r| {
+                r.platform_id == platform_id::WINDOWS
+                    && r.encoding_id == encoding.windows_encoding()
+            })
+            .map(|r| r.subtable());
+        encoding
+            .codes(bytes)
+            .into_iter()
+            .map(|code| subtable?.glyph_id(code as u32))
+            .collect()
+    }
+}
+
+/// 变体序列的查找结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariationResult {
+    /// 默认 UVS，使用基本字符在 `Unicode` 子表中映射的字形
+    Default,
+    /// 非默认 UVS 指定的字形
+    Glyph(GlyphId),
+    /// 字体不支持此变体序列
+    NotFound,
+}
+
+impl EncodingRecord {
+    pub fn platform_id(&self) -> u16 {
+        self.platform_id
+    }
+
+    pub fn encoding_id(&self) -> u16 {
+        self.encoding_id
+    }
+
+    pub fn subtable_offset(&self) -> Offset32 {
+        self.subtable_offset
+    }
+
+    pub fn subtable(&self) -> &CmapSubTable {
+        &self.subtable
+    }
+}
+
+impl CmapSubTable {
+    pub fn format(&self) -> u16 {
+        match self {
+            CmapSubTable::Format0(_) => 0,
+            CmapSubTable::Format2(_) => 2,
+            CmapSubTable::Format4(_) => 4,
+            CmapSubTable::Format6(_) => 6,
+            CmapSubTable::Format8(_) => 8,
+            CmapSubTable::Format10(_) => 10,
+            CmapSubTable::Format12(_) => 12,
+            CmapSubTable::Format13(_) => 13,
+            CmapSubTable::Format14(_) => 14,
+        }
+    }
+
+    /// 格式 14 没有语言字段，返回 0
+    pub fn language(&self) -> u32 {
+        match self {
+            CmapSubTable::Format0(t) => t.language as u32,
+            CmapSubTable::Format2(t) => t.language as u32,
+            CmapSubTable::Format4(t) => t.language as u32,
+            CmapSubTable::Format6(t) => t.language as u32,
+            CmapSubTable::Format8(t) => t.language,
+            CmapSubTable::Format10(t) => t.language,
+            CmapSubTable::Format12(t) => t.language,
+            CmapSubTable::Format13(t) => t.language,
+            CmapSubTable::Format14(_) => 0,
+        }
+    }
+
+    /// 字符代码对应的字形索引，未映射（即映射到 `.notdef`）时返回 `None`
+    ///
+    /// 格式 14 只映射变体序列，总是返回 `None`
+    pub fn glyph_id(&self, code: u32) -> Option<GlyphId> {
+        let glyph_id = match self {
+            CmapSubTable::Format0(t) => t.lookup(code),
+            CmapSubTable::Format2(t) => t.lookup(code),
+            CmapSubTable::Format4(t) => t.lookup(code),
+            CmapSubTable::Format6(t) => t.lookup(code),
+            CmapSubTable::Format8(t) => lookup_sequential(&t.groups, code),
+            CmapSubTable::Format10(t) => t.lookup(code),
+            CmapSubTable::Format12(t) => lookup_sequential(&t.groups, code),
+            CmapSubTable::Format13(t) => find_group(&t.groups, code)
+                .and_then(|group| u16::try_from(group.start_glyph_id).ok()),
+            CmapSubTable::Format14(_) => None,
+        };
+        glyph_id.filter(|&glyph_id| glyph_id != 0)
+    }
+
+    /// 所有映射到非 0 字形的 (字符代码, 字形索引)，按字符代码升序排列
+    ///
+    /// 格式 14 只映射变体序列，没有任何映射
+    pub fn mappings(&self) -> Box<dyn Iterator<Item = (u32, GlyphId)> + '_> {
+        let mappings: Box<dyn Iterator<Item = (u32, GlyphId)> + '_> = match self {
+            CmapSubTable::Format0(t) => Box::new(
+                (0..t.glyph_id_array.len() as u32).filter_map(|code| Some((code, t.lookup(code)?))),
+            ),
+            CmapSubTable::Format2(t) => {
+                Box::new((0..0x10000).filter_map(|code| Some((code, t.lookup(code)?))))
+            }
+            CmapSubTable::Format4(t) => Box::new((0..t.seg_count()).flat_map(move |i| {
+                (t.start_code[i]..=t.end_code[i])
+                    .filter_map(move |code| Some((code as u32, t.segment_glyph_id(i, code)?)))
+            })),
+            CmapSubTable::Format6(t) => Box::new(
+                (t.first_code as u32..t.first_code as u32 + t.glyph_id_array.len() as u32)
+                    .filter_map(|code| Some((code, t.lookup(code)?))),
+            ),
+            CmapSubTable::Format8(t) => Box::new(sequential_mappings(&t.groups)),
+            CmapSubTable::Format10(t) => Box::new(
+                t.glyph_id_array
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &glyph_id)| (t.start_char_code + i as u32, glyph_id)),
+            ),
+            CmapSubTable::Format12(t) => Box::new(sequential_mappings(&t.groups)),
+            CmapSubTable::Format13(t) => Box::new(t.groups.iter().flat_map(|group| {
+                let glyph_id = u16::try_from(group.start_glyph_id).unwrap_or(0);
+                (group.start_char_code..=group.end_char_code).map(move |code| (code, glyph_id))
+            })),
+            CmapSubTable::Format14(_) => Box::new(std::iter::empty()),
+        };
+        Box::new(mappings.filter(|&(_, glyph_id)| glyph_id != 0))
+    }
+}
+
+impl ByteEncodingTable {
+    pub fn format(&self) -> u16 {
+        self.format
+    }
+
+    pub fn length(&self) -> u16 {
+        self.length
+    }
+
+    fn lookup(&self, code: u32) -> Option<GlyphId> {
+        self.glyph_id_array.get(code as usize).map(|&g| g as u16)
+    }
+}
+
+impl HighByteMappingThrough {
+    pub fn format(&self) -> u16 {
+        self.format
+    }
+
+    pub fn length(&self) -> u16 {
+        self.length
+    }
+
+    fn lookup(&self, code: u32) -> Option<GlyphId> {
+        // 单字节代码使用 `SubHeader` 0，双字节代码由高字节选择 `SubHeader`
+        let (index, low) = if code < 0x100 {
+            if self.sub_header_keys[code as usize] != 0 {
+                return None;
+            }
+            (0, code as u16)
+        } else if code < 0x10000 {
+            let index = self.sub_header_keys[(code >> 8) as usize] as usize / 8;
+            if index == 0 {
+                return None;
+            }
+            (index, (code & 0xFF) as u16)
+        } else {
+            return None;
+        };
+        let sub_header = self.sub_headers.get(index)?;
+        let offset = low.checked_sub(sub_header.first_code)?;
+        if offset >= sub_header.entry_count {
+            return None;
+        }
+        // `id_range_offset` 相对于该字段自身的位置，
+        // 字段位于 `sub_headers[index]` 的第 6 字节，`glyph_id_array` 紧随 `sub_headers`
+        let position = 8 * index + 6 + sub_header.id_range_offset as usize + 2 * offset as usize;
+        let array_index = position.checked_sub(8 * self.sub_headers.len())? / 2;
+        match *self.glyph_id_array.get(array_index)? {
+            0 => Some(0),
+            glyph_id => Some(glyph_id.wrapping_add(sub_header.id_delta)),
+        }
+    }
+}
+
+impl SegmentMappingToDeltaValues {
+    pub fn format(&self) -> u16 {
+        self.format
+    }
+
+    pub fn length(&self) -> u16 {
+        self.length
+    }
+
+    pub fn seg_count_x2(&self) -> u16 {
+        self.seg_count_x2
+    }
+
+    pub fn search_range(&self) -> u16 {
+        self.search_range
+    }
+
+    pub fn entry_selector(&self) -> u16 {
+        self.entry_selector
+    }
+
+    pub fn range_shift(&self) -> u16 {
+        self.range_shift
+    }
+
+    pub fn seg_count(&self) -> usize {
+        self.end_code.len()
+    }
+
+    fn lookup(&self, code: u32) -> Option<GlyphId> {
+        let code = u16::try_from(code).ok()?;
+        let i = self.end_code.partition_point(|&end| end < code);
+        if code < *self.start_code.get(i)? {
+            return None;
+        }
+        self.segment_glyph_id(i, code)
+    }
+
+    /// 第 `i` 个段中 `code` 对应的字形索引
+    fn segment_glyph_id(&self, i: usize, code: u16) -> Option<GlyphId> {
+        let id_delta = self.id_delta[i];
+        let id_range_offset = self.id_range_offset[i];
+        if id_range_offset == 0 {
+            return Some(code.wrapping_add(id_delta));
+        }
+        // `id_range_offset[i]` 相对于该字段自身的位置，`glyph_id_array` 紧随 `id_range_offset`，
+        // 因此减去 `id_range_offset` 中剩余的 `seg_count - i` 项
+        let index = (id_range_offset / 2) as usize + (code - self.start_code[i]) as usize + i;
+        let index = index.checked_sub(self.seg_count())?;
+        match *self.glyph_id_array.get(index)? {
+            0 => Some(0),
+            glyph_id => Some(glyph_id.wrapping_add(id_delta)),
+        }
+    }
+}
+
+impl TrimmedTableMapping {
+    pub fn format(&self) -> u16 {
+        self.format
+    }
+
+    pub fn length(&self) -> u16 {
+        self.length
+    }
+
+    pub fn entry_count(&self) -> u16 {
+        self.entry_count
+    }
+
+    fn lookup(&self, code: u32) -> Option<GlyphId> {
+        let index = code.checked_sub(self.first_code as u32)?;
+        self.glyph_id_array.get(index as usize).copied()
+    }
+}
+
+impl TrimmedArray {
+    pub fn format(&self) -> u16 {
+        self.format
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    pub fn num_chars(&self) -> u32 {
+        self.num_chars
+    }
+
+    fn lookup(&self, code: u32) -> Option<GlyphId> {
+        let index = code.checked_sub(self.start_char_code)?;
+        self.glyph_id_array.get(index as usize).copied()
+    }
+}
+
+impl Mixed16And32BitCoverage {
+    pub fn format(&self) -> u16 {
+        self.format
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    pub fn num_groups(&self) -> u32 {
+        self.num_groups
+    }
+
+    /// `code` 是否为 32 位字符代码的高 16 位
+    pub fn is32(&self, code: u16) -> bool {
+        self.is32[code as usize / 8] & (1 << (7 - code % 8)) != 0
+    }
+
+    pub fn groups(&self) -> &Vec<SequentialMapGroup> {
+        &self.groups
+    }
+}
+
+impl SegmentedCoverage {
+    pub fn format(&self) -> u16 {
+        self.format
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    pub fn num_groups(&self) -> u32 {
+        self.num_groups
+    }
+
+    pub fn groups(&self) -> &Vec<SequentialMapGroup> {
+        &self.groups
+    }
+}
+
+impl ManyToOneRangeMappings {
+    pub fn format(&self) -> u16 {
+        self.format
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    pub fn num_groups(&self) -> u32 {
+        self.num_groups
+    }
+
+    pub fn groups(&self) -> &Vec<ConstantMapGroup> {
+        &self.groups
+    }
+}
+
+impl UnicodeVariationSequences {
+    pub fn format(&self) -> u16 {
+        self.format
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    pub fn num_var_selector_records(&self) -> u32 {
+        self.num_var_selector_records
+    }
+
+    pub fn var_selectors(&self) -> &Vec<VariationSelector> {
+        &self.var_selector
+    }
+
+    /// 查找变体序列，变体选择器记录、默认 UVS 范围和非默认 UVS 映射均已在读取时检查为有序
+    pub fn lookup(&self, base: u32, selector: u32) -> VariationResult {
+        let record = match self
+            .var_selector
+            .binary_search_by_key(&selector, |record| record.var_selector())
+        {
+            Ok(i) => &self.var_selector[i],
+            Err(_) => return VariationResult::NotFound,
+        };
+        if let Some(table) = &record.default_uvs {
+            let i = table
+                .ranges
+                .partition_point(|range| range.end_unicode_value() < base);
+            if table
+                .ranges
+                .get(i)
+                .is_some_and(|range| range.start_unicode_value() <= base)
+            {
+                return VariationResult::Default;
+            }
+        }
+        if let Some(table) = &record.non_default_uvs
+            && let Ok(i) = table
+                .uvs_mappings
+                .binary_search_by_key(&base, |mapping| mapping.unicode_value())
+        {
+            return match table.uvs_mappings[i].glyph_id {
+                0 => VariationResult::NotFound,
+                glyph_id => VariationResult::Glyph(glyph_id),
+            };
+        }
+        VariationResult::NotFound
+    }
+
+    /// 所有变体序列 (基本字符, 变体选择器, 结果)，见 [Cmap::variation_sequences]
+    pub fn sequences(&self) -> impl Iterator<Item = (u32, u32, VariationResult)> + '_ {
+        self.var_selector.iter().flat_map(|record| {
+            let selector = record.var_selector();
+            let mut sequences: Vec<_> = record
+                .default_uvs
+                .iter()
+                .flat_map(|table| &table.ranges)
+                .flat_map(|range| range.start_unicode_value()..=range.end_unicode_value())
+                .map(|base| (base, selector, VariationResult::Default))
+                .chain(
+                    record
+                        .non_default_uvs
+                        .iter()
+                        .flat_map(|table| &table.uvs_mappings)
+                        .filter(|mapping| mapping.glyph_id != 0)
+                        .map(|mapping| {
+                            (
+                                mapping.unicode_value(),
+                                selector,
+                                VariationResult::Glyph(mapping.glyph_id),
+                            )
+                        }),
+                )
+                .collect();
+            sequences.sort_by_key(|&(base, _, _)| base);
+            sequences
+        })
+    }
+}
+
+impl VariationSelector {
+    pub fn var_selector(&self) -> u32 {
+        self.var_selector.value()
+    }
+
+    pub fn default_uvs_offset(&self) -> Offset32 {
+        self.default_uvs_offset
+    }
+
+    pub fn non_default_uvs_offset(&self) -> Offset32 {
+        self.non_default_uvs_offset
+    }
+
+    pub fn default_uvs(&self) -> Option<&DefaultUVSTable> {
+        self.default_uvs.as_ref()
+    }
+
+    pub fn non_default_uvs(&self) -> Option<&NonDefaultUVSTable> {
+        self.non_default_uvs.as_ref()
+    }
+}
+
+impl DefaultUVSTable {
+    pub fn num_unicode_value_ranges(&self) -> u32 {
+        self.num_unicode_value_ranges
+    }
+
+    pub fn ranges(&self) -> &Vec<UnicodeRange> {
+        &self.ranges
+    }
+}
+
+impl NonDefaultUVSTable {
+    pub fn num_uvs_mappings(&self) -> u32 {
+        self.num_uvs_mappings
+    }
+
+    pub fn uvs_mappings(&self) -> &Vec<UVSMapping> {
+        &self.uvs_mappings
+    }
+}
+
+impl UVSMapping {
+    pub fn unicode_value(&self) -> u32 {
+        self.unicode_value.value()
+    }
+
+    pub fn glyph_id(&self) -> GlyphId {
+        self.glyph_id
+    }
+}
+
+impl SequentialMapGroup {
+    pub fn start_char_code(&self) -> u32 {
+        self.start_char_code
+    }
+
+    pub fn end_char_code(&self) -> u32 {
+        self.end_char_code
+    }
+
+    pub fn start_glyph_id(&self) -> u32 {
+        self.start_glyph_id
+    }
+}
+
+impl Cmap {
+    /// 由 `Unicode` 码位到字形索引的映射生成 `cmap` 表，映射到字形 0 的码位被忽略
+    ///
+    /// - (0, 3) 和 (3, 1) 共用包含 BMP 映射的格式 4 子表，
+    ///   子表超过 65535 字节时只包含码位最小的、能放下的一部分映射
+    /// - 存在补充平面字符或格式 4 子表放不下所有 BMP 映射时，
+    ///   (0, 4) 和 (3, 10) 共用包含所有映射的格式 12 子表
+    /// - `variations` 不为空时生成 (0, 5) 格式 14 子表，
+    ///   `variations` 的格式与 [Cmap::variation_sequences] 相同，[VariationResult::NotFound] 被忽略
+    /// - `mac_roman` 为 `true` 时生成 (1, 0) Mac OS Roman 子表，
+    ///   字形索引都小于 256 且格式 0 更小时使用格式 0，否则使用格式 6
+    ///
+    /// 编码记录按平台 ID 和编码 ID 排序，子表偏移量在写入时计算。
+    /// 码位或变体选择器超出 `Unicode` 范围时返回错误。
+    pub fn compile(
+        mappings: &BTreeMap<u32, GlyphId>,
+        variations: &[(u32, u32, VariationResult)],
+        mac_roman: bool,
+    ) -> Result<Cmap, IOError> {
+        use macintosh_platform_encoding::ROMAN;
+        use platform_id::{MACINTOSH, UNICODE, WINDOWS};
+        use unicode_platform_encoding::{UNICODE_VARIATION, UNICODE2_0_BMP, UNICODE2_0_FULL};
+        use windows_platform_encoding::{UNICODE_BMP, UNICODE_FULL};
+
+        if let Some(&code) = mappings.keys().next_back() {
+            unicode_value(code)?;
+        }
+        let mappings: Vec<(u32, GlyphId)> = mappings
+            .iter()
+            .map(|(&code, &glyph_id)| (code, glyph_id))
+            .filter(|&(_, glyph_id)| glyph_id != 0)
+            .collect();
+        let (bmp, full) = compile_unicode(&mappings);
+        let bmp = Rc::new(CmapSubTable::Format4(bmp));
+        let full = full.map(|full| Rc::new(CmapSubTable::Format12(full)));
+
+        let mut records = vec![(UNICODE, UNICODE2_0_BMP, bmp.clone())];
+        if let Some(full) = &full {
+            records.push((UNICODE, UNICODE2_0_FULL, full.clone()));
+        }
+        if !variations.is_empty() {
+            let subtable = UnicodeVariationSequences::compile(variations)?;
+            records.push((
+                UNICODE,
+                UNICODE_VARIATION,
+                Rc::new(CmapSubTable::Format14(subtable)),
+            ));
+        }
+        if mac_roman {
+            records.push((MACINTOSH, ROMAN, Rc::new(compile_mac_roman(&mappings))));
+        }
+        records.push((WINDOWS, UNICODE_BMP, bmp));
+        if let Some(full) = full {
+            records.push((WINDOWS, UNICODE_FULL, full));
+        }
+        Ok(Cmap {
+            version: 0,
+            num_tables: records.len() as u16,
+            encoding_records: records
+                .into_iter()
+                .map(|(platform_id, encoding_id, subtable)| EncodingRecord {
+                    platform_id,
+                    encoding_id,
+                    subtable_offset: 0,
+                    subtable,
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(feature = "cjk")]
+impl Cmap {
+    /// 字体没有 `Unicode` 子表时，把 [Cmap::legacy_subtable] 转换为 (3, 1) 格式 4 子表，
+    /// 存在补充平面字符或格式 4 子表放不下所有映射时还添加 (3, 10) 格式 12 子表，
+    /// 返回添加了这些子表的 `cmap`，原有的子表保留。
+    ///
+    /// 已有 `Unicode` 子表、没有传统编码子表或没有可转换的映射时返回 `None`。
+    pub fn unicode_from_legacy(&self) -> Option<Cmap> {
+        use windows_platform_encoding::{UNICODE_BMP, UNICODE_FULL};
+
+        if self.best_unicode_subtable().is_some() {
+            return None;
+        }
+        let (encoding, subtable) = self.legacy_subtable()?;
+        let mappings: Vec<(u32, GlyphId)> = subtable.transcode(encoding).into_iter().collect();
+        if mappings.is_empty() {
+            return None;
+        }
+        let mut records: Vec<EncodingRecord> = self
+            .encoding_records
+            .iter()
+            .map(|r| EncodingRecord {
+                platform_id: r.platform_id,
+                encoding_id: r.encoding_id,
+                subtable_offset: 0,
+                subtable: r.subtable.clone(),
+            })
+            .collect();
+        let (bmp, full) = compile_unicode(&mappings);
+        let mut subtables = vec![(UNICODE_BMP, CmapSubTable::Format4(bmp))];
+        if let Some(full) = full {
+            subtables.push((UNICODE_FULL, CmapSubTable::Format12(full)));
+        }
+        for (encoding_id, subtable) in subtables {
+            let key = (platform_id::WINDOWS, encoding_id);
+            let index = records.partition_point(|r| (r.platform_id, r.encoding_id) < key);
+            records.insert(
+                index,
+                EncodingRecord {
+                    platform_id: platform_id::WINDOWS,
+                    encoding_id,
+                    subtable_offset: 0,
+                    subtable: Rc::new(subtable),
+                },
+            );
+        }
+        Some(Cmap {
+            version: self.version,
+            num_tables: records.len() as u16,
+            encoding_records: records,
+        })
+    }
+}
+
+#[cfg(feature = "cjk")]
+impl CmapSubTable {
+    /// 把使用传统编码的映射转换为 `Unicode` 码位的映射，
+    /// 多个字符代码对应同一字符时使用较小的字符代码
+    pub fn transcode(&self, encoding: LegacyEncoding) -> BTreeMap<u32, GlyphId> {
+        let mut mappings = BTreeMap::new();
+        for (code, glyph_id) in self.mappings() {
+            let Some(c) = u16::try_from(code).ok().and_then(|code| encoding.to_char(code)) else {
+                continue;
+            };
+            mappings.entry(c as u32).or_insert(glyph_id);
+        }
+        mappings
+    }
+}
+
+/// 检查码位在 `Unicode` 范围内
+fn unicode_value(code: u32) -> Result<u24, IOError> {
+    u24::new(code)
+        .filter(|_| code <= 0x10FFFF)
+        .ok_or_else(|| IOError::UnableOperate(format!("0x{:X} is not a Unicode code point", code)))
+}
+
+/// 由按码位升序排列的映射生成格式 4 子表，以及需要时包含所有映射的格式 12 子表
+///
+/// 格式 4 子表的长度不能超过 65535 字节，放不下所有 BMP 映射时只保留码位最小的一部分，
+/// 其余映射由格式 12 子表提供。
+fn compile_unicode(
+    mappings: &[(u32, GlyphId)],
+) -> (SegmentMappingToDeltaValues, Option<SegmentedCoverage>) {
+    let bmp_end = mappings.partition_point(|&(code, _)| code <= 0xFFFF);
+    let mut end = bmp_end;
+    let mut bmp = SegmentMappingToDeltaValues::compile(&mappings[..end]);
+    if bmp.is_none() {
+        // 子表长度随映射增加而增加，二分查找能放下的最长前缀
+        let (mut fits, mut overflows) = (0, bmp_end);
+        while overflows - fits > 1 {
+            let mid = (fits + overflows) / 2;
+            match SegmentMappingToDeltaValues::compile(&mappings[..mid]) {
+                Some(_) => fits = mid,
+                None => overflows = mid,
+            }
+        }
+        end = fits;
+        bmp = SegmentMappingToDeltaValues::compile(&mappings[..end]);
+    }
+    let full = (end < mappings.len()).then(|| SegmentedCoverage::compile(mappings));
+    // 只有结束段的空子表为 24 字节，总能放下
+    (bmp.unwrap(), full)
+}
+
+/// 格式 4 的 (`search_range`, `entry_selector`, `range_shift`)
+fn search_params(seg_count: u16) -> (u16, u16, u16) {
+    let entry_selector = match seg_count {
+        0 => 0,
+        n => 15 - n.leading_zeros() as u16,
+    };
+    let search_range = (1 << entry_selector) * 2;
+    (
+        search_range,
+        entry_selector,
+        (seg_count * 2).saturating_sub(search_range),
+    )
+}
+
+/// 码位和字形索引都连续的区间，`start..end` 为 `mappings` 中的下标
+fn sequential_runs<C: Copy + Into<u32>>(mappings: &[(C, GlyphId)]) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = vec![];
+    for (i, &(code, glyph_id)) in mappings.iter().enumerate() {
+        match runs.last_mut() {
+            Some((_, end))
+                if code.into() == mappings[i - 1].0.into() + 1
+                    && glyph_id == mappings[i - 1].1.wrapping_add(1) =>
+            {
+                *end = i + 1
+            }
+            _ => runs.push((i, i + 1)),
+        }
+    }
+    runs
+}
+
+impl SegmentMappingToDeltaValues {
+    /// 由按码位升序排列的映射生成，只使用 BMP 中的映射
+    ///
+    /// 码位和字形索引都连续的区间可以用一个只有 `id_delta` 的段表示（8 字节），
+    /// 也可以与相邻区间合并为使用 `glyph_id_array` 的段（8 字节加上每个码位 2 字节，
+    /// 区间之间未映射的码位填 0）。按子表长度最小选择每个区间的表示方式，
+    /// 最小的长度仍超过 65535 字节时返回 `None`。
+    fn compile(mappings: &[(u32, GlyphId)]) -> Option<Self> {
+        let bmp: Vec<(u16, GlyphId)> = mappings
+            .iter()
+            .filter(|&&(code, _)| code < 0xFFFF)
+            .map(|&(code, glyph_id)| (code as u16, glyph_id))
+            .collect();
+        let runs = sequential_runs(&bmp);
+        let first_code = |run: usize| bmp[runs[run].0].0 as usize;
+        let last_code = |run: usize| bmp[runs[run].1 - 1].0 as usize;
+
+        // `cost[k]` 为前 `k` 个区间的最小长度，`choice[k]` 为最后一个段的第一个区间，
+        // 最后一个段只包含第 `k - 1` 个区间时可能是 `id_delta` 段，见 `is_array`
+        let mut cost = vec![0usize; runs.len() + 1];
+        let mut choice = vec![(0, false); runs.len() + 1];
+        // 以第 `p` 个区间开始的数组段：`cost[p] + 8 + 2 * (last_code + 1 - first_code(p))`，
+        // 记录 `cost[p] + 8 - 2 * first_code(p)` 的最小值
+        let mut best_array = (usize::MAX, 0);
+        for q in 0..runs.len() {
+            let candidate = cost[q] + 8 + 2 * (u16::MAX as usize) - 2 * first_code(q);
+            if candidate < best_array.0 {
+                best_array = (candidate, q);
+            }
+            let delta = cost[q] + 8;
+            let array = best_array.0 + 2 * (last_code(q) + 1) - 2 * (u16::MAX as usize);
+            cost[q + 1] = delta.min(array);
+            choice[q + 1] = match delta <= array {
+                true => (q, false),
+                false => (best_array.1, true),
+            };
+        }
+        // 子表头 16 字节，最后还有一个 `0xFFFF` 段
+        if 24 + cost[runs.len()] > u16::MAX as usize {
+            return None;
+        }
+        let mut segments = vec![];
+        let mut k = runs.len();
+        while k > 0 {
+            let (p, is_array) = choice[k];
+            segments.push((p, k, is_array));
+            k = p;
+        }
+        segments.reverse();
+
+        let seg_count = segments.len() + 1;
+        let mut end_code = Vec::with_capacity(seg_count);
+        let mut start_code = Vec::with_capacity(seg_count);
+        let mut id_delta = Vec::with_capacity(seg_count);
+        let mut id_range_offset = Vec::with_capacity(seg_count);
+        let mut glyph_id_array = vec![];
+        for (i, &(first, last, is_array)) in segments.iter().enumerate() {
+            let (start, end) = (bmp[runs[first].0], bmp[runs[last - 1].1 - 1]);
+            start_code.push(start.0);
+            end_code.push(end.0);
+            if is_array {
+                // 见 `segment_glyph_id`，`glyph_id_array` 的下标为 `id_range_offset[i] / 2 + i - seg_count`
+                id_delta.push(0);
+                id_range_offset
+                    .push(u16::try_from(2 * (glyph_id_array.len() + seg_count - i)).ok()?);
+                let mut code = start.0;
+                for &(next, glyph_id) in &bmp[runs[first].0..runs[last - 1].1] {
+                    glyph_id_array.extend((code..next).map(|_| 0));
+                    glyph_id_array.push(glyph_id);
+                    code = next + 1;
+                }
+            } else {
+                id_delta.push(start.1.wrapping_sub(start.0));
+                id_range_offset.push(0);
+            }
+        }
+        // 最后一个段必须是 `0xFFFF`，未映射时 `0xFFFF + 1` 映射到字形 0
+        let last = mappings
+            .binary_search_by_key(&0xFFFF, |&(code, _)| code)
+            .map_or(0, |i| mappings[i].1);
+        start_code.push(0xFFFF);
+        end_code.push(0xFFFF);
+        id_delta.push(last.wrapping_add(1));
+        id_range_offset.push(0);
+
+        let (search_range, entry_selector, range_shift) = search_params(seg_count as u16);
+        let length = 16 + 8 * seg_count + 2 * glyph_id_array.len();
+        Some(Self {
+            format: 4,
+            length: u16::try_from(length).ok()?,
+            language: 0,
+            seg_count_x2: 2 * seg_count as u16,
+            search_range,
+            entry_selector,
+            range_shift,
+            end_code,
+            reserved_pad: 0,
+            start_code,
+            id_delta,
+            id_range_offset,
+            glyph_id_array,
+        })
+    }
+}
+
+impl SegmentedCoverage {
+    /// 由按码位升序排列的映射生成，码位和字形索引都连续的区间合并为一个分组
+    fn compile(mappings: &[(u32, GlyphId)]) -> Self {
+        let groups: Vec<SequentialMapGroup> = sequential_runs(mappings)
+            .into_iter()
+            .map(|(start, end)| SequentialMapGroup {
+                start_char_code: mappings[start].0,
+                end_char_code: mappings[end - 1].0,
+                start_glyph_id: mappings[start].1 as u32,
+            })
+            .collect();
+        Self {
+            format: 12,
+            reserved: 0,
+            length: 16 + 12 * groups.len() as u32,
+            language: 0,
+            num_groups: groups.len() as u32,
+            groups,
+        }
+    }
+}
+
+impl UnicodeVariationSequences {
+    /// 由 (基本字符, 变体选择器, 结果) 生成，相邻的默认 UVS 合并为范围
+    ///
+    /// 基本字符或变体选择器超出 `Unicode` 范围时返回错误。
+    fn compile(variations: &[(u32, u32, VariationResult)]) -> Result<Self, IOError> {
+        /// 每个变体选择器的 (默认 UVS 基本字符, 非默认 UVS 映射)
+        type Sequences = (Vec<u32>, Vec<(u32, GlyphId)>);
+        let mut selectors: BTreeMap<u32, Sequences> = BTreeMap::new();
+        for &(base, selector, result) in variations {
+            match result {
+                VariationResult::Default => selectors.entry(selector).or_default().0.push(base),
+                VariationResult::Glyph(glyph_id) => {
+                    selectors.entry(selector).or_default().1.push((base, glyph_id))
+                }
+                VariationResult::NotFound => {}
+            }
+        }
+
+        let mut offset = 10 + 11 * selectors.len() as u32;
+        let mut var_selector = Vec::with_capacity(selectors.len());
+        for (selector, (mut defaults, mut glyphs)) in selectors {
+            defaults.sort_unstable();
+            defaults.dedup();
+            glyphs.sort_unstable_by_key(|&(base, _)| base);
+            glyphs.dedup_by_key(|&mut (base, _)| base);
+
+            let mut ranges: Vec<UnicodeRange> = vec![];
+            for base in defaults {
+                match ranges.last_mut() {
+                    Some(range)
+                        if range.end_unicode_value() + 1 == base
+                            && range.additional_count < u8::MAX =>
+                    {
+                        range.additional_count += 1
+                    }
+                    _ => ranges.push(UnicodeRange {
+                        start_unicode_value: unicode_value(base)?,
+                        additional_count: 0,
+                    }),
+                }
+            }
+            let default_uvs_offset = match ranges.is_empty() {
+                true => 0,
+                false => offset,
+            };
+            offset += 4 * (ranges.len() as u32) + 4 * (!ranges.is_empty() as u32);
+            let non_default_uvs_offset = match glyphs.is_empty() {
+                true => 0,
+                false => offset,
+            };
+            offset += 5 * (glyphs.len() as u32) + 4 * (!glyphs.is_empty() as u32);
+
+            var_selector.push(VariationSelector {
+                var_selector: unicode_value(selector)?,
+                default_uvs_offset,
+                non_default_uvs_offset,
+                default_uvs: (!ranges.is_empty()).then_some(DefaultUVSTable {
+                    num_unicode_value_ranges: ranges.len() as u32,
+                    ranges,
+                }),
+                non_default_uvs: match glyphs.is_empty() {
+                    true => None,
+                    false => Some(NonDefaultUVSTable {
+                        num_uvs_mappings: glyphs.len() as u32,
+                        uvs_mappings: glyphs
+                            .into_iter()
+                            .map(|(base, glyph_id)| {
+                                Ok(UVSMapping {
+                                    unicode_value: unicode_value(base)?,
+                                    glyph_id,
+                                })
+                            })
+                            .collect::<Result<_, IOError>>()?,
+                    }),
+                },
+            });
+        }
+        Ok(Self {
+            format: 14,
+            length: offset,
+            num_var_selector_records: var_selector.len() as u32,
+            var_selector,
+        })
+    }
+}
+
+/// (1, 0) Mac OS Roman 子表，见 [Cmap::compile]
+fn compile_mac_roman(mappings: &[(u32, GlyphId)]) -> CmapSubTable {
+    let glyph_ids: Vec<GlyphId> = (0..=u8::MAX)
+        .map(|byte| {
+            let code = mac_roman_to_char(byte) as u32;
+            mappings
+                .binary_search_by_key(&code, |&(code, _)| code)
+                .map_or(0, |i| mappings[i].1)
+        })
+        .collect();
+    let first = glyph_ids.iter().position(|&g| g != 0).unwrap_or(0);
+    let end = glyph_ids.iter().rposition(|&g| g != 0).map_or(first, |i| i + 1);
+    let trimmed = glyph_ids[first..end].to_vec();
+    if glyph_ids.iter().all(|&g| g <= 0xFF) && 262 <= 10 + 2 * trimmed.len() {
+        let mut glyph_id_array = [0u8; 256];
+        for (byte, &glyph_id) in glyph_id_array.iter_mut().zip(&glyph_ids) {
+            *byte = glyph_id as u8;
+        }
+        return CmapSubTable::Format0(ByteEncodingTable {
+            format: 0,
+            length: 262,
+            language: 0,
+            glyph_id_array,
+        });
+    }
+    CmapSubTable::Format6(TrimmedTableMapping {
+        format: 6,
+        length: 10 + 2 * trimmed.len() as u16,
+        language: 0,
+        first_code: first as u16,
+        entry_count: trimmed.len() as u16,
+        glyph_id_array: trimmed,
+    })
+}
+
+/// 二分查找包含 `code` 的分组，分组已在读取时检查为有序且不重叠
+fn find_group(groups: &[SequentialMapGroup], code: u32) -> Option<&SequentialMapGroup> {
+    let i = groups.partition_point(|group| group.end_char_code < code);
+    groups.get(i).filter(|group| group.start_char_code <= code)
+}
+
+fn lookup_sequential(groups: &[SequentialMapGroup], code: u32) -> Option<GlyphId> {
+    let group = find_group(groups, code)?;
+    let glyph_id = group
+        .start_glyph_id
+        .checked_add(code - group.start_char_code)?;
+    u16::try_from(glyph_id).ok()
+}
+
+fn sequential_mappings(groups: &[SequentialMapGroup]) -> impl Iterator<Item = (u32, GlyphId)> + '_ {
+    groups.iter().flat_map(|group| {
+        (group.start_char_code..=group.end_char_code).map_while(move |code| {
+            let glyph_id = group
+                .start_glyph_id
+                .checked_add(code - group.start_char_code)?;
+            Some((code, u16::try_from(glyph_id).ok()?))
+        })
+    })
+}
+
+/// 检查分组按 `start_char_code` 升序排列且互不重叠，`base` 为第一个分组的偏移量
+fn check_groups(groups: &[SequentialMapGroup], base: usize) -> Result<(), IOError> {
+    for (i, group) in groups.iter().enumerate() {
+        let offset = base + 12 * i;
+        if group.end_char_code < group.start_char_code {
+            return Err(IOError::unexpected(
+                &format!("groups[{}]/endCharCode", i),
+                format!(">= 0x{:X}", group.start_char_code),
+                format!("0x{:X}", group.end_char_code),
+                offset + 4,
+            ));
+        }
+        if i > 0 && group.start_char_code <= groups[i - 1].end_char_code {
+            return Err(IOError::unexpected(
+                &format!("groups[{}]/startCharCode", i),
+                format!("> 0x{:X}", groups[i - 1].end_char_code),
+                format!("0x{:X}", group.start_char_code),
+                offset,
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn read_u16_array(reader: &mut ReaderBoxed, len: usize) -> Result<Vec<u16>, IOError> {
+    let mut array = Vec::with_capacity(len.min(reader.remaining() / 2));
+    for _ in 0..len {
+        array.push(reader.read_u16()?);
+    }
+    Ok(array)
+}
+
+fn read_groups(
+    reader: &mut ReaderBoxed,
+    num_groups: u32,
+) -> Result<Vec<SequentialMapGroup>, IOError> {
+    let mut groups = Vec::with_capacity((num_groups as usize).min(reader.remaining() / 12));
+    for _ in 0..num_groups {
+        groups.push(SequentialMapGroup::read_from(reader)?);
+    }
+    Ok(groups)
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for Cmap {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let version = reader.read_u16()?;
+        let num_tables = reader.read_u16()?;
+        let mut records = Vec::with_capacity(num_tables as usize);
+        for _ in 0..num_tables {
+            records.push((reader.read_u16()?, reader.read_u16()?, reader.read_u32()?));
+        }
+        // 指向同一偏移量的编码记录共享子表
+        let mut subtables: HashMap<Offset32, Rc<CmapSubTable>> = HashMap::new();
+        let mut encoding_records = Vec::with_capacity(records.len());
+        for (i, (platform_id, encoding_id, subtable_offset)) in records.into_iter().enumerate() {
+            let subtable = match subtables.get(&subtable_offset) {
+                Some(subtable) => subtable.clone(),
+                None => {
+                    let offset = subtable_offset as usize;
+                    let subtable = reader
+                        .sub_reader_from(offset)
+                        .and_then(|mut reader| {
+                            let subtable = CmapSubTable::read_from(&mut reader);
+                            subtable.at(reader.position())
+                        })
+                        .context(format!("encodingRecord[{}]", i), offset)?;
+                    let subtable = Rc::new(subtable);
+                    subtables.insert(subtable_offset, subtable.clone());
+                    subtable
+                }
+            };
+            encoding_records.push(EncodingRecord {
+                platform_id,
+                encoding_id,
+                subtable_offset,
+                subtable,
+            });
+        }
+        Ok(Self {
+            version,
+            num_tables,
+            encoding_records,
+        })
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for CmapSubTable {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        reader.mark()?;
+        let format = reader.read_u16()?;
+        reader.reset()?;
+        match format {
+            0 => ByteEncodingTable::read_from(reader).map(CmapSubTable::Format0),
+            2 => HighByteMappingThrough::read_from(reader).map(CmapSubTable::Format2),
+            4 => SegmentMappingToDeltaValues::read_from(reader).map(CmapSubTable::Format4),
+            6 => TrimmedTableMapping::read_from(reader).map(CmapSubTable::Format6),
+            8 => Mixed16And32BitCoverage::read_from(reader).map(CmapSubTable::Format8),
+            10 => TrimmedArray::read_from(reader).map(CmapSubTable::Format10),
+            12 => SegmentedCoverage::read_from(reader).map(CmapSubTable::Format12),
+            13 => ManyToOneRangeMappings::read_from(reader).map(CmapSubTable::Format13),
+            14 => UnicodeVariationSequences::read_from(reader).map(CmapSubTable::Format14),
+            format => {
+                return Err(IOError::unexpected(
+                    "format",
+                    "0, 2, 4, 6, 8, 10, 12, 13 or 14",
+                    format,
+                    0,
+                ));
+            }
+        }
+        .context(format!("subtable format {}", format), 0)
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for ByteEncodingTable {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let format = reader.read_u16()?;
+        let length = reader.read_u16()?;
+        let language = reader.read_u16()?;
+        let offset = reader.position();
+        let bytes = reader.read_bytes_expected(256)?;
+        let glyph_id_array = bytes.try_into().map_err(|bytes: Vec<u8>| {
+            IOError::unexpected("glyphIdArray", "256 bytes", bytes.len(), offset)
+        })?;
+        Ok(Self {
+            format,
+            length,
+            language,
+            glyph_id_array,
+        })
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for HighByteMappingThrough {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let format = reader.read_u16()?;
+        let length = reader.read_u16()?;
+        let language = reader.read_u16()?;
+        let mut sub_header_keys = [0u16; 256];
+        for (i, key) in sub_header_keys.iter_mut().enumerate() {
+            *key = reader.read_u16()?;
+            if *key % 8 != 0 {
+                return Err(IOError::unexpected(
+                    &format!("subHeaderKeys[{}]", i),
+                    "multiple of 8",
+                    *key,
+                    6 + 2 * i,
+                ));
+            }
+        }
+        let num_sub_headers = *sub_header_keys.iter().max().unwrap() as usize / 8 + 1;
+        let mut sub_headers = Vec::with_capacity(num_sub_headers);
+        for i in 0..num_sub_headers {
+            let sub_header = HighByteMappingThroughTableSubHeader {
+                first_code: reader.read_u16()?,
+                entry_count: reader.read_u16()?,
+                id_delta: reader.read_u16()?,
+                id_range_offset: reader.read_u16()?,
+            };
+            if sub_header.first_code as u32 + sub_header.entry_count as u32 > 256 {
+                return Err(IOError::unexpected(
+                    &format!("subHeaders[{}]/entryCount", i),
+                    format!("<= {}", 256 - sub_header.first_code.min(256)),
+                    sub_header.entry_count,
+                    518 + 8 * i + 2,
+                ));
+            }
+            sub_headers.push(sub_header);
+        }
+        let header_length = 518 + 8 * num_sub_headers;
+        if (length as usize) < header_length {
+            return Err(IOError::unexpected(
+                "length",
+                format!(">= {}", header_length),
+                length,
+                2,
+            ));
+        }
+        let glyph_id_array = read_u16_array(reader, (length as usize - header_length) / 2)?;
+        Ok(Self {
+            format,
+            length,
+            language,
+            sub_header_keys,
+            sub_headers,
+            glyph_id_array,
+        })
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for SegmentMappingToDeltaValues {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let format = reader.read_u16()?;
+        let length = reader.read_u16()?;
+        let language = reader.read_u16()?;
+        let seg_count_x2 = reader.read_u16()?;
+        if seg_count_x2 % 2 != 0 {
+            return Err(IOError::unexpected(
+                "segCountX2",
+                "even",
+                format!("0x{:X}", seg_count_x2),
+                6,
+            ));
+        }
+        let seg_count = seg_count_x2 as usize / 2;
+        let search_range = reader.read_u16()?;
+        let entry_selector = reader.read_u16()?;
+        let range_shift = reader.read_u16()?;
+        let end_code = read_u16_array(reader, seg_count)?;
+        let reserved_pad = reader.read_u16()?;
+        let start_code = read_u16_array(reader, seg_count)?;
+        let id_delta = read_u16_array(reader, seg_count)?;
+        let id_range_offset = read_u16_array(reader, seg_count)?;
+
+        // 段按 `end_code` 升序排列且互不重叠
+        for i in 0..seg_count {
+            let offset = 16 + seg_count_x2 as usize + 2 * i;
+            if start_code[i] > end_code[i] {
+                return Err(IOError::unexpected(
+                    &format!("startCode[{}]", i),
+                    format!("<= 0x{:04X}", end_code[i]),
+                    format!("0x{:04X}", start_code[i]),
+                    offset,
+                ));
+            }
+            if i > 0 && start_code[i] <= end_code[i - 1] {
+                return Err(IOError::unexpected(
+                    &format!("startCode[{}]", i),
+                    format!("> 0x{:04X}", end_code[i - 1]),
+                    format!("0x{:04X}", start_code[i]),
+                    offset,
+                ));
+            }
+        }
+
+        let header_length = 16 + 8 * seg_count;
+        if (length as usize) < header_length {
+            return Err(IOError::unexpected(
+                "length",
+                format!(">= {}", header_length),
+                length,
+                2,
+            ));
+        }
+        let glyph_id_array = read_u16_array(reader, (length as usize - header_length) / 2)?;
+        Ok(Self {
+            format,
+            length,
+            language,
+            seg_count_x2,
+            search_range,
+            entry_selector,
+            range_shift,
+            end_code,
+            reserved_pad,
+            start_code,
+            id_delta,
+            id_range_offset,
+            glyph_id_array,
+        })
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for TrimmedTableMapping {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let format = reader.read_u16()?;
+        let length = reader.read_u16()?;
+        let language = reader.read_u16()?;
+        let first_code = reader.read_u16()?;
+        let entry_count = reader.read_u16()?;
+        Ok(Self {
+            format,
+            length,
+            language,
+            first_code,
+            entry_count,
+            glyph_id_array: read_u16_array(reader, entry_count as usize)?,
+        })
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for SequentialMapGroup {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        Ok(Self {
+            start_char_code: reader.read_u32()?,
+            end_char_code: reader.read_u32()?,
+            start_glyph_id: reader.read_u32()?,
+        })
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for Mixed16And32BitCoverage {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let format = reader.read_u16()?;
+        let reserved = reader.read_u16()?;
+        let length = reader.read_u32()?;
+        let language = reader.read_u32()?;
+        let offset = reader.position();
+        let is32 = reader
+            .read_bytes_expected(8192)?
+            .try_into()
+            .map_err(|bytes: Vec<u8>| {
+                IOError::unexpected("is32", "8192 bytes", bytes.len(), offset)
+            })?;
+        let num_groups = reader.read_u32()?;
+        let groups = read_groups(reader, num_groups)?;
+        check_groups(&groups, 8208)?;
+        Ok(Self {
+            format,
+            reserved,
+            length,
+            language,
+            is32,
+            num_groups,
+            groups,
+        })
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for TrimmedArray {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let format = reader.read_u16()?;
+        let reserved = reader.read_u16()?;
+        let length = reader.read_u32()?;
+        let language = reader.read_u32()?;
+        let start_char_code = reader.read_u32()?;
+        let num_chars = reader.read_u32()?;
+        // 最后一个字符代码不能超出 `u32` 的范围
+        if num_chars > 0 && start_char_code.checked_add(num_chars - 1).is_none() {
+            return Err(IOError::unexpected(
+                "numChars",
+                format!("<= 0x{:X}", u32::MAX - start_char_code + 1),
+                format!("0x{:X}", num_chars),
+                16,
+            ));
+        }
+        Ok(Self {
+            format,
+            reserved,
+            length,
+            language,
+            start_char_code,
+            num_chars,
+            glyph_id_array: read_u16_array(reader, num_chars as usize)?,
+        })
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for SegmentedCoverage {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let format = reader.read_u16()?;
+        let reserved = reader.read_u16()?;
+        let length = reader.read_u32()?;
+        let language = reader.read_u32()?;
+        let num_groups = reader.read_u32()?;
+        let groups = read_groups(reader, num_groups)?;
+        check_groups(&groups, 16)?;
+        Ok(Self {
+            format,
+            reserved,
+            length,
+            language,
+            num_groups,
+            groups,
+        })
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for ManyToOneRangeMappings {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let format = reader.read_u16()?;
+        let reserved = reader.read_u16()?;
+        let length = reader.read_u32()?;
+        let language = reader.read_u32()?;
+        let num_groups = reader.read_u32()?;
+        let groups = read_groups(reader, num_groups)?;
+        check_groups(&groups, 16)?;
+        Ok(Self {
+            format,
+            reserved,
+            length,
+            language,
+            num_groups,
+            groups,
+        })
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for UnicodeVariationSequences {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let format = reader.read_u16()?;
+        let length = reader.read_u32()?;
+        let num_var_selector_records = reader.read_u32()?;
+        let mut records =
+            Vec::with_capacity((num_var_selector_records as usize).min(reader.remaining() / 11));
+        for i in 0..num_var_selector_records as usize {
+            let var_selector = reader.read_u24()?;
+            if let Some(&(prev, _, _)) = records.last()
+                && var_selector <= prev
+            {
+                return Err(IOError::unexpected(
+                    &format!("varSelector[{}]", i),
+                    format!("> 0x{:X}", prev.value()),
+                    format!("0x{:X}", var_selector.value()),
+                    10 + 11 * i,
+                ));
+            }
+            records.push((var_selector, reader.read_u32()?, reader.read_u32()?));
+        }
+        let mut var_selector = Vec::with_capacity(records.len());
+        for (i, (selector, default_uvs_offset, non_default_uvs_offset)) in
+            records.into_iter().enumerate()
+        {
+            let default_uvs = match default_uvs_offset as usize {
+                0 => None,
+                offset => Some(
+                    reader
+                        .sub_reader_from(offset)
+                        .and_then(|mut reader| {
+                            let default_uvs = DefaultUVSTable::read_from(&mut reader);
+                            default_uvs.at(reader.position())
+                        })
+                        .context(format!("varSelector[{}]/defaultUVS", i), offset)?,
+                ),
+            };
+            let non_default_uvs = match non_default_uvs_offset as usize {
+                0 => None,
+                offset => Some(
+                    reader
+                        .sub_reader_from(offset)
+                        .and_then(|mut reader| {
+                            let non_default_uvs = NonDefaultUVSTable::read_from(&mut reader);
+                            non_default_uvs.at(reader.position())
+                        })
+                        .context(format!("varSelector[{}]/nonDefaultUVS", i), offset)?,
+                ),
+            };
+            var_selector.push(VariationSelector {
+                var_selector: selector,
+                default_uvs_offset,
+                non_default_uvs_offset,
+                default_uvs,
+                non_default_uvs,
+            });
+        }
+        Ok(Self {
+            format,
+            length,
+            num_var_selector_records,
+            var_selector,
+        })
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for DefaultUVSTable {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let num_unicode_value_ranges = reader.read_u32()?;
+        let mut ranges: Vec<UnicodeRange> =
+            Vec::with_capacity((num_unicode_value_ranges as usize).min(reader.remaining() / 4));
+        for i in 0..num_unicode_value_ranges as usize {
+            let range = UnicodeRange {
+                start_unicode_value: reader.read_u24()?,
+                additional_count: reader.read_u8()?,
+            };
+            if let Some(prev) = ranges.last()
+                && range.start_unicode_value.value() <= prev.end_unicode_value()
+            {
+                return Err(IOError::unexpected(
+                    &format!("ranges[{}]/startUnicodeValue", i),
+                    format!("> 0x{:X}", prev.end_unicode_value()),
+                    format!("0x{:X}", range.start_unicode_value.value()),
+                    4 + 4 * i,
+                ));
+            }
+            ranges.push(range);
+        }
+        Ok(Self {
+            num_unicode_value_ranges,
+            ranges,
+        })
+    }
+}
+
+impl UnicodeRange {
+    pub fn start_unicode_value(&self) -> u32 {
+        self.start_unicode_value.value()
+    }
+
+    pub fn additional_count(&self) -> u8 {
+        self.additional_count
+    }
+
+    /// 此范围内的最后一个值
+    pub fn end_unicode_value(&self) -> u32 {
+        self.start_unicode_value.value() + self.additional_count as u32
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for NonDefaultUVSTable {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let num_uvs_mappings = reader.read_u32()?;
+        let mut uvs_mappings: Vec<UVSMapping> =
+            Vec::with_capacity((num_uvs_mappings as usize).min(reader.remaining() / 5));
+        for i in 0..num_uvs_mappings as usize {
+            let mapping = UVSMapping {
+                unicode_value: reader.read_u24()?,
+                glyph_id: reader.read_u16()?,
+            };
+            if let Some(prev) = uvs_mappings.last()
+                && mapping.unicode_value <= prev.unicode_value
+            {
+                return Err(IOError::unexpected(
+                    &format!("uvsMappings[{}]/unicodeValue", i),
+                    format!("> 0x{:X}", prev.unicode_value.value()),
+                    format!("0x{:X}", mapping.unicode_value.value()),
+                    4 + 5 * i,
+                ));
+            }
+            uvs_mappings.push(mapping);
+        }
+        Ok(Self {
+            num_uvs_mappings,
+            uvs_mappings,
+        })
+    }
+}
+
+/// 编码记录的子表偏移量重新计算，共享的子表只写入一次
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Cmap {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(self.version)?;
+        writer.write(self.encoding_records.len() as u16)?;
+        let mut positions = Vec::with_capacity(self.encoding_records.len());
+        for record in &self.encoding_records {
+            writer.write(record.platform_id)?;
+            writer.write(record.encoding_id)?;
+            positions.push(writer.reserve(4)?);
+        }
+        let mut written: Vec<(&Rc<CmapSubTable>, u32)> = vec![];
+        for (record, pos) in self.encoding_records.iter().zip(positions) {
+            let offset = match written
+                .iter()
+                .find(|(subtable, _)| Rc::ptr_eq(subtable, &record.subtable))
+            {
+                Some(&(_, offset)) => offset,
+                None => {
+                    let offset = (writer.position() - start) as u32;
+                    record.subtable.write_to(writer)?;
+                    written.push((&record.subtable, offset));
+                    offset
+                }
+            };
+            writer.patch_at(pos, offset)?;
+        }
+        Ok(writer.position() - start)
+    }
+}
+
+/// 子表的长度和数量字段根据数据重新计算
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for CmapSubTable {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        match self {
+            CmapSubTable::Format0(t) => {
+                writer.write(0u16)?;
+                writer.write(262u16)?;
+                writer.write(t.language)?;
+                writer.write(t.glyph_id_array.as_slice())?;
+            }
+            CmapSubTable::Format2(t) => {
+                let length = 518 + 8 * t.sub_headers.len() + 2 * t.glyph_id_array.len();
+                writer.write(2u16)?;
+                writer.write(subtable_length16(length)?)?;
+                writer.write(t.language)?;
+                for &key in &t.sub_header_keys {
+                    writer.write(key)?;
+                }
+                for sub_header in &t.sub_headers {
+                    writer.write(sub_header.first_code)?;
+                    writer.write(sub_header.entry_count)?;
+                    writer.write(sub_header.id_delta)?;
+                    writer.write(sub_header.id_range_offset)?;
+                }
+                write_u16_array(writer, &t.glyph_id_array)?;
+            }
+            CmapSubTable::Format4(t) => {
+                let seg_count = t.seg_count() as u16;
+                let length = 16 + 8 * t.seg_count() + 2 * t.glyph_id_array.len();
+                let (search_range, entry_selector, range_shift) = search_params(seg_count);
+                writer.write(4u16)?;
+                writer.write(subtable_length16(length)?)?;
+                writer.write(t.language)?;
+                writer.write(seg_count * 2)?;
+                writer.write(search_range)?;
+                writer.write(entry_selector)?;
+                writer.write(range_shift)?;
+                write_u16_array(writer, &t.end_code)?;
+                writer.write(t.reserved_pad)?;
+                write_u16_array(writer, &t.start_code)?;
+                write_u16_array(writer, &t.id_delta)?;
+                write_u16_array(writer, &t.id_range_offset)?;
+                write_u16_array(writer, &t.glyph_id_array)?;
+            }
+            CmapSubTable::Format6(t) => {
+                writer.write(6u16)?;
+                writer.write(subtable_length16(10 + 2 * t.glyph_id_array.len())?)?;
+                writer.write(t.language)?;
+                writer.write(t.first_code)?;
+                writer.write(t.glyph_id_array.len() as u16)?;
+                write_u16_array(writer, &t.glyph_id_array)?;
+            }
+            CmapSubTable::Format8(t) => {
+                writer.write(8u16)?;
+                writer.write(t.reserved)?;
+                writer.write((8208 + 12 * t.groups.len()) as u32)?;
+                writer.write(t.language)?;
+                writer.write(t.is32.as_slice())?;
+                write_groups(writer, &t.groups)?;
+            }
+            CmapSubTable::Format10(t) => {
+                writer.write(10u16)?;
+                writer.write(t.reserved)?;
+                writer.write((20 + 2 * t.glyph_id_array.len()) as u32)?;
+                writer.write(t.language)?;
+                writer.write(t.start_char_code)?;
+                writer.write(t.glyph_id_array.len() as u32)?;
+                write_u16_array(writer, &t.glyph_id_array)?;
+            }
+            CmapSubTable::Format12(t) => {
+                writer.write(12u16)?;
+                writer.write(t.reserved)?;
+                writer.write((16 + 12 * t.groups.len()) as u32)?;
+                writer.write(t.language)?;
+                write_groups(writer, &t.groups)?;
+            }
+            CmapSubTable::Format13(t) => {
+                writer.write(13u16)?;
+                writer.write(t.reserved)?;
+                writer.write((16 + 12 * t.groups.len()) as u32)?;
+                writer.write(t.language)?;
+                write_groups(writer, &t.groups)?;
+            }
+            CmapSubTable::Format14(t) => t.write_to(writer).map(|_| ())?,
+        }
+        Ok(writer.position() - start)
+    }
+}
+
+/// 变体选择器记录之后依次写入每个记录的默认 UVS 表和非默认 UVS 表
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for UnicodeVariationSequences {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(14u16)?;
+        let length = writer.reserve(4)?;
+        writer.write(self.var_selector.len() as u32)?;
+        let mut positions = Vec::with_capacity(self.var_selector.len());
+        for record in &self.var_selector {
+            writer.write(record.var_selector)?;
+            positions.push(writer.reserve(8)?);
+        }
+        for (record, pos) in self.var_selector.iter().zip(positions) {
+            if let Some(table) = &record.default_uvs {
+                writer.patch_at(pos, (writer.position() - start) as u32)?;
+                writer.write(table.ranges.len() as u32)?;
+                for range in &table.ranges {
+                    writer.write(range.start_unicode_value)?;
+                    writer.write(range.additional_count)?;
+                }
+            }
+            if let Some(table) = &record.non_default_uvs {
+                writer.patch_at(pos + 4, (writer.position() - start) as u32)?;
+                writer.write(table.uvs_mappings.len() as u32)?;
+                for mapping in &table.uvs_mappings {
+                    writer.write(mapping.unicode_value)?;
+                    writer.write(mapping.glyph_id)?;
+                }
+            }
+        }
+        writer.patch_at(length, (writer.position() - start) as u32)?;
+        Ok(writer.position() - start)
+    }
+}
+
+/// 格式 0 到 6 的长度字段只有 16 位
+#[cfg(feature = "writer")]
+fn subtable_length16(length: usize) -> Result<u16, IOError> {
+    u16::try_from(length).map_err(|_| {
+        IOError::UnableOperate(format!("cmap subtable length {} exceeds 65535", length))
+    })
+}
+
+#[cfg(feature = "writer")]
+fn write_u16_array(writer: &mut dyn Writer, array: &[u16]) -> Result<(), IOError> {
+    for &value in array {
+        writer.write(value)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "writer")]
+fn write_groups(writer: &mut dyn Writer, groups: &[SequentialMapGroup]) -> Result<(), IOError> {
+    writer.write(groups.len() as u32)?;
+    for group in groups {
+        writer.write(group.start_char_code)?;
+        writer.write(group.end_char_code)?;
+        writer.write(group.start_glyph_id)?;
+    }
+    Ok(())
+}
@@ -1,8 +1,43 @@
 use crate::font::FWord;
+use crate::font::io::ReadWith;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 pub struct Cvt {
     data: Vec<FWord>,
 }
 
 impl_named!(Cvt, "cvt");
+
+impl Cvt {
+    pub fn data(&self) -> &Vec<FWord> {
+        &self.data
+    }
+}
+
+/// 参数为表长度
+impl ReadWith<ReaderBoxed<'_>, usize> for Cvt {
+    fn read_with(reader: &mut ReaderBoxed, length: usize) -> Result<Self, IOError> {
+        let mut data = Vec::with_capacity(length / 2);
+        for _ in 0..length / 2 {
+            data.push(reader.read_i16()?);
+        }
+        Ok(Self { data })
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Cvt {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        for &v in &self.data {
+            writer.write(v)?;
+        }
+        Ok(writer.position() - start)
+    }
+}
@@ -1,28 +1,293 @@
 use crate::font::Offset32;
+use crate::font::io::ReadFrom;
 use crate::impl_named;
+use crate::io::error::{ErrorContext, IOError};
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
+/// 数字签名表，对字体的任何修改都会使签名失效
 pub struct DSIG {
+    /// 1
     version: u32,
     num_signatures: u16,
+    /// 位 0：不能重新签名
     flags: u16,
     signature_records: Vec<SignatureRecord>,
+    /// 与 `signature_records` 一一对应
+    signature_blocks: Vec<SignatureBlock>,
 }
 
 impl_named!(DSIG, "DSIG");
 
 pub struct SignatureRecord {
+    /// 签名块格式，目前只定义了 `1`
     format: u32,
     length: u32,
+    /// 相对于 `DSIG` 表开头
     signature_block_offset: Offset32,
 }
 
 pub enum SignatureBlock {
     Format1(SignatureBlockFormat1),
+    /// 未知格式，保留原始数据
+    Unknown(Vec<u8>),
 }
 
 pub struct SignatureBlockFormat1 {
     reserved1: u16,
     reserved2: u16,
     signature_length: u32,
+    /// `PKCS#7` 数据
     signature: Vec<u8>,
 }
+
+/// 签名中摘要使用的算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+}
+
+impl DSIG {
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn num_signatures(&self) -> u16 {
+        self.num_signatures
+    }
+
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+
+    /// `flags` 位 0，签名者禁止重新签名
+    pub fn cannot_be_resigned(&self) -> bool {
+        self.flags & 1 != 0
+    }
+
+    pub fn signature_records(&self) -> &Vec<SignatureRecord> {
+        &self.signature_records
+    }
+
+    pub fn signature_blocks(&self) -> &Vec<SignatureBlock> {
+        &self.signature_blocks
+    }
+
+    /// 所有格式 1 签名块中的 `PKCS#7` 数据
+    pub fn pkcs7_blobs(&self) -> Vec<&[u8]> {
+        self.signature_blocks
+            .iter()
+            .filter_map(|block| match block {
+                SignatureBlock::Format1(block) => Some(block.signature.as_slice()),
+                SignatureBlock::Unknown(_) => None,
+            })
+            .collect()
+    }
+
+    /// 从 `PKCS#7` 数据中找出的签名内容摘要
+    pub fn digests(&self) -> Vec<(DigestAlgorithm, &[u8])> {
+        self.pkcs7_blobs()
+            .into_iter()
+            .flat_map(find_digests)
+            .collect()
+    }
+}
+
+impl SignatureRecord {
+    pub fn format(&self) -> u32 {
+        self.format
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    pub fn signature_block_offset(&self) -> Offset32 {
+        self.signature_block_offset
+    }
+}
+
+impl SignatureBlockFormat1 {
+    pub fn signature_length(&self) -> u32 {
+        self.signature_length
+    }
+
+    pub fn signature(&self) -> &Vec<u8> {
+        &self.signature
+    }
+}
+
+/// 数字签名与当前字体数据的关系
+#[cfg(feature = "dsig")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureCoverage {
+    /// 没有 `DSIG` 表，或 `DSIG` 中没有签名
+    Unsigned,
+    /// 签名中的摘要与字体数据一致
+    Covered,
+    /// 签名中的摘要与字体数据不一致，签名后字体被修改过
+    Stale,
+    /// 签名中没有可识别的摘要，或摘要算法不支持（如 `MD5`）
+    Unknown,
+}
+
+#[cfg(feature = "dsig")]
+impl DSIG {
+    /// 检查签名中的摘要是否与 `data` 一致，`data` 为去除签名后的字体或字体集合数据
+    pub fn coverage(&self, data: &[u8]) -> SignatureCoverage {
+        use sha1::Digest;
+
+        if self.signature_blocks.is_empty() {
+            return SignatureCoverage::Unsigned;
+        }
+        let mut result = SignatureCoverage::Unknown;
+        for (algorithm, digest) in self.digests() {
+            let computed = match algorithm {
+                DigestAlgorithm::Md5 => continue,
+                DigestAlgorithm::Sha1 => sha1::Sha1::digest(data).to_vec(),
+                DigestAlgorithm::Sha256 => sha2::Sha256::digest(data).to_vec(),
+            };
+            if computed == digest {
+                return SignatureCoverage::Covered;
+            }
+            result = SignatureCoverage::Stale;
+        }
+        result
+    }
+}
+
+/// `DER` 编码的摘要算法 OID
+const DIGEST_OIDS: [(DigestAlgorithm, &[u8], usize); 3] = [
+    (
+        DigestAlgorithm::Md5,
+        &[0x06, 0x08, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x02, 0x05],
+        16,
+    ),
+    (
+        DigestAlgorithm::Sha1,
+        &[0x06, 0x05, 0x2B, 0x0E, 0x03, 0x02, 0x1A],
+        20,
+    ),
+    (
+        DigestAlgorithm::Sha256,
+        &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01],
+        32,
+    ),
+];
+
+/// 查找 `DigestInfo`：算法 OID 之后（可能有 `NULL` 参数）紧跟长度与算法一致的 `OCTET STRING`
+fn find_digests(der: &[u8]) -> Vec<(DigestAlgorithm, &[u8])> {
+    let mut digests = vec![];
+    for (algorithm, oid, len) in DIGEST_OIDS {
+        let mut start = 0;
+        while let Some(pos) = der[start..].windows(oid.len()).position(|w| w == oid) {
+            let mut i = start + pos + oid.len();
+            if der[i..].starts_with(&[0x05, 0x00]) {
+                i += 2;
+            }
+            if der[i..].starts_with(&[0x04, len as u8]) && der.len() >= i + 2 + len {
+                digests.push((algorithm, &der[i + 2..i + 2 + len]));
+            }
+            start += pos + 1;
+        }
+    }
+    digests
+}
+
+impl SignatureBlock {
+    /// 读取 `record` 指向的签名块，`reader` 从签名块开头开始
+    fn read(reader: &mut ReaderBoxed, record: &SignatureRecord) -> Result<Self, IOError> {
+        match record.format {
+            1 => {
+                let reserved1 = reader.read_u16()?;
+                let reserved2 = reader.read_u16()?;
+                let signature_length = reader.read_u32()?;
+                Ok(SignatureBlock::Format1(SignatureBlockFormat1 {
+                    reserved1,
+                    reserved2,
+                    signature_length,
+                    signature: reader.read_bytes_expected(signature_length as usize)?,
+                }))
+            }
+            _ => Ok(SignatureBlock::Unknown(
+                reader.read_bytes_expected(record.length as usize)?,
+            )),
+        }
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for DSIG {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let version = reader.read_u32()?;
+        let num_signatures = reader.read_u16()?;
+        let flags = reader.read_u16()?;
+        let mut signature_records = Vec::with_capacity(num_signatures as usize);
+        for _ in 0..num_signatures {
+            signature_records.push(SignatureRecord {
+                format: reader.read_u32()?,
+                length: reader.read_u32()?,
+                signature_block_offset: reader.read_u32()?,
+            });
+        }
+        let mut signature_blocks = Vec::with_capacity(num_signatures as usize);
+        for (i, record) in signature_records.iter().enumerate() {
+            let offset = record.signature_block_offset as usize;
+            let block = reader
+                .sub_reader(offset, record.length as usize)
+                .and_then(|mut reader| {
+                    let block = SignatureBlock::read(&mut reader, record);
+                    block.at(reader.position())
+                })
+                .context(format!("signatureRecords[{}]", i), offset)?;
+            signature_blocks.push(block);
+        }
+        Ok(Self {
+            version,
+            num_signatures,
+            flags,
+            signature_records,
+            signature_blocks,
+        })
+    }
+}
+
+/// 签名块紧跟签名记录依次写入，`signature_block_offset` 和 `length` 重新计算
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for DSIG {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(self.version)?;
+        writer.write(self.signature_blocks.len() as u16)?;
+        writer.write(self.flags)?;
+        let mut offset = 8 + 12 * self.signature_blocks.len();
+        for (record, block) in self.signature_records.iter().zip(&self.signature_blocks) {
+            let length = match block {
+                SignatureBlock::Format1(block) => 8 + block.signature.len(),
+                SignatureBlock::Unknown(data) => data.len(),
+            };
+            writer.write(record.format)?;
+            writer.write(length as u32)?;
+            writer.write(offset as u32)?;
+            offset += length;
+        }
+        for block in &self.signature_blocks {
+            match block {
+                SignatureBlock::Format1(block) => {
+                    writer.write(block.reserved1)?;
+                    writer.write(block.reserved2)?;
+                    writer.write(block.signature.len() as u32)?;
+                    writer.write(&block.signature)?;
+                }
+                SignatureBlock::Unknown(data) => {
+                    writer.write(data)?;
+                }
+            }
+        }
+        Ok(writer.position() - start)
+    }
+}
@@ -1,4 +1,11 @@
+use crate::font::io::ReadFrom;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 pub struct Gasp {
     head: GaspHeader,
@@ -17,3 +24,62 @@ pub struct GaspRange {
     range_max_ppem: u16,
     range_gasp_behavior: u16,
 }
+
+impl Gasp {
+    pub fn version(&self) -> u16 {
+        self.head.version
+    }
+
+    pub fn num_ranges(&self) -> u16 {
+        self.head.num_ranges
+    }
+
+    pub fn gasp_ranges(&self) -> &Vec<GaspRange> {
+        &self.head.gasp_ranges
+    }
+}
+
+impl GaspRange {
+    pub fn range_max_ppem(&self) -> u16 {
+        self.range_max_ppem
+    }
+
+    pub fn range_gasp_behavior(&self) -> u16 {
+        self.range_gasp_behavior
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for Gasp {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let version = reader.read_u16()?;
+        let num_ranges = reader.read_u16()?;
+        let mut gasp_ranges = Vec::with_capacity(num_ranges as usize);
+        for _ in 0..num_ranges {
+            gasp_ranges.push(GaspRange {
+                range_max_ppem: reader.read_u16()?,
+                range_gasp_behavior: reader.read_u16()?,
+            });
+        }
+        Ok(Self {
+            head: GaspHeader {
+                version,
+                num_ranges,
+                gasp_ranges,
+            },
+        })
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Gasp {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(self.head.version)?;
+        writer.write(self.head.gasp_ranges.len() as u16)?;
+        for range in &self.head.gasp_ranges {
+            writer.write(range.range_max_ppem)?;
+            writer.write(range.range_gasp_behavior)?;
+        }
+        Ok(writer.position() - start)
+    }
+}
@@ -1,11 +1,47 @@
+use super::loca::{Loca, LocaRef};
+use crate::font::GlyphId;
+use crate::font::io::ReadWith;
 use crate::impl_named;
+use crate::io::error::{ErrorContext, IOError};
+use crate::io::reader::{Reader, ReaderBoxed};
+use crate::io::slice_reader::SliceReader;
+use crate::types::{F2D14, Tag};
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
+use std::cell::OnceCell;
+use std::ops::Range;
 
+/// # glyf — 字形数据表
+///
+/// 包含 `TrueType` 轮廓字形的数据，每个字形的位置由 [Loca] 给出。
+/// 字形分为简单字形和复合字形：简单字形由若干条二次 `B` 样条轮廓组成，
+/// 复合字形由其他字形经变换组合而成。
 pub struct Glyf {
+    /// 表的原始数据，写入时原样写入
+    data: Vec<u8>,
+    /// 表在文件中的偏移量，用于错误的位置信息
+    table_offset: usize,
+    /// 每个字形在 `data` 中的范围，来自 `loca`，访问字形时才检查
+    ranges: Vec<Range<usize>>,
+    /// 首次访问时解码的字形，没有轮廓的字形（如空格）为 `Ok(None)`，
+    /// 无法解码的字形为 `Err`，不影响其他字形
+    glyphs: Vec<OnceCell<Result<Option<Glyph>, IOError>>>,
+}
+
+impl_named!(Glyf, "glyf");
+
+/// `maxp` 缺失或为版本 0.5 时复合字形的最大嵌套层数
+pub const DEFAULT_MAX_COMPONENT_DEPTH: u16 = 16;
+
+pub struct Glyph {
     header: GlyfHeader,
     glyph_description: GlyfGlyphDescription,
 }
 
 pub struct GlyfHeader {
+    /// 非负为简单字形的轮廓数，负数表示复合字形
     number_of_contours: i16,
     x_min: i16,
     y_min: i16,
@@ -13,30 +49,827 @@ pub struct GlyfHeader {
     y_max: i16,
 }
 
-impl_named!(Glyf, "glyf");
-
 pub enum GlyfGlyphDescription {
     Format1(SimpleGlyph),
     Format2(CompositeGlyph),
 }
 
+/// 简单字形的点标志
+pub mod simple_glyph_flags {
+    /// 点在曲线上，否则为二次曲线的控制点
+    pub const ON_CURVE_POINT: u8 = 0x01;
+    /// `x` 坐标为 1 字节，符号由 [X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR] 决定
+    pub const X_SHORT_VECTOR: u8 = 0x02;
+    /// `y` 坐标为 1 字节，符号由 [Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR] 决定
+    pub const Y_SHORT_VECTOR: u8 = 0x04;
+    /// 下一个字节为此标志额外重复的次数
+    pub const REPEAT_FLAG: u8 = 0x08;
+    /// 设置 [X_SHORT_VECTOR] 时表示正数，否则表示 `x` 与上一个点相同
+    pub const X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR: u8 = 0x10;
+    /// 设置 [Y_SHORT_VECTOR] 时表示正数，否则表示 `y` 与上一个点相同
+    pub const Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR: u8 = 0x20;
+    /// 轮廓可能重叠，只在第一个点上设置
+    pub const OVERLAP_SIMPLE: u8 = 0x40;
+}
+
 pub struct SimpleGlyph {
+    /// 每个轮廓最后一个点的索引，递增
     end_pts_of_contours: Vec<u16>,
     instruction_length: u16,
     instructions: Vec<u8>,
+    /// 展开重复后每个点的标志
     flags: Vec<u8>,
-    /// u8 or i16
-    x_coordinates_data: Vec<u8>,
-    /// u8 or i16
-    y_coordinates_data: Vec<u8>,
+    /// 由坐标增量累加得到的绝对坐标
+    points: Vec<GlyphPoint>,
+}
+
+/// 字形轮廓上的点
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GlyphPoint {
+    x: i16,
+    y: i16,
+    on_curve: bool,
+}
+
+/// 复合字形的组件标志
+pub mod composite_glyph_flags {
+    /// 参数为 16 位，否则为 8 位
+    pub const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    /// 参数为有符号的 `x`、`y` 偏移量，否则为无符号的点索引
+    pub const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+    /// 网格拟合时偏移量取整到网格
+    pub const ROUND_XY_TO_GRID: u16 = 0x0004;
+    /// `x`、`y` 使用同一个缩放比例
+    pub const WE_HAVE_A_SCALE: u16 = 0x0008;
+    /// 后面还有组件
+    pub const MORE_COMPONENTS: u16 = 0x0020;
+    /// `x`、`y` 分别缩放
+    pub const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    /// 使用 2×2 变换矩阵
+    pub const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+    /// 最后一个组件之后是复合字形的指令
+    pub const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+    /// 复合字形使用此组件的水平度量
+    pub const USE_MY_METRICS: u16 = 0x0200;
+    /// 组件可能重叠，只在第一个组件上设置
+    pub const OVERLAP_COMPOUND: u16 = 0x0400;
+    /// 偏移量也经过变换
+    pub const SCALED_COMPONENT_OFFSET: u16 = 0x0800;
+    /// 偏移量不经过变换
+    pub const UNSCALED_COMPONENT_OFFSET: u16 = 0x1000;
 }
 
 pub struct CompositeGlyph {
+    components: Vec<Component>,
+    /// 设置了 [composite_glyph_flags::WE_HAVE_INSTRUCTIONS] 时复合字形的指令
+    instructions: Vec<u8>,
+}
+
+/// 复合字形的组件，引用另一个字形并经过变换和平移
+pub struct Component {
     flags: u16,
-    glyph_index: u16,
-    /// u8 or i8 or u16 or i16
-    argument1_data: Vec<u8>,
-    /// u8 or i8 or u16 or i16
-    argument2_data: Vec<u8>,
-    data: Vec<u8>,
+    glyph_index: GlyphId,
+    placement: ComponentPlacement,
+    transform: ComponentTransform,
+}
+
+/// 组件的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentPlacement {
+    /// 组件的偏移量
+    Offset { x: i16, y: i16 },
+    /// 平移组件使其第 `child` 个点与复合字形中已放置的第 `parent` 个点重合
+    Anchor { parent: u16, child: u16 },
+}
+
+/// 组件的线性变换
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComponentTransform {
+    None,
+    Scale(F2D14),
+    XYScale {
+        x_scale: F2D14,
+        y_scale: F2D14,
+    },
+    /// `x' = x_scale * x + scale10 * y`，`y' = scale01 * x + y_scale * y`
+    TwoByTwo {
+        x_scale: F2D14,
+        scale01: F2D14,
+        scale10: F2D14,
+        y_scale: F2D14,
+    },
+}
+
+impl Glyf {
+    /// 字形数量
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// 获取字形，首次访问时解码并缓存
+    ///
+    /// 没有轮廓或超出范围时返回 `None`，`loca` 范围有误或字形无法解码时返回错误，
+    /// 错误的偏移量为相对于文件开头的偏移量。
+    pub fn glyph(&self, glyph_id: GlyphId) -> Result<Option<&Glyph>, IOError> {
+        let Some(cell) = self.glyphs.get(glyph_id as usize) else {
+            return Ok(None);
+        };
+        match cell.get_or_init(|| self.decode(glyph_id)) {
+            Ok(glyph) => Ok(glyph.as_ref()),
+            Err(e) => Err(e.clone()),
+        }
+    }
+
+    fn decode(&self, glyph_id: GlyphId) -> Result<Option<Glyph>, IOError> {
+        let range = self.ranges[glyph_id as usize].clone();
+        let glyph = match self.glyph_data(glyph_id) {
+            Some([]) => Ok(None),
+            Some(data) => Glyph::parse(data).map(Some),
+            None if range.start > range.end => Err(IOError::unexpected(
+                &format!("loca[{}]", glyph_id as usize + 1),
+                format!(">= 0x{:X}", range.start),
+                format!("0x{:X}", range.end),
+                0,
+            )),
+            None => Err(IOError::NoEnoughData),
+        };
+        glyph
+            .context(format!("glyph[{}]", glyph_id), range.start)
+            .in_table(&Tag::from(*b"glyf"), self.table_offset)
+    }
+
+    /// 第 `glyph_id` 个字形在 `loca` 范围内的原始数据，范围有误时返回 `None`
+    pub fn glyph_data(&self, glyph_id: GlyphId) -> Option<&[u8]> {
+        self.data.get(self.ranges.get(glyph_id as usize)?.clone())
+    }
+
+    /// 把字形展开为绝对坐标的轮廓
+    ///
+    /// 复合字形的组件经变换后按偏移量或对齐点放置，嵌套的复合字形递归展开，
+    /// 变换后的坐标四舍五入为整数。[composite_glyph_flags::ROUND_XY_TO_GRID] 只在网格拟合时生效，这里不处理。
+    ///
+    /// `max_depth` 为复合字形的最大嵌套层数（`maxp.max_component_depth`），
+    /// 只引用简单字形的复合字形为 1 层。组件循环引用、嵌套过深、
+    /// 引用不存在或无法解码的字形、对齐点超出范围时返回错误。
+    pub fn outline(
+        &self,
+        glyph_id: GlyphId,
+        max_depth: u16,
+    ) -> Result<Vec<Vec<GlyphPoint>>, IOError> {
+        self.flatten(glyph_id, max_depth, &mut vec![])
+    }
+
+    /// `path` 为正在展开的复合字形
+    fn flatten(
+        &self,
+        glyph_id: GlyphId,
+        max_depth: u16,
+        path: &mut Vec<GlyphId>,
+    ) -> Result<Vec<Vec<GlyphPoint>>, IOError> {
+        if glyph_id as usize >= self.len() {
+            return Err(IOError::UnableOperate(format!(
+                "glyph {} out of range 0..{}",
+                glyph_id,
+                self.len()
+            )));
+        }
+        let composite = match self.glyph(glyph_id)?.map(Glyph::glyph_description) {
+            None => return Ok(vec![]),
+            Some(GlyfGlyphDescription::Format1(simple)) => {
+                return Ok(simple.contours().into_iter().map(<[_]>::to_vec).collect());
+            }
+            Some(GlyfGlyphDescription::Format2(composite)) => composite,
+        };
+        if path.contains(&glyph_id) {
+            return Err(IOError::UnableOperate(format!(
+                "component cycle {:?} -> {}",
+                path, glyph_id
+            )));
+        }
+        if path.len() >= max_depth as usize {
+            return Err(IOError::UnableOperate(format!(
+                "component depth of glyph {} exceeds {}",
+                path[0], max_depth
+            )));
+        }
+
+        path.push(glyph_id);
+        let mut contours: Vec<Vec<GlyphPoint>> = vec![];
+        for component in &composite.components {
+            let mut child = self.flatten(component.glyph_index, max_depth, path)?;
+            let transform = component.transform;
+            for point in child.iter_mut().flatten() {
+                (point.x, point.y) = transform.apply(point.x, point.y);
+            }
+            let (dx, dy) = match component.placement {
+                ComponentPlacement::Offset { x, y } if component.scaled_component_offset() => {
+                    transform.apply(x, y)
+                }
+                ComponentPlacement::Offset { x, y } => (x, y),
+                ComponentPlacement::Anchor {
+                    parent,
+                    child: point,
+                } => {
+                    let find = |contours: &[Vec<GlyphPoint>], index: u16| {
+                        contours
+                            .iter()
+                            .flatten()
+                            .nth(index as usize)
+                            .copied()
+                            .ok_or_else(|| {
+                                IOError::UnableOperate(format!(
+                                    "anchor point {} of glyph {} out of range",
+                                    index, glyph_id
+                                ))
+                            })
+                    };
+                    let parent = find(&contours, parent)?;
+                    let point = find(&child, point)?;
+                    (
+                        parent.x.wrapping_sub(point.x),
+                        parent.y.wrapping_sub(point.y),
+                    )
+                }
+            };
+            for point in child.iter_mut().flatten() {
+                point.x = point.x.wrapping_add(dx);
+                point.y = point.y.wrapping_add(dy);
+            }
+            contours.extend(child);
+        }
+        path.pop();
+        Ok(contours)
+    }
+}
+
+impl Glyph {
+    pub fn header(&self) -> &GlyfHeader {
+        &self.header
+    }
+
+    pub fn glyph_description(&self) -> &GlyfGlyphDescription {
+        &self.glyph_description
+    }
+
+    pub fn is_composite(&self) -> bool {
+        self.header.number_of_contours < 0
+    }
+
+    /// 简单字形的轮廓，复合字形为空，展开复合字形见 [Glyf::outline]
+    pub fn contours(&self) -> Vec<&[GlyphPoint]> {
+        match &self.glyph_description {
+            GlyfGlyphDescription::Format1(glyph) => glyph.contours(),
+            GlyfGlyphDescription::Format2(_) => vec![],
+        }
+    }
+
+    /// 从一个字形的数据开头读取
+    fn parse(data: &[u8]) -> Result<Self, IOError> {
+        let mut reader = SliceReader::new(data);
+        let (header, glyph_description) =
+            Self::parse_description(&mut reader).at(reader.position())?;
+        Ok(Self {
+            header,
+            glyph_description,
+        })
+    }
+
+    fn parse_description(
+        reader: &mut SliceReader<&[u8]>,
+    ) -> Result<(GlyfHeader, GlyfGlyphDescription), IOError> {
+        let header = GlyfHeader {
+            number_of_contours: reader.read_i16()?,
+            x_min: reader.read_i16()?,
+            y_min: reader.read_i16()?,
+            x_max: reader.read_i16()?,
+            y_max: reader.read_i16()?,
+        };
+        let glyph_description = match header.number_of_contours {
+            n if n >= 0 => GlyfGlyphDescription::Format1(SimpleGlyph::parse(reader, n as u16)?),
+            _ => GlyfGlyphDescription::Format2(CompositeGlyph::parse(reader)?),
+        };
+        Ok((header, glyph_description))
+    }
+}
+
+impl GlyfHeader {
+    pub fn number_of_contours(&self) -> i16 {
+        self.number_of_contours
+    }
+
+    pub fn x_min(&self) -> i16 {
+        self.x_min
+    }
+
+    pub fn y_min(&self) -> i16 {
+        self.y_min
+    }
+
+    pub fn x_max(&self) -> i16 {
+        self.x_max
+    }
+
+    pub fn y_max(&self) -> i16 {
+        self.y_max
+    }
+}
+
+impl SimpleGlyph {
+    pub fn end_pts_of_contours(&self) -> &Vec<u16> {
+        &self.end_pts_of_contours
+    }
+
+    pub fn instruction_length(&self) -> u16 {
+        self.instruction_length
+    }
+
+    pub fn instructions(&self) -> &Vec<u8> {
+        &self.instructions
+    }
+
+    pub fn flags(&self) -> &Vec<u8> {
+        &self.flags
+    }
+
+    pub fn points(&self) -> &Vec<GlyphPoint> {
+        &self.points
+    }
+
+    /// 按 `end_pts_of_contours` 划分的轮廓
+    pub fn contours(&self) -> Vec<&[GlyphPoint]> {
+        let mut start = 0;
+        self.end_pts_of_contours
+            .iter()
+            .map(|&end| {
+                let contour = &self.points[start..=end as usize];
+                start = end as usize + 1;
+                contour
+            })
+            .collect()
+    }
+
+    /// 第一个点设置了 [simple_glyph_flags::OVERLAP_SIMPLE]
+    pub fn overlap_simple(&self) -> bool {
+        self.flags
+            .first()
+            .is_some_and(|&flag| flag & simple_glyph_flags::OVERLAP_SIMPLE != 0)
+    }
+
+    fn parse(reader: &mut SliceReader<&[u8]>, number_of_contours: u16) -> Result<Self, IOError> {
+        use simple_glyph_flags::*;
+
+        let mut end_pts_of_contours = Vec::with_capacity(number_of_contours as usize);
+        for i in 0..number_of_contours as usize {
+            let offset = reader.position();
+            let end = reader.read_u16()?;
+            if let Some(&previous) = end_pts_of_contours.last()
+                && end <= previous
+            {
+                return Err(IOError::unexpected(
+                    &format!("endPtsOfContours[{}]", i),
+                    format!("> {}", previous),
+                    end,
+                    offset,
+                ));
+            }
+            end_pts_of_contours.push(end);
+        }
+        let instruction_length = reader.read_u16()?;
+        let instructions = reader.read_slice(instruction_length as usize)?.to_vec();
+
+        let num_points = end_pts_of_contours
+            .last()
+            .map_or(0, |&end| end as usize + 1);
+        let mut flags = Vec::with_capacity(num_points);
+        while flags.len() < num_points {
+            let offset = reader.position();
+            let flag = reader.read_u8()?;
+            let repeat = match flag & REPEAT_FLAG {
+                0 => 0,
+                _ => reader.read_u8()? as usize,
+            };
+            if flags.len() + 1 + repeat > num_points {
+                return Err(IOError::unexpected(
+                    "flags",
+                    format!("{} points", num_points),
+                    format!("{} points", flags.len() + 1 + repeat),
+                    offset,
+                ));
+            }
+            flags.extend(std::iter::repeat_n(flag, 1 + repeat));
+        }
+
+        let xs = read_coordinates(
+            reader,
+            &flags,
+            X_SHORT_VECTOR,
+            X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR,
+        )?;
+        let ys = read_coordinates(
+            reader,
+            &flags,
+            Y_SHORT_VECTOR,
+            Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR,
+        )?;
+        let points = flags
+            .iter()
+            .zip(xs.into_iter().zip(ys))
+            .map(|(&flag, (x, y))| GlyphPoint {
+                x,
+                y,
+                on_curve: flag & ON_CURVE_POINT != 0,
+            })
+            .collect();
+        Ok(Self {
+            end_pts_of_contours,
+            instruction_length,
+            instructions,
+            flags,
+            points,
+        })
+    }
+}
+
+/// 读取一个坐标轴上所有点的增量并累加为绝对坐标
+fn read_coordinates(
+    reader: &mut SliceReader<&[u8]>,
+    flags: &[u8],
+    short: u8,
+    same_or_positive: u8,
+) -> Result<Vec<i16>, IOError> {
+    let mut value = 0i16;
+    let mut coordinates = Vec::with_capacity(flags.len());
+    for &flag in flags {
+        let delta = match (flag & short != 0, flag & same_or_positive != 0) {
+            (true, true) => reader.read_u8()? as i16,
+            (true, false) => -(reader.read_u8()? as i16),
+            (false, true) => 0,
+            (false, false) => reader.read_i16()?,
+        };
+        value = value.wrapping_add(delta);
+        coordinates.push(value);
+    }
+    Ok(coordinates)
+}
+
+impl GlyphPoint {
+    pub fn new(x: i16, y: i16, on_curve: bool) -> Self {
+        Self { x, y, on_curve }
+    }
+
+    pub fn x(&self) -> i16 {
+        self.x
+    }
+
+    pub fn y(&self) -> i16 {
+        self.y
+    }
+
+    pub fn on_curve(&self) -> bool {
+        self.on_curve
+    }
+}
+
+impl CompositeGlyph {
+    pub fn components(&self) -> &Vec<Component> {
+        &self.components
+    }
+
+    pub fn instructions(&self) -> &Vec<u8> {
+        &self.instructions
+    }
+
+    /// 设置了 [composite_glyph_flags::USE_MY_METRICS] 的组件引用的字形
+    pub fn metrics_glyph(&self) -> Option<GlyphId> {
+        self.components
+            .iter()
+            .find(|component| component.use_my_metrics())
+            .map(|component| component.glyph_index)
+    }
+
+    /// 第一个组件设置了 [composite_glyph_flags::OVERLAP_COMPOUND]
+    pub fn overlap_compound(&self) -> bool {
+        self.components
+            .first()
+            .is_some_and(|component| component.flags & composite_glyph_flags::OVERLAP_COMPOUND != 0)
+    }
+
+    fn parse(reader: &mut SliceReader<&[u8]>) -> Result<Self, IOError> {
+        use composite_glyph_flags::*;
+
+        let mut components = vec![];
+        let mut have_instructions = false;
+        loop {
+            let offset = reader.position();
+            let component = Component::parse(reader)
+                .at(reader.position() - offset)
+                .context(format!("component[{}]", components.len()), offset)?;
+            let flags = component.flags;
+            have_instructions |= flags & WE_HAVE_INSTRUCTIONS != 0;
+            components.push(component);
+            if flags & MORE_COMPONENTS == 0 {
+                break;
+            }
+        }
+        let instructions = match have_instructions {
+            true => {
+                let len = reader.read_u16()?;
+                reader.read_slice(len as usize)?.to_vec()
+            }
+            false => vec![],
+        };
+        Ok(Self {
+            components,
+            instructions,
+        })
+    }
+}
+
+impl Component {
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+
+    pub fn glyph_index(&self) -> GlyphId {
+        self.glyph_index
+    }
+
+    pub fn placement(&self) -> ComponentPlacement {
+        self.placement
+    }
+
+    pub fn transform(&self) -> ComponentTransform {
+        self.transform
+    }
+
+    pub fn use_my_metrics(&self) -> bool {
+        self.flags & composite_glyph_flags::USE_MY_METRICS != 0
+    }
+
+    pub fn round_xy_to_grid(&self) -> bool {
+        self.flags & composite_glyph_flags::ROUND_XY_TO_GRID != 0
+    }
+
+    /// 偏移量是否经过变换，两个标志都没有设置时不变换
+    pub fn scaled_component_offset(&self) -> bool {
+        use composite_glyph_flags::*;
+        self.flags & (SCALED_COMPONENT_OFFSET | UNSCALED_COMPONENT_OFFSET)
+            == SCALED_COMPONENT_OFFSET
+    }
+
+    fn parse(reader: &mut SliceReader<&[u8]>) -> Result<Self, IOError> {
+        use composite_glyph_flags::*;
+
+        let flags = reader.read_u16()?;
+        let glyph_index = reader.read_u16()?;
+        let words = flags & ARG_1_AND_2_ARE_WORDS != 0;
+        let placement = match (words, flags & ARGS_ARE_XY_VALUES != 0) {
+            (true, true) => ComponentPlacement::Offset {
+                x: reader.read_i16()?,
+                y: reader.read_i16()?,
+            },
+            (false, true) => ComponentPlacement::Offset {
+                x: reader.read_i8()? as i16,
+                y: reader.read_i8()? as i16,
+            },
+            (true, false) => ComponentPlacement::Anchor {
+                parent: reader.read_u16()?,
+                child: reader.read_u16()?,
+            },
+            (false, false) => ComponentPlacement::Anchor {
+                parent: reader.read_u8()? as u16,
+                child: reader.read_u8()? as u16,
+            },
+        };
+        let mut read = || reader.read_i16().map(F2D14::from_bits);
+        let transform = if flags & WE_HAVE_A_SCALE != 0 {
+            ComponentTransform::Scale(read()?)
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            ComponentTransform::XYScale {
+                x_scale: read()?,
+                y_scale: read()?,
+            }
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            ComponentTransform::TwoByTwo {
+                x_scale: read()?,
+                scale01: read()?,
+                scale10: read()?,
+                y_scale: read()?,
+            }
+        } else {
+            ComponentTransform::None
+        };
+        Ok(Self {
+            flags,
+            glyph_index,
+            placement,
+            transform,
+        })
+    }
+}
+
+impl ComponentTransform {
+    /// 变换矩阵 `[xx, yx, xy, yy]`，`x' = xx * x + xy * y`，`y' = yx * x + yy * y`
+    pub fn matrix(&self) -> [f32; 4] {
+        match *self {
+            ComponentTransform::None => [1.0, 0.0, 0.0, 1.0],
+            ComponentTransform::Scale(scale) => [scale.to_f32(), 0.0, 0.0, scale.to_f32()],
+            ComponentTransform::XYScale { x_scale, y_scale } => {
+                [x_scale.to_f32(), 0.0, 0.0, y_scale.to_f32()]
+            }
+            ComponentTransform::TwoByTwo {
+                x_scale,
+                scale01,
+                scale10,
+                y_scale,
+            } => [
+                x_scale.to_f32(),
+                scale01.to_f32(),
+                scale10.to_f32(),
+                y_scale.to_f32(),
+            ],
+        }
+    }
+
+    /// 变换一个点，结果四舍五入为整数
+    pub fn apply(&self, x: i16, y: i16) -> (i16, i16) {
+        if let ComponentTransform::None = self {
+            return (x, y);
+        }
+        let [xx, yx, xy, yy] = self.matrix();
+        let (x, y) = (x as f32, y as f32);
+        (
+            (xx * x + xy * y).round() as i16,
+            (yx * x + yy * y).round() as i16,
+        )
+    }
+}
+
+/// 参数为 (`loca` 表, `glyf` 表在文件中的偏移量)
+///
+/// 只读取表的数据和每个字形的范围，字形在 [Glyf::glyph] 中才解码。
+impl ReadWith<ReaderBoxed<'_>, (&Loca, usize)> for Glyf {
+    fn read_with(reader: &mut ReaderBoxed, args: (&Loca, usize)) -> Result<Self, IOError> {
+        let (loca, table_offset) = args;
+        let data = reader.read_bytes_expected(reader.remaining())?;
+        let ranges: Vec<Range<usize>> = (0..loca.len().saturating_sub(1))
+            .map(|glyph_id| loca.glyph_range(glyph_id as GlyphId).unwrap_or_default())
+            .collect();
+        let glyphs = ranges.iter().map(|_| OnceCell::new()).collect();
+        Ok(Self {
+            data,
+            table_offset,
+            ranges,
+            glyphs,
+        })
+    }
+}
+
+/// 表的数据原样写入，与读取时的 `loca` 一致
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Glyf {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        writer.write(self.data.as_slice())
+    }
+}
+
+/// `glyf` 表的借用视图，字形数据在访问时才解码
+#[derive(Clone, Copy)]
+pub struct GlyfRef<'a> {
+    data: &'a [u8],
+    loca: LocaRef<'a>,
+}
+
+impl<'a> GlyfRef<'a> {
+    pub fn new(data: &'a [u8], loca: LocaRef<'a>) -> Self {
+        Self { data, loca }
+    }
+
+    /// 字形数量
+    pub fn len(&self) -> usize {
+        self.loca.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 获取字形，没有轮廓的字形（如空格）返回 `None`
+    pub fn glyph(&self, glyph_id: u16) -> Result<Option<GlyphRef<'a>>, IOError> {
+        let range = match self.loca.glyph_range(glyph_id) {
+            Some(range) => range,
+            None => return Err(IOError::NoEnoughData),
+        };
+        if range.start > range.end {
+            return Err(IOError::unexpected(
+                &format!("loca[{}]", glyph_id as usize + 1),
+                format!(">= 0x{:X}", range.start),
+                format!("0x{:X}", range.end),
+                range.start,
+            ));
+        }
+        if range.end > self.data.len() {
+            return Err(IOError::NoEnoughData);
+        }
+        if range.is_empty() {
+            return Ok(None);
+        }
+        GlyphRef::new(&self.data[range]).map(Some)
+    }
+}
+
+/// 单个字形数据的借用视图
+#[derive(Clone, Copy)]
+pub struct GlyphRef<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> GlyphRef<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, IOError> {
+        if data.len() < 10 {
+            return Err(IOError::NoEnoughData);
+        }
+        Ok(Self { data })
+    }
+
+    fn i16_at(&self, offset: usize) -> i16 {
+        i16::from_be_bytes([self.data[offset], self.data[offset + 1]])
+    }
+
+    /// 字形的原始数据
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// 非负为简单字形的轮廓数，负数表示复合字形
+    pub fn number_of_contours(&self) -> i16 {
+        self.i16_at(0)
+    }
+
+    pub fn x_min(&self) -> i16 {
+        self.i16_at(2)
+    }
+
+    pub fn y_min(&self) -> i16 {
+        self.i16_at(4)
+    }
+
+    pub fn x_max(&self) -> i16 {
+        self.i16_at(6)
+    }
+
+    pub fn y_max(&self) -> i16 {
+        self.i16_at(8)
+    }
+
+    pub fn is_composite(&self) -> bool {
+        self.number_of_contours() < 0
+    }
+
+    /// 简单字形每个轮廓最后一个点的索引，复合字形为空
+    pub fn end_pts_of_contours(&self) -> Result<Vec<u16>, IOError> {
+        if self.is_composite() {
+            return Ok(vec![]);
+        }
+        let mut reader = SliceReader::new(&self.data[10..]);
+        let mut end_pts = Vec::with_capacity(self.number_of_contours() as usize);
+        for _ in 0..self.number_of_contours() {
+            end_pts.push(reader.read_u16()?);
+        }
+        Ok(end_pts)
+    }
+
+    /// 字形指令，没有指令时为空
+    pub fn instructions(&self) -> Result<&'a [u8], IOError> {
+        let mut reader = SliceReader::new(&self.data[10..]);
+        if self.is_composite() {
+            // 跳过所有组件，最后一个组件带有 `WE_HAVE_INSTRUCTIONS` 时其后为指令
+            loop {
+                let flags = reader.read_u16()?;
+                let mut len = 2 + if flags & 0x0001 != 0 { 4 } else { 2 };
+                if flags & 0x0008 != 0 {
+                    len += 2;
+                } else if flags & 0x0040 != 0 {
+                    len += 4;
+                } else if flags & 0x0080 != 0 {
+                    len += 8;
+                }
+                reader.skip(len)?;
+                if flags & 0x0020 == 0 {
+                    if flags & 0x0100 == 0 {
+                        return Ok(&[]);
+                    }
+                    break;
+                }
+            }
+        } else {
+            reader.skip(self.number_of_contours() as usize * 2)?;
+        }
+        let instruction_length = reader.read_u16()?;
+        reader.read_slice(instruction_length as usize)
+    }
 }
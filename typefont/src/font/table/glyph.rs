@@ -1,4 +1,8 @@
 use super::ebdt::EbdtComponent;
+use crate::font::io::ReadFrom;
+use crate::io::error::IOError;
+use crate::io::reader::Reader;
+use crate::io::slice_reader::SliceReader;
 
 pub struct BigGlyphMetrics {
     height: u8,
@@ -108,3 +112,148 @@ pub struct GlyphBitmapDataFormat19 {
     data_len: u32,
     data: Vec<u8>,
 }
+
+impl<R: Reader + ?Sized> ReadFrom<R> for BigGlyphMetrics {
+    fn read_from(reader: &mut R) -> Result<Self, IOError> {
+        Ok(Self {
+            height: reader.read_u8()?,
+            width: reader.read_u8()?,
+            hori_bearing_x: reader.read_i8()?,
+            hori_bearing_y: reader.read_i8()?,
+            hori_advance: reader.read_u8()?,
+            vert_bearing_x: reader.read_i8()?,
+            vert_bearing_y: reader.read_i8()?,
+            vert_advance: reader.read_u8()?,
+        })
+    }
+}
+
+impl<R: Reader + ?Sized> ReadFrom<R> for SmallGlyphMetrics {
+    fn read_from(reader: &mut R) -> Result<Self, IOError> {
+        Ok(Self {
+            height: reader.read_u8()?,
+            width: reader.read_u8()?,
+            bearing_x: reader.read_i8()?,
+            bearing_y: reader.read_i8()?,
+            advance: reader.read_u8()?,
+        })
+    }
+}
+
+impl BigGlyphMetrics {
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    pub fn width(&self) -> u8 {
+        self.width
+    }
+
+    pub fn hori_bearing_x(&self) -> i8 {
+        self.hori_bearing_x
+    }
+
+    pub fn hori_bearing_y(&self) -> i8 {
+        self.hori_bearing_y
+    }
+
+    pub fn hori_advance(&self) -> u8 {
+        self.hori_advance
+    }
+
+    pub fn vert_bearing_x(&self) -> i8 {
+        self.vert_bearing_x
+    }
+
+    pub fn vert_bearing_y(&self) -> i8 {
+        self.vert_bearing_y
+    }
+
+    pub fn vert_advance(&self) -> u8 {
+        self.vert_advance
+    }
+}
+
+impl SmallGlyphMetrics {
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    pub fn width(&self) -> u8 {
+        self.width
+    }
+
+    pub fn bearing_x(&self) -> i8 {
+        self.bearing_x
+    }
+
+    pub fn bearing_y(&self) -> i8 {
+        self.bearing_y
+    }
+
+    pub fn advance(&self) -> u8 {
+        self.advance
+    }
+}
+
+/// PNG 格式位图数据的借用视图，`data` 直接引用 `CBDT` 表中的数据
+pub enum GlyphBitmapDataRef<'a> {
+    /// small metrics, PNG image data
+    Format17 {
+        glyph_metrics: SmallGlyphMetrics,
+        data: &'a [u8],
+    },
+    /// big metrics, PNG image data
+    Format18 {
+        glyph_metrics: BigGlyphMetrics,
+        data: &'a [u8],
+    },
+    /// metrics in CBLC table, PNG image data
+    Format19 { data: &'a [u8] },
+}
+
+impl<'a> GlyphBitmapDataRef<'a> {
+    /// 从位图数据开头读取，`image_format` 来自 `CBLC` 的索引子表头
+    pub fn read(data: &'a [u8], image_format: u16) -> Result<Self, IOError> {
+        let mut reader = SliceReader::new(data);
+        Ok(match image_format {
+            17 => {
+                let glyph_metrics = SmallGlyphMetrics::read_from(&mut reader)?;
+                let data_len = reader.read_u32()?;
+                GlyphBitmapDataRef::Format17 {
+                    glyph_metrics,
+                    data: reader.read_slice(data_len as usize)?,
+                }
+            }
+            18 => {
+                let glyph_metrics = BigGlyphMetrics::read_from(&mut reader)?;
+                let data_len = reader.read_u32()?;
+                GlyphBitmapDataRef::Format18 {
+                    glyph_metrics,
+                    data: reader.read_slice(data_len as usize)?,
+                }
+            }
+            19 => {
+                let data_len = reader.read_u32()?;
+                GlyphBitmapDataRef::Format19 {
+                    data: reader.read_slice(data_len as usize)?,
+                }
+            }
+            format => {
+                return Err(IOError::Unexpected {
+                    expected: "imageFormat 17, 18 or 19".to_string(),
+                    found: format.to_string(),
+                });
+            }
+        })
+    }
+
+    /// PNG 图像数据
+    pub fn data(&self) -> &'a [u8] {
+        match self {
+            GlyphBitmapDataRef::Format17 { data, .. } => data,
+            GlyphBitmapDataRef::Format18 { data, .. } => data,
+            GlyphBitmapDataRef::Format19 { data } => data,
+        }
+    }
+}
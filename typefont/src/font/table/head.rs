@@ -1,6 +1,13 @@
 use super::super::Fixed;
 use super::super::LongDataTime;
+use crate::font::io::ReadFrom;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 /// 此表提供了有关字体的全局信息。
 /// 边界框值应仅使用具有轮廓的字形进行计算。
@@ -123,3 +130,144 @@ pub mod font_direction_hint {
 }
 
 impl_named!(Head, "head");
+
+/// `magic_number` 固定值
+pub const HEAD_MAGIC_NUMBER: u32 = 0x5F0F3CF5;
+
+impl Head {
+    pub fn major_version(&self) -> u16 {
+        self.major_version
+    }
+
+    pub fn minor_version(&self) -> u16 {
+        self.minor_version
+    }
+
+    pub fn font_revision(&self) -> Fixed {
+        self.font_revision
+    }
+
+    pub fn checksum_adjustment(&self) -> u32 {
+        self.checksum_adjustment
+    }
+
+    pub fn magic_number(&self) -> u32 {
+        self.magic_number
+    }
+
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+
+    pub fn units_per_em(&self) -> u16 {
+        self.units_per_em
+    }
+
+    pub fn created(&self) -> LongDataTime {
+        self.created
+    }
+
+    pub fn modified(&self) -> LongDataTime {
+        self.modified
+    }
+
+    pub fn x_min(&self) -> i16 {
+        self.x_min
+    }
+
+    pub fn y_min(&self) -> i16 {
+        self.y_min
+    }
+
+    pub fn x_max(&self) -> i16 {
+        self.x_max
+    }
+
+    pub fn y_max(&self) -> i16 {
+        self.y_max
+    }
+
+    pub fn mac_style(&self) -> u16 {
+        self.mac_style
+    }
+
+    pub fn lowest_rec_ppem(&self) -> u16 {
+        self.lowest_rec_ppem
+    }
+
+    pub fn font_direction_hint(&self) -> i16 {
+        self.font_direction_hint
+    }
+
+    pub fn index_to_loc_format(&self) -> i16 {
+        self.index_to_loc_format
+    }
+
+    pub fn glyph_data_format(&self) -> i16 {
+        self.glyph_data_format
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for Head {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let major_version = reader.read_u16()?;
+        let minor_version = reader.read_u16()?;
+        let font_revision = reader.read_i32()?;
+        let checksum_adjustment = reader.read_u32()?;
+        let magic_number = reader.read_u32()?;
+        if magic_number != HEAD_MAGIC_NUMBER {
+            return Err(IOError::unexpected(
+                "magicNumber",
+                format!("0x{:08X}", HEAD_MAGIC_NUMBER),
+                format!("0x{:08X}", magic_number),
+                12,
+            ));
+        }
+        Ok(Self {
+            major_version,
+            minor_version,
+            font_revision,
+            checksum_adjustment,
+            magic_number,
+            flags: reader.read_u16()?,
+            units_per_em: reader.read_u16()?,
+            created: reader.read_i64()?,
+            modified: reader.read_i64()?,
+            x_min: reader.read_i16()?,
+            y_min: reader.read_i16()?,
+            x_max: reader.read_i16()?,
+            y_max: reader.read_i16()?,
+            mac_style: reader.read_u16()?,
+            lowest_rec_ppem: reader.read_u16()?,
+            font_direction_hint: reader.read_i16()?,
+            index_to_loc_format: reader.read_i16()?,
+            glyph_data_format: reader.read_i16()?,
+        })
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Head {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(self.major_version)?;
+        writer.write(self.minor_version)?;
+        writer.write(self.font_revision)?;
+        writer.write(self.checksum_adjustment)?;
+        writer.write(self.magic_number)?;
+        writer.write(self.flags)?;
+        writer.write(self.units_per_em)?;
+        writer.write(self.created)?;
+        writer.write(self.modified)?;
+        writer.write(self.x_min)?;
+        writer.write(self.y_min)?;
+        writer.write(self.x_max)?;
+        writer.write(self.y_max)?;
+        writer.write(self.mac_style)?;
+        writer.write(self.lowest_rec_ppem)?;
+        writer.write(self.font_direction_hint)?;
+        writer.write(self.index_to_loc_format)?;
+        writer.write(self.glyph_data_format)?;
+        Ok(writer.position() - start)
+    }
+}
@@ -1,5 +1,12 @@
 use super::super::{FWord, UFWord};
+use crate::font::io::ReadFrom;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 /// # 水平头表
 ///
@@ -65,3 +72,112 @@ pub struct Hhea {
 }
 
 impl_named!(Hhea, "hhea");
+
+impl Hhea {
+    pub fn major_version(&self) -> u16 {
+        self.major_version
+    }
+
+    pub fn minor_version(&self) -> u16 {
+        self.minor_version
+    }
+
+    pub fn ascender(&self) -> FWord {
+        self.ascender
+    }
+
+    pub fn descender(&self) -> FWord {
+        self.descender
+    }
+
+    pub fn line_gap(&self) -> FWord {
+        self.line_gap
+    }
+
+    pub fn advance_width_max(&self) -> UFWord {
+        self.advance_width_max
+    }
+
+    pub fn min_left_side_bearing(&self) -> FWord {
+        self.min_left_side_bearing
+    }
+
+    pub fn min_right_side_bearing(&self) -> FWord {
+        self.min_right_side_bearing
+    }
+
+    pub fn x_max_extent(&self) -> FWord {
+        self.x_max_extent
+    }
+
+    pub fn caret_slope_rise(&self) -> i16 {
+        self.caret_slope_rise
+    }
+
+    pub fn caret_slope_run(&self) -> i16 {
+        self.caret_slope_run
+    }
+
+    pub fn caret_offset(&self) -> i16 {
+        self.caret_offset
+    }
+
+    pub fn metric_data_format(&self) -> i16 {
+        self.metric_data_format
+    }
+
+    pub fn number_of_h_metrics(&self) -> u16 {
+        self.number_of_h_metrics
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for Hhea {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        Ok(Self {
+            major_version: reader.read_u16()?,
+            minor_version: reader.read_u16()?,
+            ascender: reader.read_i16()?,
+            descender: reader.read_i16()?,
+            line_gap: reader.read_i16()?,
+            advance_width_max: reader.read_u16()?,
+            min_left_side_bearing: reader.read_i16()?,
+            min_right_side_bearing: reader.read_i16()?,
+            x_max_extent: reader.read_i16()?,
+            caret_slope_rise: reader.read_i16()?,
+            caret_slope_run: reader.read_i16()?,
+            caret_offset: reader.read_i16()?,
+            reserved1: reader.read_i16()?,
+            reserved2: reader.read_i16()?,
+            reserved3: reader.read_i16()?,
+            reserved4: reader.read_i16()?,
+            metric_data_format: reader.read_i16()?,
+            number_of_h_metrics: reader.read_u16()?,
+        })
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Hhea {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(self.major_version)?;
+        writer.write(self.minor_version)?;
+        writer.write(self.ascender)?;
+        writer.write(self.descender)?;
+        writer.write(self.line_gap)?;
+        writer.write(self.advance_width_max)?;
+        writer.write(self.min_left_side_bearing)?;
+        writer.write(self.min_right_side_bearing)?;
+        writer.write(self.x_max_extent)?;
+        writer.write(self.caret_slope_rise)?;
+        writer.write(self.caret_slope_run)?;
+        writer.write(self.caret_offset)?;
+        writer.write(self.reserved1)?;
+        writer.write(self.reserved2)?;
+        writer.write(self.reserved3)?;
+        writer.write(self.reserved4)?;
+        writer.write(self.metric_data_format)?;
+        writer.write(self.number_of_h_metrics)?;
+        Ok(writer.position() - start)
+    }
+}
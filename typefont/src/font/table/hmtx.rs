@@ -1,11 +1,18 @@
 use super::super::{FWord, UFWord};
+use crate::font::io::ReadWith;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 pub struct Hmtx {
     /// h_metrics\[number_of_h_metrics]
     h_metrics: Vec<LongHorMetric>,
     /// left_side_bearings\[num_glyphs - number_of_h_metrics]
-    left_side_bearings: FWord,
+    left_side_bearings: Vec<FWord>,
 }
 
 impl_named!(Hmtx, "hmtx");
@@ -14,3 +21,134 @@ pub struct LongHorMetric {
     advance_width: UFWord,
     lsb: FWord,
 }
+
+impl LongHorMetric {
+    pub fn advance_width(&self) -> UFWord {
+        self.advance_width
+    }
+
+    pub fn lsb(&self) -> FWord {
+        self.lsb
+    }
+}
+
+impl Hmtx {
+    pub fn h_metrics(&self) -> &Vec<LongHorMetric> {
+        &self.h_metrics
+    }
+
+    pub fn left_side_bearings(&self) -> &Vec<FWord> {
+        &self.left_side_bearings
+    }
+
+    /// 字形的前进宽度，超出 `h_metrics` 的字形使用最后一个记录的宽度
+    pub fn advance_width(&self, glyph_id: u16) -> Option<UFWord> {
+        let index = glyph_id as usize;
+        if index >= self.h_metrics.len() + self.left_side_bearings.len() {
+            return None;
+        }
+        self.h_metrics
+            .get(index)
+            .or(self.h_metrics.last())
+            .map(|m| m.advance_width)
+    }
+
+    /// 字形的左边距
+    pub fn lsb(&self, glyph_id: u16) -> Option<FWord> {
+        let index = glyph_id as usize;
+        match self.h_metrics.get(index) {
+            Some(m) => Some(m.lsb),
+            None => self
+                .left_side_bearings
+                .get(index - self.h_metrics.len())
+                .copied(),
+        }
+    }
+}
+
+/// 参数为 (`hhea.number_of_h_metrics`, `maxp.num_glyphs`)
+impl ReadWith<ReaderBoxed<'_>, (u16, u16)> for Hmtx {
+    fn read_with(reader: &mut ReaderBoxed, args: (u16, u16)) -> Result<Self, IOError> {
+        let (number_of_h_metrics, num_glyphs) = args;
+        let mut h_metrics = Vec::with_capacity(number_of_h_metrics as usize);
+        for _ in 0..number_of_h_metrics {
+            h_metrics.push(LongHorMetric {
+                advance_width: reader.read_u16()?,
+                lsb: reader.read_i16()?,
+            });
+        }
+        let lsb_count = num_glyphs.saturating_sub(number_of_h_metrics);
+        let mut left_side_bearings = Vec::with_capacity(lsb_count as usize);
+        for _ in 0..lsb_count {
+            left_side_bearings.push(reader.read_i16()?);
+        }
+        Ok(Self {
+            h_metrics,
+            left_side_bearings,
+        })
+    }
+}
+
+/// `hmtx` 表的借用视图，度量值在访问时才解码
+#[derive(Clone, Copy)]
+pub struct HmtxRef<'a> {
+    data: &'a [u8],
+    number_of_h_metrics: u16,
+    num_glyphs: u16,
+}
+
+impl<'a> HmtxRef<'a> {
+    pub fn new(data: &'a [u8], number_of_h_metrics: u16, num_glyphs: u16) -> Result<Self, IOError> {
+        let lsb_count = num_glyphs.saturating_sub(number_of_h_metrics) as usize;
+        if data.len() < number_of_h_metrics as usize * 4 + lsb_count * 2 {
+            return Err(IOError::NoEnoughData);
+        }
+        Ok(Self {
+            data,
+            number_of_h_metrics,
+            num_glyphs,
+        })
+    }
+
+    fn u16_at(&self, offset: usize) -> u16 {
+        u16::from_be_bytes([self.data[offset], self.data[offset + 1]])
+    }
+
+    /// 字形的前进宽度，超出 `h_metrics` 的字形使用最后一个记录的宽度
+    pub fn advance_width(&self, glyph_id: u16) -> Option<UFWord> {
+        if glyph_id >= self.num_glyphs || self.number_of_h_metrics == 0 {
+            return None;
+        }
+        let index = glyph_id.min(self.number_of_h_metrics - 1) as usize;
+        Some(self.u16_at(index * 4))
+    }
+
+    /// 字形的左边距
+    pub fn lsb(&self, glyph_id: u16) -> Option<FWord> {
+        if glyph_id >= self.num_glyphs {
+            return None;
+        }
+        let offset = if glyph_id < self.number_of_h_metrics {
+            glyph_id as usize * 4 + 2
+        } else {
+            self.number_of_h_metrics as usize * 4
+                + (glyph_id - self.number_of_h_metrics) as usize * 2
+        };
+        Some(self.u16_at(offset) as FWord)
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Hmtx {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        for m in &self.h_metrics {
+            writer.write(m.advance_width)?;
+            writer.write(m.lsb)?;
+        }
+        for &lsb in &self.left_side_bearings {
+            writer.write(lsb)?;
+        }
+        Ok(writer.position() - start)
+    }
+}
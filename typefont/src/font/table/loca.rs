@@ -1,9 +1,170 @@
-use crate::font::{Offset16, Offset32};
+use crate::font::io::ReadWith;
+use crate::font::{GlyphId, Offset16, Offset32};
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
+use std::ops::Range;
 
+/// 字形数据在 `glyf` 表中的偏移量，共 `num_glyphs + 1` 个，
+/// 第 `i` 个字形的数据位于第 `i` 个与第 `i + 1` 个偏移量之间，两者相等表示字形没有轮廓。
+///
+/// 格式由 `head.index_to_loc_format` 决定：`0` 为短格式，保存实际偏移量除以 2 的值；`1` 为长格式。
 pub enum Loca {
     Short { offsets: Vec<Offset16> },
     Long { offsets: Vec<Offset32> },
 }
 
 impl_named!(Loca, "loca");
+
+impl Loca {
+    /// 偏移量个数，为 `num_glyphs + 1`
+    pub fn len(&self) -> usize {
+        match self {
+            Loca::Short { offsets } => offsets.len(),
+            Loca::Long { offsets } => offsets.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 第 `index` 个字形在 `glyf` 表中的偏移量，短格式已乘以 2
+    pub fn get(&self, index: usize) -> Option<u32> {
+        match self {
+            Loca::Short { offsets } => offsets.get(index).map(|&offset| offset as u32 * 2),
+            Loca::Long { offsets } => offsets.get(index).copied(),
+        }
+    }
+
+    /// 字形数据在 `glyf` 表中的范围
+    pub fn glyph_range(&self, glyph_id: GlyphId) -> Option<Range<usize>> {
+        let start = self.get(glyph_id as usize)? as usize;
+        let end = self.get(glyph_id as usize + 1)? as usize;
+        Some(start..end)
+    }
+}
+
+/// 参数为 (`head.index_to_loc_format`, `maxp.num_glyphs`)
+impl ReadWith<ReaderBoxed<'_>, (i16, u16)> for Loca {
+    fn read_with(reader: &mut ReaderBoxed, args: (i16, u16)) -> Result<Self, IOError> {
+        let (index_to_loc_format, num_glyphs) = args;
+        let count = num_glyphs as usize + 1;
+        Ok(match index_to_loc_format {
+            0 => {
+                let mut offsets = Vec::with_capacity(count);
+                for _ in 0..count {
+                    offsets.push(reader.read_u16()?);
+                }
+                Loca::Short { offsets }
+            }
+            1 => {
+                let mut offsets = Vec::with_capacity(count);
+                for _ in 0..count {
+                    offsets.push(reader.read_u32()?);
+                }
+                Loca::Long { offsets }
+            }
+            format => {
+                return Err(IOError::unexpected(
+                    "head.indexToLocFormat",
+                    "0 or 1",
+                    format,
+                    0,
+                ));
+            }
+        })
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Loca {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        match self {
+            Loca::Short { offsets } => {
+                for &offset in offsets {
+                    writer.write(offset)?;
+                }
+            }
+            Loca::Long { offsets } => {
+                for &offset in offsets {
+                    writer.write(offset)?;
+                }
+            }
+        }
+        Ok(writer.position() - start)
+    }
+}
+
+/// `loca` 表的借用视图，偏移量在访问时才解码
+#[derive(Clone, Copy)]
+pub struct LocaRef<'a> {
+    data: &'a [u8],
+    /// `head.index_to_loc_format` 为 `1`
+    long: bool,
+    /// `maxp.num_glyphs`
+    num_glyphs: u16,
+}
+
+impl<'a> LocaRef<'a> {
+    /// 与 [Loca] 相同，`index_to_loc_format` 不是 `0` 或 `1`、
+    /// 数据不足 `num_glyphs + 1` 个偏移量时返回错误，多余的数据被忽略
+    pub fn new(data: &'a [u8], index_to_loc_format: i16, num_glyphs: u16) -> Result<Self, IOError> {
+        let long = match index_to_loc_format {
+            0 => false,
+            1 => true,
+            format => {
+                return Err(IOError::unexpected(
+                    "head.indexToLocFormat",
+                    "0 or 1",
+                    format,
+                    0,
+                ));
+            }
+        };
+        let width = if long { 4 } else { 2 };
+        if data.len() < (num_glyphs as usize + 1) * width {
+            return Err(IOError::NoEnoughData);
+        }
+        Ok(Self {
+            data,
+            long,
+            num_glyphs,
+        })
+    }
+
+    /// 偏移量个数，为 `num_glyphs + 1`
+    pub fn len(&self) -> usize {
+        self.num_glyphs as usize + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 第 `index` 个字形在 `glyf` 表中的偏移量，短格式已乘以 2
+    pub fn get(&self, index: usize) -> Option<u32> {
+        if index >= self.len() {
+            return None;
+        }
+        if self.long {
+            let bs = &self.data[index * 4..index * 4 + 4];
+            Some(u32::from_be_bytes([bs[0], bs[1], bs[2], bs[3]]))
+        } else {
+            let bs = &self.data[index * 2..index * 2 + 2];
+            Some(u16::from_be_bytes([bs[0], bs[1]]) as u32 * 2)
+        }
+    }
+
+    /// 字形数据在 `glyf` 表中的范围
+    pub fn glyph_range(&self, glyph_id: u16) -> Option<Range<usize>> {
+        let start = self.get(glyph_id as usize)? as usize;
+        let end = self.get(glyph_id as usize + 1)? as usize;
+        Some(start..end)
+    }
+}
@@ -1,4 +1,11 @@
+use crate::font::io::ReadFrom;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 pub struct LTSH {
     version: u16,
@@ -7,3 +14,40 @@ pub struct LTSH {
 }
 
 impl_named!(LTSH, "LTSH");
+
+impl LTSH {
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    pub fn num_glyphs(&self) -> u16 {
+        self.num_glyphs
+    }
+
+    pub fn y_pixels(&self) -> &Vec<u8> {
+        &self.y_pixels
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for LTSH {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let version = reader.read_u16()?;
+        let num_glyphs = reader.read_u16()?;
+        Ok(Self {
+            version,
+            num_glyphs,
+            y_pixels: reader.read_bytes_expected(num_glyphs as usize)?,
+        })
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for LTSH {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(self.version)?;
+        writer.write(self.y_pixels.len() as u16)?;
+        writer.write(&self.y_pixels)?;
+        Ok(writer.position() - start)
+    }
+}
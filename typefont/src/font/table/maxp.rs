@@ -1,5 +1,12 @@
 use super::super::Version16Dot16;
+use crate::font::io::ReadFrom;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 pub enum Maxp {
     V0_5(MaxpV0_5),
@@ -30,3 +37,148 @@ pub struct MaxpV1_0 {
     max_component_elements: u16,
     max_component_depth: u16,
 }
+
+impl Maxp {
+    pub fn version(&self) -> Version16Dot16 {
+        match self {
+            Maxp::V0_5(v) => v.version,
+            Maxp::V1_0(v) => v.version,
+        }
+    }
+
+    pub fn num_glyphs(&self) -> u16 {
+        match self {
+            Maxp::V0_5(v) => v.num_glyphs,
+            Maxp::V1_0(v) => v.num_glyphs,
+        }
+    }
+
+    /// `TrueType` 轮廓字体的限制值，`CFF` 字体（版本 0.5）为 `None`
+    pub fn as_v1_0(&self) -> Option<&MaxpV1_0> {
+        match self {
+            Maxp::V0_5(_) => None,
+            Maxp::V1_0(v) => Some(v),
+        }
+    }
+}
+
+impl MaxpV1_0 {
+    pub fn max_points(&self) -> u16 {
+        self.max_points
+    }
+
+    pub fn max_contours(&self) -> u16 {
+        self.max_contours
+    }
+
+    pub fn max_composite_points(&self) -> u16 {
+        self.max_composite_points
+    }
+
+    pub fn max_composite_contours(&self) -> u16 {
+        self.max_composite_contours
+    }
+
+    pub fn max_zones(&self) -> u16 {
+        self.max_zones
+    }
+
+    pub fn max_twilight_points(&self) -> u16 {
+        self.max_twilight_points
+    }
+
+    pub fn max_storage(&self) -> u16 {
+        self.max_storage
+    }
+
+    pub fn max_function_defs(&self) -> u16 {
+        self.max_function_defs
+    }
+
+    pub fn max_instruction_defs(&self) -> u16 {
+        self.max_instruction_defs
+    }
+
+    pub fn max_stack_elements(&self) -> u16 {
+        self.max_stack_elements
+    }
+
+    pub fn max_size_of_instructions(&self) -> u16 {
+        self.max_size_of_instructions
+    }
+
+    pub fn max_component_elements(&self) -> u16 {
+        self.max_component_elements
+    }
+
+    pub fn max_component_depth(&self) -> u16 {
+        self.max_component_depth
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for Maxp {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let version = reader.read_u32()?;
+        let num_glyphs = reader.read_u16()?;
+        match version {
+            0x00005000 => Ok(Maxp::V0_5(MaxpV0_5 {
+                version,
+                num_glyphs,
+            })),
+            0x00010000 => Ok(Maxp::V1_0(MaxpV1_0 {
+                version,
+                num_glyphs,
+                max_points: reader.read_u16()?,
+                max_contours: reader.read_u16()?,
+                max_composite_points: reader.read_u16()?,
+                max_composite_contours: reader.read_u16()?,
+                max_zones: reader.read_u16()?,
+                max_twilight_points: reader.read_u16()?,
+                max_storage: reader.read_u16()?,
+                max_function_defs: reader.read_u16()?,
+                max_instruction_defs: reader.read_u16()?,
+                max_stack_elements: reader.read_u16()?,
+                max_size_of_instructions: reader.read_u16()?,
+                max_component_elements: reader.read_u16()?,
+                max_component_depth: reader.read_u16()?,
+            })),
+            version => Err(IOError::unexpected(
+                "version",
+                "0.5 or 1.0",
+                format!("0x{:08X}", version),
+                0,
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Maxp {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        match self {
+            Maxp::V0_5(v) => {
+                writer.write(v.version)?;
+                writer.write(v.num_glyphs)?;
+            }
+            Maxp::V1_0(v) => {
+                writer.write(v.version)?;
+                writer.write(v.num_glyphs)?;
+                writer.write(v.max_points)?;
+                writer.write(v.max_contours)?;
+                writer.write(v.max_composite_points)?;
+                writer.write(v.max_composite_contours)?;
+                writer.write(v.max_zones)?;
+                writer.write(v.max_twilight_points)?;
+                writer.write(v.max_storage)?;
+                writer.write(v.max_function_defs)?;
+                writer.write(v.max_instruction_defs)?;
+                writer.write(v.max_stack_elements)?;
+                writer.write(v.max_size_of_instructions)?;
+                writer.write(v.max_component_elements)?;
+                writer.write(v.max_component_depth)?;
+            }
+        }
+        Ok(writer.position() - start)
+    }
+}
@@ -51,6 +51,16 @@ pub mod vmtx;
 pub mod vorg;
 pub mod vvar;
 
+use crate::font::Tag;
+use crate::font::io::{ReadFrom, ReadWith};
+use crate::font::table_record::TableRecord;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
+
 pub trait Named {
     fn name() -> String;
 }
@@ -105,6 +115,139 @@ pub enum Table {
     Vmtx(vmtx::Vmtx),
     VORG(vorg::VORG),
     VVAR(vvar::VVAR),
+    /// 未知或暂不支持解析的表，保留原始数据
+    Unknown { tag: Tag, bytes: Vec<u8> },
+}
+
+impl Table {
+    /// 读取 `tag` 对应的表时依赖的其他表，如 `hmtx` 依赖 `hhea` 和 `maxp` 中的字段
+    pub fn dependencies(tag: &Tag) -> &'static [&'static [u8; 4]] {
+        match &tag.to_bytes() {
+            b"glyf" => &[b"loca"],
+            b"hmtx" => &[b"hhea", b"maxp"],
+            b"loca" => &[b"head", b"maxp"],
+            _ => &[],
+        }
+    }
+
+    /// 从当前位置读取 `record` 对应的表
+    ///
+    /// `tables` 为已读取的表，应包含 [Table::dependencies] 中字体存在的表
+    pub fn read(
+        reader: &mut ReaderBoxed,
+        record: &TableRecord,
+        tables: &[&Table],
+    ) -> Result<Table, IOError> {
+        let length = record.length() as usize;
+        Ok(match &record.tag().to_bytes() {
+            b"cvt " => Table::Cvt(cvt::Cvt::read_with(reader, length)?),
+            b"CBDT" => Table::CBDT(cbdt::CBDT::read_with(reader, length)?),
+            b"CFF " => Table::CFF(cff::CFF::read_with(reader, length)?),
+            b"CFF2" => Table::CFF2(cff2::CFF2::read_with(reader, length)?),
+            b"cmap" => Table::Cmap(cmap::Cmap::read_from(reader)?),
+            b"DSIG" => Table::DSIG(dsig::DSIG::read_from(reader)?),
+            b"fpgm" => Table::Fpgm(fpgm::Fpgm::read_with(reader, length)?),
+            b"gasp" => Table::Gasp(gasp::Gasp::read_from(reader)?),
+            b"glyf" => match tables.iter().find_map(|&t| match t {
+                Table::Loca(loca) => Some(loca),
+                _ => None,
+            }) {
+                Some(loca) => Table::Glyf(glyf::Glyf::read_with(
+                    reader,
+                    (loca, record.offset() as usize),
+                )?),
+                None => Self::read_unknown(reader, record)?,
+            },
+            b"head" => Table::Head(head::Head::read_from(reader)?),
+            b"hhea" => Table::Hhea(hhea::Hhea::read_from(reader)?),
+            b"hmtx" => {
+                let hhea = tables.iter().find_map(|&t| match t {
+                    Table::Hhea(hhea) => Some(hhea),
+                    _ => None,
+                });
+                let maxp = tables.iter().find_map(|&t| match t {
+                    Table::Maxp(maxp) => Some(maxp),
+                    _ => None,
+                });
+                match (hhea, maxp) {
+                    (Some(hhea), Some(maxp)) => Table::Hmtx(hmtx::Hmtx::read_with(
+                        reader,
+                        (hhea.number_of_h_metrics(), maxp.num_glyphs()),
+                    )?),
+                    _ => Self::read_unknown(reader, record)?,
+                }
+            }
+            b"loca" => {
+                let head = tables.iter().find_map(|&t| match t {
+                    Table::Head(head) => Some(head),
+                    _ => None,
+                });
+                let maxp = tables.iter().find_map(|&t| match t {
+                    Table::Maxp(maxp) => Some(maxp),
+                    _ => None,
+                });
+                match (head, maxp) {
+                    (Some(head), Some(maxp)) => Table::Loca(loca::Loca::read_with(
+                        reader,
+                        (head.index_to_loc_format(), maxp.num_glyphs()),
+                    )?),
+                    _ => Self::read_unknown(reader, record)?,
+                }
+            }
+            b"LTSH" => Table::LTSH(ltsh::LTSH::read_from(reader)?),
+            b"maxp" => Table::Maxp(maxp::Maxp::read_from(reader)?),
+            b"name" => Table::Name(name::Name::read_from(reader)?),
+            b"OS/2" => Table::OS2(os2::Os2::read_from(reader)?),
+            b"PCLT" => Table::PCLT(pclt::PCLT::read_from(reader)?),
+            b"post" => Table::Post(post::Post::read_from(reader)?),
+            b"prep" => Table::Prep(prep::Prep::read_with(reader, length)?),
+            b"vhea" => Table::Vhea(vhea::Vhea::read_from(reader)?),
+            b"VORG" => Table::VORG(vorg::VORG::read_from(reader)?),
+            _ => Self::read_unknown(reader, record)?,
+        })
+    }
+
+    fn read_unknown(reader: &mut ReaderBoxed, record: &TableRecord) -> Result<Table, IOError> {
+        Ok(Table::Unknown {
+            tag: record.tag().clone(),
+            bytes: reader.read_bytes_expected(record.length() as usize)?,
+        })
+    }
+}
+
+/// 只有已解析的表和 [Table::Unknown] 可以写入，其余表返回 [IOError::UnableOperate]
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Table {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        match self {
+            Table::CBDT(table) => table.write_to(writer),
+            Table::CFF(table) => table.write_to(writer),
+            Table::CFF2(table) => table.write_to(writer),
+            Table::Cmap(table) => table.write_to(writer),
+            Table::Cvt(table) => table.write_to(writer),
+            Table::DSIG(table) => table.write_to(writer),
+            Table::Fpgm(table) => table.write_to(writer),
+            Table::Gasp(table) => table.write_to(writer),
+            Table::Glyf(table) => table.write_to(writer),
+            Table::Head(table) => table.write_to(writer),
+            Table::Hhea(table) => table.write_to(writer),
+            Table::Hmtx(table) => table.write_to(writer),
+            Table::Loca(table) => table.write_to(writer),
+            Table::LTSH(table) => table.write_to(writer),
+            Table::Maxp(table) => table.write_to(writer),
+            Table::Name(table) => table.write_to(writer),
+            Table::OS2(table) => table.write_to(writer),
+            Table::PCLT(table) => table.write_to(writer),
+            Table::Post(table) => table.write_to(writer),
+            Table::Prep(table) => table.write_to(writer),
+            Table::Vhea(table) => table.write_to(writer),
+            Table::VORG(table) => table.write_to(writer),
+            Table::Unknown { bytes, .. } => writer.write(bytes),
+            _ => Err(IOError::UnableOperate(
+                "writing this table is not supported".to_string(),
+            )),
+        }
+    }
 }
 
 #[macro_export]
@@ -1,6 +1,29 @@
 use super::super::Offset16;
+#[cfg(feature = "cjk")]
+use crate::font::encoding::LegacyEncoding;
+use crate::font::encoding::{decode_mac_roman, decode_utf16be, encode_mac_roman, encode_utf16be};
+use crate::font::io::ReadFrom;
 use crate::impl_named;
+use crate::io::error::{ErrorContext, IOError};
+use crate::io::reader::{Reader, ReaderBoxed};
+use crate::io::slice_reader::SliceReader;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
+#[cfg(feature = "writer")]
+use std::collections::HashMap;
 
+/// # name — 命名表
+///
+/// 命名表允许把多语言字符串与字体关联，如版权声明、字体族名、样式名等。
+/// 每个名称记录由平台 ID、编码 ID、语言 ID 和名称 ID 标识，字符串保存在表末尾的字符串存储区中。
+///
+/// 平台 0 和平台 3 的字符串使用 `UTF-16BE` 编码，平台 1 的字符串使用编码 ID 对应的 Mac 脚本编码。
+/// 版本 1 增加了语言标签记录，语言 ID 大于等于 `0x8000` 时表示第 `language_id - 0x8000` 个语言标签。
+///
+/// 读取时按记录取出字符串数据。未修改的表原样写入字符串存储区，
+/// 调用 [Name::set] 或 [Name::remove] 后重新生成字符串存储区，相同的字符串数据只保存一次。
 pub enum Name {
     V0(NameV0),
     V1(NameV1),
@@ -9,21 +32,417 @@ pub enum Name {
 impl_named!(Name, "name");
 
 pub struct NameV0 {
+    /// 0
     version: u16,
     count: u16,
     storage_offset: Offset16,
     name_record: Vec<NameRecord>,
-    data: String,
+    /// 与 `name_record` 一一对应的未解码字符串数据
+    strings: Vec<Vec<u8>>,
+    /// 读取时的字符串存储区，修改记录后清空
+    storage: Vec<u8>,
 }
 
 pub struct NameV1 {
+    /// 1
     version: u16,
     count: u16,
     storage_offset: Offset16,
     name_record: Vec<NameRecord>,
     lang_tag_count: u16,
     lang_tag_record: Vec<LangTagRecord>,
-    data: String,
+    /// 与 `name_record` 一一对应的未解码字符串数据
+    strings: Vec<Vec<u8>>,
+    /// 与 `lang_tag_record` 一一对应的 `BCP 47` 语言标签
+    lang_tags: Vec<String>,
+    /// 读取时的字符串存储区，修改记录后清空
+    storage: Vec<u8>,
+}
+
+/// 预定义的名称 ID
+pub mod name_id {
+    pub const COPYRIGHT_NOTICE: u16 = 0;
+    pub const FAMILY_NAME: u16 = 1;
+    pub const SUBFAMILY_NAME: u16 = 2;
+    pub const UNIQUE_IDENTIFIER: u16 = 3;
+    pub const FULL_NAME: u16 = 4;
+    pub const VERSION_STRING: u16 = 5;
+    pub const POSTSCRIPT_NAME: u16 = 6;
+    pub const TRADEMARK: u16 = 7;
+    pub const MANUFACTURER_NAME: u16 = 8;
+    pub const DESIGNER: u16 = 9;
+    pub const DESCRIPTION: u16 = 10;
+    pub const VENDOR_URL: u16 = 11;
+    pub const DESIGNER_URL: u16 = 12;
+    pub const LICENSE_DESCRIPTION: u16 = 13;
+    pub const LICENSE_INFO_URL: u16 = 14;
+    pub const TYPOGRAPHIC_FAMILY_NAME: u16 = 16;
+    pub const TYPOGRAPHIC_SUBFAMILY_NAME: u16 = 17;
+    /// 仅用于平台 1
+    pub const COMPATIBLE_FULL: u16 = 18;
+    pub const SAMPLE_TEXT: u16 = 19;
+    pub const POSTSCRIPT_CID_FINDFONT_NAME: u16 = 20;
+    pub const WWS_FAMILY_NAME: u16 = 21;
+    pub const WWS_SUBFAMILY_NAME: u16 = 22;
+    pub const LIGHT_BACKGROUND_PALETTE: u16 = 23;
+    pub const DARK_BACKGROUND_PALETTE: u16 = 24;
+    pub const VARIATIONS_POSTSCRIPT_NAME_PREFIX: u16 = 25;
+}
+
+/// 平台 3 的语言 ID 与 `BCP 47` 语言标签，只包含常用的语言
+const WINDOWS_LANGUAGES: [(u16, &str); 56] = [
+    (0x0401, "ar-SA"),
+    (0x0402, "bg-BG"),
+    (0x0403, "ca-ES"),
+    (0x0404, "zh-TW"),
+    (0x0405, "cs-CZ"),
+    (0x0406, "da-DK"),
+    (0x0407, "de-DE"),
+    (0x0408, "el-GR"),
+    (0x0409, "en-US"),
+    (0x040B, "fi-FI"),
+    (0x040C, "fr-FR"),
+    (0x040D, "he-IL"),
+    (0x040E, "hu-HU"),
+    (0x040F, "is-IS"),
+    (0x0410, "it-IT"),
+    (0x0411, "ja-JP"),
+    (0x0412, "ko-KR"),
+    (0x0413, "nl-NL"),
+    (0x0414, "nb-NO"),
+    (0x0415, "pl-PL"),
+    (0x0416, "pt-BR"),
+    (0x0418, "ro-RO"),
+    (0x0419, "ru-RU"),
+    (0x041A, "hr-HR"),
+    (0x041B, "sk-SK"),
+    (0x041C, "sq-AL"),
+    (0x041D, "sv-SE"),
+    (0x041E, "th-TH"),
+    (0x041F, "tr-TR"),
+    (0x0420, "ur-PK"),
+    (0x0421, "id-ID"),
+    (0x0422, "uk-UA"),
+    (0x0423, "be-BY"),
+    (0x0424, "sl-SI"),
+    (0x0425, "et-EE"),
+    (0x0426, "lv-LV"),
+    (0x0427, "lt-LT"),
+    (0x0429, "fa-IR"),
+    (0x042A, "vi-VN"),
+    (0x042D, "eu-ES"),
+    (0x0439, "hi-IN"),
+    (0x0804, "zh-CN"),
+    (0x0807, "de-CH"),
+    (0x0809, "en-GB"),
+    (0x080A, "es-MX"),
+    (0x080C, "fr-BE"),
+    (0x0810, "it-CH"),
+    (0x0813, "nl-BE"),
+    (0x0816, "pt-PT"),
+    (0x0C04, "zh-HK"),
+    (0x0C07, "de-AT"),
+    (0x0C09, "en-AU"),
+    (0x0C0A, "es-ES"),
+    (0x0C0C, "fr-CA"),
+    (0x1004, "zh-SG"),
+    (0x1009, "en-CA"),
+];
+
+/// 平台 1 的语言 ID（数组下标）对应的 `BCP 47` 语言标签，只包含前 34 个
+const MACINTOSH_LANGUAGES: [&str; 34] = [
+    "en", "fr", "de", "it", "nl", "sv", "es", "da", "pt", "no", "he", "ja", "ar", "fi", "el", "is",
+    "mt", "tr", "hr", "zh-Hant", "ur", "hi", "th", "ko", "lt", "pl", "hu", "et", "lv", "se", "fo",
+    "fa", "ru", "zh-Hans",
+];
+
+/// 按平台和编码解码名称字符串，不支持的编码返回 `None`
+///
+/// 平台 1 只内置 Mac OS Roman，日文、繁体中文、韩文和简体中文需要 `cjk` 特性。
+pub fn decode_name_string(platform_id: u16, encoding_id: u16, bytes: &[u8]) -> Option<String> {
+    match (platform_id, encoding_id) {
+        (0, _) | (3, _) | (2, 1) => decode_utf16be(bytes),
+        (1, 0) => Some(decode_mac_roman(bytes)),
+        #[cfg(feature = "cjk")]
+        (1, _) => mac_cjk_encoding(encoding_id)?.decode(bytes),
+        (2, 0) => bytes
+            .is_ascii()
+            .then(|| bytes.iter().map(|&b| b as char).collect()),
+        (2, 2) => Some(bytes.iter().map(|&b| b as char).collect()),
+        _ => None,
+    }
+}
+
+/// 按平台和编码编码名称字符串，包含无法编码的字符或不支持的编码时返回 `None`
+pub fn encode_name_string(platform_id: u16, encoding_id: u16, text: &str) -> Option<Vec<u8>> {
+    match (platform_id, encoding_id) {
+        (0, _) | (3, _) | (2, 1) => Some(encode_utf16be(text)),
+        (1, 0) => encode_mac_roman(text),
+        #[cfg(feature = "cjk")]
+        (1, _) => mac_cjk_encoding(encoding_id)?.encode(text),
+        (2, 0) => text.is_ascii().then(|| text.as_bytes().to_vec()),
+        (2, 2) => text.chars().map(|c| u8::try_from(c as u32).ok()).collect(),
+        _ => None,
+    }
+}
+
+/// Mac 日文、繁体中文、韩文和简体中文脚本对应的多字节编码
+#[cfg(feature = "cjk")]
+fn mac_cjk_encoding(encoding_id: u16) -> Option<LegacyEncoding> {
+    match encoding_id {
+        1 => Some(LegacyEncoding::ShiftJis),
+        2 => Some(LegacyEncoding::Big5),
+        3 => Some(LegacyEncoding::Wansung),
+        25 => Some(LegacyEncoding::Prc),
+        _ => None,
+    }
+}
+
+impl Name {
+    pub fn version(&self) -> u16 {
+        match self {
+            Name::V0(name) => name.version,
+            Name::V1(name) => name.version,
+        }
+    }
+
+    /// 读取时字符串存储区的偏移量，写入时按记录数重新计算
+    pub fn storage_offset(&self) -> Offset16 {
+        match self {
+            Name::V0(name) => name.storage_offset,
+            Name::V1(name) => name.storage_offset,
+        }
+    }
+
+    /// 按平台 ID、编码 ID、语言 ID、名称 ID 排序的名称记录
+    pub fn name_records(&self) -> &Vec<NameRecord> {
+        match self {
+            Name::V0(name) => &name.name_record,
+            Name::V1(name) => &name.name_record,
+        }
+    }
+
+    /// 语言标签记录，版本 0 为空
+    pub fn lang_tag_records(&self) -> &[LangTagRecord] {
+        match self {
+            Name::V0(_) => &[],
+            Name::V1(name) => &name.lang_tag_record,
+        }
+    }
+
+    /// 与 [Name::lang_tag_records] 一一对应的语言标签，版本 0 为空
+    pub fn lang_tags(&self) -> &[String] {
+        match self {
+            Name::V0(_) => &[],
+            Name::V1(name) => &name.lang_tags,
+        }
+    }
+
+    /// 第 `index` 个名称记录的未解码字符串数据
+    pub fn string_data(&self, index: usize) -> Option<&[u8]> {
+        self.strings().get(index).map(Vec::as_slice)
+    }
+
+    /// 第 `index` 个名称记录解码后的字符串，见 [decode_name_string]
+    pub fn string(&self, index: usize) -> Option<String> {
+        let record = self.name_records().get(index)?;
+        decode_name_string(
+            record.platform_id,
+            record.encoding_id,
+            self.string_data(index)?,
+        )
+    }
+
+    /// 名称记录的 `BCP 47` 语言标签
+    ///
+    /// 语言 ID 大于等于 `0x8000` 时为语言标签记录中的标签，否则按平台查表，未知的语言 ID 返回 `None`。
+    pub fn language_tag(&self, record: &NameRecord) -> Option<&str> {
+        language_tag(record.platform_id, record.language_id, self.lang_tags())
+    }
+
+    /// 名称 ID 与语言标签都匹配的第一个可解码的字符串，依次查找平台 3、平台 0 和平台 1
+    ///
+    /// 语言标签不区分大小写，且必须完全匹配，`en` 不匹配 `en-US`。
+    pub fn get(&self, name_id: u16, language: &str) -> Option<String> {
+        [3, 0, 1].into_iter().find_map(|platform_id| {
+            self.find(name_id, language)
+                .filter(|&i| self.name_records()[i].platform_id == platform_id)
+                .find_map(|i| self.string(i))
+        })
+    }
+
+    /// 名称 ID 与语言标签都匹配的记录下标
+    fn find<'a>(&'a self, name_id: u16, language: &'a str) -> impl Iterator<Item = usize> + 'a {
+        self.name_records()
+            .iter()
+            .enumerate()
+            .filter(move |(_, r)| {
+                r.name_id == name_id
+                    && self
+                        .language_tag(r)
+                        .is_some_and(|tag| tag.eq_ignore_ascii_case(language))
+            })
+            .map(|(i, _)| i)
+    }
+
+    /// 设置名称 ID 与语言标签都匹配的所有记录的字符串，按各记录的平台和编码重新编码
+    ///
+    /// 没有匹配的记录时添加平台 3、编码 1 的记录：语言标签在语言 ID 表中时使用对应的语言 ID，
+    /// 否则添加语言标签记录，版本 0 的表会转换为版本 1。
+    ///
+    /// 有记录的编码无法表示 `value` 时返回错误，此时不修改任何记录。
+    pub fn set(&mut self, name_id: u16, language: &str, value: &str) -> Result<(), IOError> {
+        let indices: Vec<usize> = self.find(name_id, language).collect();
+        if indices.is_empty() {
+            let language_id = match WINDOWS_LANGUAGES
+                .iter()
+                .find(|(_, tag)| tag.eq_ignore_ascii_case(language))
+            {
+                Some(&(language_id, _)) => language_id,
+                None => self.add_lang_tag(language)?,
+            };
+            let record = NameRecord {
+                platform_id: 3,
+                encoding_id: 1,
+                language_id,
+                name_id,
+                length: 0,
+                string_offset: 0,
+            };
+            let (records, strings) = self.records_mut();
+            let index = records.partition_point(|r| r.sort_key() < record.sort_key());
+            records.insert(index, record);
+            strings.insert(index, vec![]);
+            return self.set_strings(&[index], value);
+        }
+        self.set_strings(&indices, value)
+    }
+
+    /// 删除名称 ID 与语言标签都匹配的所有记录，返回删除的记录数
+    ///
+    /// 不再被引用的语言标签记录保留。
+    pub fn remove(&mut self, name_id: u16, language: &str) -> usize {
+        let indices: Vec<usize> = self.find(name_id, language).collect();
+        let (records, strings) = self.records_mut();
+        for &i in indices.iter().rev() {
+            records.remove(i);
+            strings.remove(i);
+        }
+        self.update_counts();
+        indices.len()
+    }
+
+    fn strings(&self) -> &Vec<Vec<u8>> {
+        match self {
+            Name::V0(name) => &name.strings,
+            Name::V1(name) => &name.strings,
+        }
+    }
+
+    /// 修改记录后需要重新生成字符串存储区
+    fn records_mut(&mut self) -> (&mut Vec<NameRecord>, &mut Vec<Vec<u8>>) {
+        match self {
+            Name::V0(name) => {
+                name.storage.clear();
+                (&mut name.name_record, &mut name.strings)
+            }
+            Name::V1(name) => {
+                name.storage.clear();
+                (&mut name.name_record, &mut name.strings)
+            }
+        }
+    }
+
+    #[cfg(feature = "writer")]
+    fn storage(&self) -> &Vec<u8> {
+        match self {
+            Name::V0(name) => &name.storage,
+            Name::V1(name) => &name.storage,
+        }
+    }
+
+    /// 先编码所有字符串，全部成功后再修改记录
+    fn set_strings(&mut self, indices: &[usize], value: &str) -> Result<(), IOError> {
+        let mut encoded = Vec::with_capacity(indices.len());
+        for &i in indices {
+            let record = &self.name_records()[i];
+            let bytes = encode_name_string(record.platform_id, record.encoding_id, value)
+                .ok_or_else(|| {
+                    IOError::UnableOperate(format!(
+                        "name {} cannot be encoded for platform {} encoding {}",
+                        record.name_id, record.platform_id, record.encoding_id
+                    ))
+                })?;
+            encoded.push(bytes);
+        }
+        let (records, strings) = self.records_mut();
+        for (&i, bytes) in indices.iter().zip(encoded) {
+            records[i].length = u16::try_from(bytes.len()).unwrap_or(u16::MAX);
+            strings[i] = bytes;
+        }
+        self.update_counts();
+        Ok(())
+    }
+
+    /// 添加语言标签记录，返回对应的语言 ID，已有相同的标签时直接使用
+    fn add_lang_tag(&mut self, language: &str) -> Result<u16, IOError> {
+        if let Name::V0(name) = self {
+            *self = Name::V1(NameV1 {
+                version: 1,
+                count: name.count,
+                storage_offset: 0,
+                name_record: std::mem::take(&mut name.name_record),
+                lang_tag_count: 0,
+                lang_tag_record: vec![],
+                strings: std::mem::take(&mut name.strings),
+                lang_tags: vec![],
+                storage: vec![],
+            });
+        }
+        let Name::V1(name) = self else { unreachable!() };
+        let index = match name
+            .lang_tags
+            .iter()
+            .position(|tag| tag.eq_ignore_ascii_case(language))
+        {
+            Some(index) => index,
+            None => {
+                name.lang_tags.push(language.to_string());
+                name.lang_tag_record.push(LangTagRecord {
+                    length: (2 * language.encode_utf16().count()) as u16,
+                    lang_tag_offset: 0,
+                });
+                name.lang_tag_count = name.lang_tags.len() as u16;
+                name.storage.clear();
+                name.lang_tags.len() - 1
+            }
+        };
+        u16::try_from(0x8000 + index)
+            .map_err(|_| IOError::UnableOperate("too many language tags".to_string()))
+    }
+
+    fn update_counts(&mut self) {
+        match self {
+            Name::V0(name) => name.count = name.name_record.len() as u16,
+            Name::V1(name) => name.count = name.name_record.len() as u16,
+        }
+    }
+}
+
+fn language_tag(platform_id: u16, language_id: u16, lang_tags: &[String]) -> Option<&str> {
+    if language_id >= 0x8000 {
+        return lang_tags
+            .get(language_id as usize - 0x8000)
+            .map(String::as_str);
+    }
+    match platform_id {
+        1 => MACINTOSH_LANGUAGES.get(language_id as usize).copied(),
+        3 => WINDOWS_LANGUAGES
+            .iter()
+            .find(|&&(id, _)| id == language_id)
+            .map(|&(_, tag)| tag),
+        _ => None,
+    }
 }
 
 pub struct LangTagRecord {
@@ -39,3 +458,318 @@ pub struct NameRecord {
     length: u16,
     string_offset: Offset16,
 }
+
+impl NameRecord {
+    pub fn platform_id(&self) -> u16 {
+        self.platform_id
+    }
+
+    pub fn encoding_id(&self) -> u16 {
+        self.encoding_id
+    }
+
+    pub fn language_id(&self) -> u16 {
+        self.language_id
+    }
+
+    pub fn name_id(&self) -> u16 {
+        self.name_id
+    }
+
+    pub fn length(&self) -> u16 {
+        self.length
+    }
+
+    /// 相对于字符串存储区开头的偏移量
+    pub fn string_offset(&self) -> Offset16 {
+        self.string_offset
+    }
+
+    /// 名称记录按平台 ID、编码 ID、语言 ID、名称 ID 排序
+    fn sort_key(&self) -> (u16, u16, u16, u16) {
+        (
+            self.platform_id,
+            self.encoding_id,
+            self.language_id,
+            self.name_id,
+        )
+    }
+}
+
+impl<R: Reader + ?Sized> ReadFrom<R> for NameRecord {
+    fn read_from(reader: &mut R) -> Result<Self, IOError> {
+        Ok(Self {
+            platform_id: reader.read_u16()?,
+            encoding_id: reader.read_u16()?,
+            language_id: reader.read_u16()?,
+            name_id: reader.read_u16()?,
+            length: reader.read_u16()?,
+            string_offset: reader.read_u16()?,
+        })
+    }
+}
+
+impl LangTagRecord {
+    pub fn length(&self) -> u16 {
+        self.length
+    }
+
+    /// 相对于字符串存储区开头的偏移量
+    pub fn lang_tag_offset(&self) -> Offset16 {
+        self.lang_tag_offset
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for Name {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let version = reader.read_u16()?;
+        if version > 1 {
+            return Err(IOError::unexpected("version", "0 or 1", version, 0));
+        }
+        let count = reader.read_u16()?;
+        let storage_offset = reader.read_u16()?;
+        let mut name_record = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            name_record.push(NameRecord::read_from(reader.as_mut())?);
+        }
+        let mut lang_tag_record = vec![];
+        if version == 1 {
+            let lang_tag_count = reader.read_u16()?;
+            for _ in 0..lang_tag_count {
+                lang_tag_record.push(LangTagRecord {
+                    length: reader.read_u16()?,
+                    lang_tag_offset: reader.read_u16()?,
+                });
+            }
+        }
+
+        let mut strings = Vec::with_capacity(name_record.len());
+        for (i, record) in name_record.iter().enumerate() {
+            let offset = storage_offset as usize + record.string_offset as usize;
+            let bytes = reader
+                .sub_reader(offset, record.length as usize)
+                .and_then(|mut reader| reader.read_bytes_expected(record.length as usize))
+                .context(format!("nameRecord[{}]", i), offset)?;
+            strings.push(bytes);
+        }
+        let storage = reader
+            .sub_reader_from(storage_offset as usize)
+            .and_then(|mut reader| {
+                let len = reader.size();
+                reader.read_bytes_expected(len)
+            })
+            .context("storage", storage_offset as usize)?;
+        if version == 0 {
+            return Ok(Name::V0(NameV0 {
+                version,
+                count,
+                storage_offset,
+                name_record,
+                strings,
+                storage,
+            }));
+        }
+        let mut lang_tags = Vec::with_capacity(lang_tag_record.len());
+        for (i, record) in lang_tag_record.iter().enumerate() {
+            let offset = storage_offset as usize + record.lang_tag_offset as usize;
+            let tag = reader
+                .sub_reader(offset, record.length as usize)
+                .and_then(|mut reader| {
+                    let bytes = reader.read_bytes_expected(record.length as usize)?;
+                    decode_utf16be(&bytes).ok_or_else(|| {
+                        IOError::unexpected("langTag", "UTF-16BE string", "invalid data", 0)
+                    })
+                })
+                .context(format!("langTagRecord[{}]", i), offset)?;
+            lang_tags.push(tag);
+        }
+        Ok(Name::V1(NameV1 {
+            version,
+            count,
+            storage_offset,
+            name_record,
+            lang_tag_count: lang_tag_record.len() as u16,
+            lang_tag_record,
+            strings,
+            lang_tags,
+            storage,
+        }))
+    }
+}
+
+/// 未修改的表原样写入字符串存储区，否则重新生成：字符串按记录顺序排列，语言标签在最后，
+/// 相同的数据只保存一次。`count`、`storage_offset`、`length` 和各偏移量重新计算。
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Name {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        let records = self.name_records();
+        let (string_locations, lang_tag_locations, storage) = match self.storage().is_empty() {
+            true => self.build_storage()?,
+            false => (
+                records
+                    .iter()
+                    .map(|r| (r.length, r.string_offset))
+                    .collect(),
+                self.lang_tag_records()
+                    .iter()
+                    .map(|r| (r.length, r.lang_tag_offset))
+                    .collect(),
+                self.storage().clone(),
+            ),
+        };
+
+        let storage_offset = match self {
+            Name::V0(_) => 6 + 12 * records.len(),
+            Name::V1(_) => 8 + 12 * records.len() + 4 * lang_tag_locations.len(),
+        };
+        writer.write(self.version())?;
+        writer.write(records.len() as u16)?;
+        writer.write(storage_offset as u16)?;
+        for (record, (length, offset)) in records.iter().zip(string_locations) {
+            writer.write(record.platform_id)?;
+            writer.write(record.encoding_id)?;
+            writer.write(record.language_id)?;
+            writer.write(record.name_id)?;
+            writer.write(length)?;
+            writer.write(offset)?;
+        }
+        if let Name::V1(_) = self {
+            writer.write(lang_tag_locations.len() as u16)?;
+            for (length, offset) in lang_tag_locations {
+                writer.write(length)?;
+                writer.write(offset)?;
+            }
+        }
+        writer.write(&storage)?;
+        Ok(writer.position() - start)
+    }
+}
+
+/// 每个名称记录和语言标签的 (`length`, 偏移量)，以及字符串存储区
+#[cfg(feature = "writer")]
+type StorageLayout = (Vec<(u16, u16)>, Vec<(u16, u16)>, Vec<u8>);
+
+#[cfg(feature = "writer")]
+impl Name {
+    fn build_storage(&self) -> Result<StorageLayout, IOError> {
+        let mut storage = vec![];
+        let mut stored: HashMap<Vec<u8>, usize> = HashMap::new();
+        let mut store = |bytes: &[u8]| -> Result<(u16, u16), IOError> {
+            let offset = *stored.entry(bytes.to_vec()).or_insert_with(|| {
+                storage.extend_from_slice(bytes);
+                storage.len() - bytes.len()
+            });
+            match (u16::try_from(bytes.len()), u16::try_from(offset)) {
+                (Ok(length), Ok(offset)) => Ok((length, offset)),
+                _ => Err(IOError::UnableOperate(
+                    "name string storage exceeds 65535 bytes".to_string(),
+                )),
+            }
+        };
+        let mut string_locations = Vec::with_capacity(self.strings().len());
+        for bytes in self.strings() {
+            string_locations.push(store(bytes)?);
+        }
+        let mut lang_tag_locations = Vec::with_capacity(self.lang_tags().len());
+        for tag in self.lang_tags() {
+            lang_tag_locations.push(store(&encode_utf16be(tag))?);
+        }
+        Ok((string_locations, lang_tag_locations, storage))
+    }
+}
+
+/// `name` 表的借用视图，字符串数据直接引用字符串存储区
+#[derive(Clone, Copy)]
+pub struct NameRef<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> NameRef<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, IOError> {
+        let name = Self { data };
+        // 表头与所有记录都需要在数据范围内
+        let mut reader = SliceReader::new(data);
+        reader.skip(6)?;
+        reader.read_slice(name.count()? as usize * 12)?;
+        if name.version()? >= 1 {
+            let lang_tag_count = reader.read_u16()?;
+            reader.read_slice(lang_tag_count as usize * 4)?;
+        }
+        if name.storage_offset()? as usize > data.len() {
+            return Err(IOError::NoEnoughData);
+        }
+        Ok(name)
+    }
+
+    fn u16_at(&self, offset: usize) -> Result<u16, IOError> {
+        let mut reader = SliceReader::new(self.data);
+        reader.seek(offset)?;
+        reader.read_u16()
+    }
+
+    pub fn version(&self) -> Result<u16, IOError> {
+        self.u16_at(0)
+    }
+
+    pub fn count(&self) -> Result<u16, IOError> {
+        self.u16_at(2)
+    }
+
+    pub fn storage_offset(&self) -> Result<Offset16, IOError> {
+        self.u16_at(4)
+    }
+
+    /// 字符串存储区
+    pub fn storage(&self) -> Result<&'a [u8], IOError> {
+        Ok(&self.data[self.storage_offset()? as usize..])
+    }
+
+    pub fn record(&self, index: u16) -> Result<NameRecord, IOError> {
+        if index >= self.count()? {
+            return Err(IOError::NoEnoughData);
+        }
+        let mut reader = SliceReader::new(self.data);
+        reader.seek(6 + index as usize * 12)?;
+        NameRecord::read_from(&mut reader)
+    }
+
+    pub fn records(&self) -> Result<Vec<NameRecord>, IOError> {
+        (0..self.count()?).map(|i| self.record(i)).collect()
+    }
+
+    /// 语言标签记录，版本 0 为空
+    pub fn lang_tag_records(&self) -> Result<Vec<LangTagRecord>, IOError> {
+        if self.version()? == 0 {
+            return Ok(vec![]);
+        }
+        let mut reader = SliceReader::new(self.data);
+        reader.seek(6 + self.count()? as usize * 12)?;
+        let lang_tag_count = reader.read_u16()?;
+        let mut records = Vec::with_capacity(lang_tag_count as usize);
+        for _ in 0..lang_tag_count {
+            records.push(LangTagRecord {
+                length: reader.read_u16()?,
+                lang_tag_offset: reader.read_u16()?,
+            });
+        }
+        Ok(records)
+    }
+
+    /// 记录对应的未解码字符串数据
+    pub fn string_data(&self, record: &NameRecord) -> Result<&'a [u8], IOError> {
+        let mut reader = SliceReader::new(self.storage()?);
+        reader.seek(record.string_offset as usize)?;
+        reader.read_slice(record.length as usize)
+    }
+
+    /// 记录对应的字符串，不支持的编码返回 `None`，见 [decode_name_string]
+    pub fn string(&self, record: &NameRecord) -> Result<Option<String>, IOError> {
+        let bytes = self.string_data(record)?;
+        Ok(decode_name_string(
+            record.platform_id,
+            record.encoding_id,
+            bytes,
+        ))
+    }
+}
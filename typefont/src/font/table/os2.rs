@@ -1,5 +1,12 @@
 use super::super::{FWord, Tag, UFWord};
+use crate::font::io::ReadFrom;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 pub enum Os2 {
     V5(Os2V5),
@@ -71,3 +78,234 @@ pub struct Os2V0 {
     us_win_ascent: UFWord,
     us_win_descent: UFWord,
 }
+
+impl Os2 {
+    pub fn version(&self) -> u16 {
+        self.v0().version
+    }
+
+    /// 所有版本共有的字段
+    pub fn v0(&self) -> &Os2V0 {
+        match self {
+            Os2::V5(v) => &v.base.base.base,
+            Os2::V4(v) | Os2::V3(v) | Os2::V2(v) => &v.base.base,
+            Os2::V1(v) => &v.base,
+            Os2::V0(v) => v,
+        }
+    }
+}
+
+impl Os2V0 {
+    pub fn x_avg_char_width(&self) -> FWord {
+        self.x_avg_char_width
+    }
+
+    pub fn us_weight_class(&self) -> u16 {
+        self.us_weight_class
+    }
+
+    pub fn us_width_class(&self) -> u16 {
+        self.us_width_class
+    }
+
+    pub fn fs_type(&self) -> u16 {
+        self.fs_type
+    }
+
+    pub fn ach_vend_id(&self) -> &Tag {
+        &self.ach_vend_id
+    }
+
+    pub fn fs_selection(&self) -> u16 {
+        self.fs_selection
+    }
+
+    pub fn s_typo_ascender(&self) -> FWord {
+        self.s_typo_ascender
+    }
+
+    pub fn s_typo_descender(&self) -> FWord {
+        self.s_typo_descender
+    }
+
+    pub fn s_typo_line_gap(&self) -> FWord {
+        self.s_typo_line_gap
+    }
+
+    pub fn us_win_ascent(&self) -> UFWord {
+        self.us_win_ascent
+    }
+
+    pub fn us_win_descent(&self) -> UFWord {
+        self.us_win_descent
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for Os2V0 {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        Ok(Self {
+            version: reader.read_u16()?,
+            x_avg_char_width: reader.read_i16()?,
+            us_weight_class: reader.read_u16()?,
+            us_width_class: reader.read_u16()?,
+            fs_type: reader.read_u16()?,
+            y_subscript_x_size: reader.read_i16()?,
+            y_subscript_y_size: reader.read_i16()?,
+            y_subscript_x_offset: reader.read_i16()?,
+            y_subscript_y_offset: reader.read_i16()?,
+            y_superscript_x_size: reader.read_i16()?,
+            y_superscript_y_size: reader.read_i16()?,
+            y_superscript_x_offset: reader.read_i16()?,
+            y_superscript_y_offset: reader.read_i16()?,
+            y_strikeout_size: reader.read_i16()?,
+            y_strikeout_position: reader.read_i16()?,
+            s_family_class: reader.read_i16()?,
+            panose: {
+                let offset = reader.position();
+                reader
+                    .read_bytes_expected(10)?
+                    .try_into()
+                    .map_err(|bytes: Vec<u8>| {
+                        IOError::unexpected("panose", "10 bytes", bytes.len(), offset)
+                    })?
+            },
+            ul_unicode_range_1: reader.read_u32()?,
+            ul_unicode_range_2: reader.read_u32()?,
+            ul_unicode_range_3: reader.read_u32()?,
+            ul_unicode_range_4: reader.read_u32()?,
+            ach_vend_id: Tag::read_from(reader)?,
+            fs_selection: reader.read_u16()?,
+            us_first_char_index: reader.read_u16()?,
+            us_last_char_index: reader.read_u16()?,
+            s_typo_ascender: reader.read_i16()?,
+            s_typo_descender: reader.read_i16()?,
+            s_typo_line_gap: reader.read_i16()?,
+            us_win_ascent: reader.read_u16()?,
+            us_win_descent: reader.read_u16()?,
+        })
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for Os2 {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let v0 = Os2V0::read_from(reader)?;
+        let version = v0.version;
+        if version == 0 {
+            return Ok(Os2::V0(v0));
+        }
+        let v1 = Os2V1 {
+            base: v0,
+            ul_code_page_range1: reader.read_u32()?,
+            ul_code_page_range2: reader.read_u32()?,
+        };
+        if version == 1 {
+            return Ok(Os2::V1(v1));
+        }
+        let v4 = Os2V4 {
+            base: v1,
+            sx_height: reader.read_i16()?,
+            s_cap_height: reader.read_i16()?,
+            us_default_char: reader.read_u16()?,
+            us_break_char: reader.read_u16()?,
+            us_max_context: reader.read_u16()?,
+        };
+        match version {
+            2 => Ok(Os2::V2(v4)),
+            3 => Ok(Os2::V3(v4)),
+            4 => Ok(Os2::V4(v4)),
+            _ => Ok(Os2::V5(Os2V5 {
+                base: v4,
+                us_lower_optical_point_size: reader.read_u16()?,
+                us_upper_optical_point_size: reader.read_u16()?,
+            })),
+        }
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Os2V0 {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(self.version)?;
+        writer.write(self.x_avg_char_width)?;
+        writer.write(self.us_weight_class)?;
+        writer.write(self.us_width_class)?;
+        writer.write(self.fs_type)?;
+        writer.write(self.y_subscript_x_size)?;
+        writer.write(self.y_subscript_y_size)?;
+        writer.write(self.y_subscript_x_offset)?;
+        writer.write(self.y_subscript_y_offset)?;
+        writer.write(self.y_superscript_x_size)?;
+        writer.write(self.y_superscript_y_size)?;
+        writer.write(self.y_superscript_x_offset)?;
+        writer.write(self.y_superscript_y_offset)?;
+        writer.write(self.y_strikeout_size)?;
+        writer.write(self.y_strikeout_position)?;
+        writer.write(self.s_family_class)?;
+        writer.write(self.panose.as_slice())?;
+        writer.write(self.ul_unicode_range_1)?;
+        writer.write(self.ul_unicode_range_2)?;
+        writer.write(self.ul_unicode_range_3)?;
+        writer.write(self.ul_unicode_range_4)?;
+        writer.write(self.ach_vend_id.to_bytes().as_slice())?;
+        writer.write(self.fs_selection)?;
+        writer.write(self.us_first_char_index)?;
+        writer.write(self.us_last_char_index)?;
+        writer.write(self.s_typo_ascender)?;
+        writer.write(self.s_typo_descender)?;
+        writer.write(self.s_typo_line_gap)?;
+        writer.write(self.us_win_ascent)?;
+        writer.write(self.us_win_descent)?;
+        Ok(writer.position() - start)
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Os2V1 {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        self.base.write_to(writer)?;
+        writer.write(self.ul_code_page_range1)?;
+        writer.write(self.ul_code_page_range2)?;
+        Ok(writer.position() - start)
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Os2V4 {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        self.base.write_to(writer)?;
+        writer.write(self.sx_height)?;
+        writer.write(self.s_cap_height)?;
+        writer.write(self.us_default_char)?;
+        writer.write(self.us_break_char)?;
+        writer.write(self.us_max_context)?;
+        Ok(writer.position() - start)
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Os2V5 {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        self.base.write_to(writer)?;
+        writer.write(self.us_lower_optical_point_size)?;
+        writer.write(self.us_upper_optical_point_size)?;
+        Ok(writer.position() - start)
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Os2 {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        match self {
+            Os2::V5(v) => v.write_to(writer),
+            Os2::V4(v) | Os2::V3(v) | Os2::V2(v) => v.write_to(writer),
+            Os2::V1(v) => v.write_to(writer),
+            Os2::V0(v) => v.write_to(writer),
+        }?;
+        Ok(writer.position() - start)
+    }
+}
@@ -1,4 +1,11 @@
+use crate::font::io::ReadFrom;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 pub struct PCLT {
     major_version: u16,
@@ -21,3 +28,63 @@ pub struct PCLT {
 }
 
 impl_named!(PCLT, "PCLT");
+
+fn read_i8_array<const N: usize>(reader: &mut ReaderBoxed) -> Result<[i8; N], IOError> {
+    let mut array = [0i8; N];
+    for v in array.iter_mut() {
+        *v = reader.read_i8()?;
+    }
+    Ok(array)
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for PCLT {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        Ok(Self {
+            major_version: reader.read_u16()?,
+            minor_version: reader.read_u16()?,
+            font_number: reader.read_u32()?,
+            pitch: reader.read_u16()?,
+            x_height: reader.read_u16()?,
+            style: reader.read_u16()?,
+            type_family: reader.read_u16()?,
+            cap_height: reader.read_u16()?,
+            symbol_set: reader.read_u16()?,
+            typeface: read_i8_array(reader)?,
+            character_complement: read_i8_array(reader)?,
+            file_name: read_i8_array(reader)?,
+            stroke_weight: reader.read_i8()?,
+            width_type: reader.read_i8()?,
+            serif_style: reader.read_u8()?,
+            reserved: reader.read_u8()?,
+        })
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for PCLT {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(self.major_version)?;
+        writer.write(self.minor_version)?;
+        writer.write(self.font_number)?;
+        writer.write(self.pitch)?;
+        writer.write(self.x_height)?;
+        writer.write(self.style)?;
+        writer.write(self.type_family)?;
+        writer.write(self.cap_height)?;
+        writer.write(self.symbol_set)?;
+        for &v in self
+            .typeface
+            .iter()
+            .chain(&self.character_complement)
+            .chain(&self.file_name)
+        {
+            writer.write(v)?;
+        }
+        writer.write(self.stroke_weight)?;
+        writer.write(self.width_type)?;
+        writer.write(self.serif_style)?;
+        writer.write(self.reserved)?;
+        Ok(writer.position() - start)
+    }
+}
@@ -1,6 +1,13 @@
 use super::super::{Fixed, Version16Dot16};
 use crate::font::FWord;
+use crate::font::io::ReadFrom;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 pub enum Post {
     V1(PostV1),
@@ -51,3 +58,195 @@ pub struct PostV3 {
     // 展开
     header: PostHeader,
 }
+
+impl ReadFrom<ReaderBoxed<'_>> for PostHeader {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        Ok(Self {
+            version: reader.read_u32()?,
+            italic_angle: reader.read_i32()?,
+            underline_position: reader.read_i16()?,
+            underline_thickness: reader.read_i16()?,
+            is_fixed_pitch: reader.read_u32()?,
+            min_mem_type42: reader.read_u32()?,
+            max_mem_type42: reader.read_u32()?,
+            min_mem_type1: reader.read_u32()?,
+            max_mem_type1: reader.read_u32()?,
+        })
+    }
+}
+
+impl PostHeader {
+    pub fn version(&self) -> Version16Dot16 {
+        self.version
+    }
+
+    pub fn italic_angle(&self) -> Fixed {
+        self.italic_angle
+    }
+
+    pub fn underline_position(&self) -> FWord {
+        self.underline_position
+    }
+
+    pub fn underline_thickness(&self) -> FWord {
+        self.underline_thickness
+    }
+
+    pub fn is_fixed_pitch(&self) -> bool {
+        self.is_fixed_pitch != 0
+    }
+
+    pub fn min_mem_type42(&self) -> u32 {
+        self.min_mem_type42
+    }
+
+    pub fn max_mem_type42(&self) -> u32 {
+        self.max_mem_type42
+    }
+
+    pub fn min_mem_type1(&self) -> u32 {
+        self.min_mem_type1
+    }
+
+    pub fn max_mem_type1(&self) -> u32 {
+        self.max_mem_type1
+    }
+}
+
+impl PostV2 {
+    pub fn num_glyphs(&self) -> u16 {
+        self.num_glyphs
+    }
+
+    pub fn glyph_name_index(&self) -> &Vec<u16> {
+        &self.glyph_name_index
+    }
+
+    /// 按顺序排列的 Pascal 字符串
+    pub fn string_data(&self) -> &str {
+        &self.string_data
+    }
+}
+
+#[allow(deprecated)]
+impl PostV2_5 {
+    pub fn num_glyphs(&self) -> u16 {
+        self.num_glyphs
+    }
+
+    pub fn offset(&self) -> &Vec<i8> {
+        &self.offset
+    }
+}
+
+impl Post {
+    #[allow(deprecated)]
+    pub fn header(&self) -> &PostHeader {
+        match self {
+            Post::V1(v) => &v.header,
+            Post::V2(v) => &v.header,
+            Post::V2_5(v) => &v.header,
+            Post::V3(v) => &v.header,
+        }
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for Post {
+    #[allow(deprecated)]
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let header = PostHeader::read_from(reader)?;
+        match header.version {
+            0x00010000 => Ok(Post::V1(PostV1 { header })),
+            0x00020000 => {
+                let num_glyphs = reader.read_u16()?;
+                let mut glyph_name_index = Vec::with_capacity(num_glyphs as usize);
+                for _ in 0..num_glyphs {
+                    glyph_name_index.push(reader.read_u16()?);
+                }
+                // 索引 0~257 为标准 Macintosh 字形名，其余按顺序指向 Pascal 字符串
+                let num_names = glyph_name_index
+                    .iter()
+                    .filter(|&&i| i >= 258)
+                    .map(|&i| i - 257)
+                    .max()
+                    .unwrap_or(0);
+                let mut string_data = String::new();
+                for _ in 0..num_names {
+                    let len = reader.read_u8()?;
+                    string_data.push(len as char);
+                    let bs = reader.read_bytes_expected(len as usize)?;
+                    string_data.extend(bs.iter().map(|&b| b as char));
+                }
+                Ok(Post::V2(PostV2 {
+                    header,
+                    num_glyphs,
+                    glyph_name_index,
+                    string_data,
+                }))
+            }
+            0x00025000 => {
+                let num_glyphs = reader.read_u16()?;
+                let mut offset = Vec::with_capacity(num_glyphs as usize);
+                for _ in 0..num_glyphs {
+                    offset.push(reader.read_i8()?);
+                }
+                Ok(Post::V2_5(PostV2_5 {
+                    header,
+                    num_glyphs,
+                    offset,
+                }))
+            }
+            0x00030000 => Ok(Post::V3(PostV3 { header })),
+            version => Err(IOError::unexpected(
+                "version",
+                "1.0, 2.0, 2.5 or 3.0",
+                format!("0x{:08X}", version),
+                0,
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for PostHeader {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(self.version)?;
+        writer.write(self.italic_angle)?;
+        writer.write(self.underline_position)?;
+        writer.write(self.underline_thickness)?;
+        writer.write(self.is_fixed_pitch)?;
+        writer.write(self.min_mem_type42)?;
+        writer.write(self.max_mem_type42)?;
+        writer.write(self.min_mem_type1)?;
+        writer.write(self.max_mem_type1)?;
+        Ok(writer.position() - start)
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Post {
+    #[allow(deprecated)]
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        self.header().write_to(writer)?;
+        match self {
+            Post::V1(_) | Post::V3(_) => {}
+            Post::V2(v) => {
+                writer.write(v.glyph_name_index.len() as u16)?;
+                for &index in &v.glyph_name_index {
+                    writer.write(index)?;
+                }
+                let bs: Vec<u8> = v.string_data.chars().map(|c| c as u8).collect();
+                writer.write(bs)?;
+            }
+            Post::V2_5(v) => {
+                writer.write(v.offset.len() as u16)?;
+                for &offset in &v.offset {
+                    writer.write(offset)?;
+                }
+            }
+        }
+        Ok(writer.position() - start)
+    }
+}
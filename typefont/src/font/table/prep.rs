@@ -1,7 +1,38 @@
+use crate::font::io::ReadWith;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 pub struct Prep {
     data: Vec<u8>,
 }
 
 impl_named!(Prep, "prep");
+
+impl Prep {
+    pub fn data(&self) -> &Vec<u8> {
+        &self.data
+    }
+}
+
+/// 参数为表长度
+impl ReadWith<ReaderBoxed<'_>, usize> for Prep {
+    fn read_with(reader: &mut ReaderBoxed, length: usize) -> Result<Self, IOError> {
+        Ok(Self {
+            data: reader.read_bytes_expected(length)?,
+        })
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Prep {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(&self.data)?;
+        Ok(writer.position() - start)
+    }
+}
@@ -1,5 +1,12 @@
 use crate::font::{FWord, UFWord, Version16Dot16};
+use crate::font::io::ReadFrom;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 pub struct Vhea {
     header: VheaHeader,
@@ -59,3 +66,119 @@ pub struct VheaHeader1_1 {
 }
 
 impl_named!(Vhea, "vhea");
+
+impl Vhea {
+    pub fn header(&self) -> &VheaHeader {
+        &self.header
+    }
+
+    pub fn num_of_long_ver_metrics(&self) -> u16 {
+        match &self.header {
+            VheaHeader::V1_0(h) => h.num_of_long_ver_metrics,
+            VheaHeader::V1_1(h) => h.num_of_long_ver_metrics,
+        }
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for Vhea {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let version = reader.read_u32()?;
+        let header = match version {
+            0x00010000 => VheaHeader::V1_0(VheaHeader1_0 {
+                version,
+                ascent: reader.read_i16()?,
+                descent: reader.read_i16()?,
+                line_gap: reader.read_i16()?,
+                advance_height_max: reader.read_u16()?,
+                min_top_side_bearing: reader.read_i16()?,
+                min_bottom_side_bearing: reader.read_i16()?,
+                y_max_extent: reader.read_i16()?,
+                caret_slope_rise: reader.read_i16()?,
+                caret_slope_run: reader.read_i16()?,
+                caret_offset: reader.read_i16()?,
+                reserved1: reader.read_i16()?,
+                reserved2: reader.read_i16()?,
+                reserved3: reader.read_i16()?,
+                reserved4: reader.read_i16()?,
+                metric_data_format: reader.read_i16()?,
+                num_of_long_ver_metrics: reader.read_u16()?,
+            }),
+            0x00011000 => VheaHeader::V1_1(VheaHeader1_1 {
+                version,
+                vert_typo_ascender: reader.read_i16()?,
+                vert_typo_descender: reader.read_i16()?,
+                vert_typo_line_gap: reader.read_i16()?,
+                advance_height_max: reader.read_i16()?,
+                min_top_side_bearing: reader.read_i16()?,
+                min_bottom_side_bearing: reader.read_i16()?,
+                y_max_extent: reader.read_i16()?,
+                caret_slope_rise: reader.read_i16()?,
+                caret_slope_run: reader.read_i16()?,
+                caret_offset: reader.read_i16()?,
+                reserved1: reader.read_i16()?,
+                reserved2: reader.read_i16()?,
+                reserved3: reader.read_i16()?,
+                reserved4: reader.read_i16()?,
+                metric_data_format: reader.read_i16()?,
+                num_of_long_ver_metrics: reader.read_u16()?,
+            }),
+            version => {
+                return Err(IOError::unexpected(
+                    "version",
+                    "1.0 or 1.1",
+                    format!("0x{:08X}", version),
+                    0,
+                ));
+            }
+        };
+        Ok(Self { header })
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for Vhea {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        match &self.header {
+            VheaHeader::V1_0(h) => {
+                writer.write(h.version)?;
+                writer.write(h.ascent)?;
+                writer.write(h.descent)?;
+                writer.write(h.line_gap)?;
+                writer.write(h.advance_height_max)?;
+                writer.write(h.min_top_side_bearing)?;
+                writer.write(h.min_bottom_side_bearing)?;
+                writer.write(h.y_max_extent)?;
+                writer.write(h.caret_slope_rise)?;
+                writer.write(h.caret_slope_run)?;
+                writer.write(h.caret_offset)?;
+                writer.write(h.reserved1)?;
+                writer.write(h.reserved2)?;
+                writer.write(h.reserved3)?;
+                writer.write(h.reserved4)?;
+                writer.write(h.metric_data_format)?;
+                writer.write(h.num_of_long_ver_metrics)?;
+            }
+            VheaHeader::V1_1(h) => {
+                writer.write(h.version)?;
+                writer.write(h.vert_typo_ascender)?;
+                writer.write(h.vert_typo_descender)?;
+                writer.write(h.vert_typo_line_gap)?;
+                writer.write(h.advance_height_max)?;
+                writer.write(h.min_top_side_bearing)?;
+                writer.write(h.min_bottom_side_bearing)?;
+                writer.write(h.y_max_extent)?;
+                writer.write(h.caret_slope_rise)?;
+                writer.write(h.caret_slope_run)?;
+                writer.write(h.caret_offset)?;
+                writer.write(h.reserved1)?;
+                writer.write(h.reserved2)?;
+                writer.write(h.reserved3)?;
+                writer.write(h.reserved4)?;
+                writer.write(h.metric_data_format)?;
+                writer.write(h.num_of_long_ver_metrics)?;
+            }
+        }
+        Ok(writer.position() - start)
+    }
+}
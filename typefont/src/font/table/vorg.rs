@@ -1,4 +1,11 @@
+use crate::font::io::ReadFrom;
 use crate::impl_named;
+use crate::io::error::IOError;
+use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 pub struct VORG {
     /// 1
@@ -16,3 +23,75 @@ pub struct VertOriginYMetrics {
     glyph_index: u16,
     vert_origin_y: i16,
 }
+
+impl VORG {
+    pub fn major_version(&self) -> u16 {
+        self.major_version
+    }
+
+    pub fn minor_version(&self) -> u16 {
+        self.minor_version
+    }
+
+    pub fn default_vert_origin_y(&self) -> i16 {
+        self.default_vert_origin_y
+    }
+
+    pub fn num_vert_origin_ymetrics(&self) -> u16 {
+        self.num_vert_origin_ymetrics
+    }
+
+    pub fn vert_origin_ymetrics(&self) -> &Vec<VertOriginYMetrics> {
+        &self.vert_origin_ymetrics
+    }
+
+    /// 字形的垂直原点 y 坐标，`vert_origin_ymetrics` 按字形索引升序排列
+    pub fn vert_origin_y(&self, glyph_index: u16) -> i16 {
+        match self
+            .vert_origin_ymetrics
+            .binary_search_by_key(&glyph_index, |m| m.glyph_index)
+        {
+            Ok(i) => self.vert_origin_ymetrics[i].vert_origin_y,
+            Err(_) => self.default_vert_origin_y,
+        }
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for VORG {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let major_version = reader.read_u16()?;
+        let minor_version = reader.read_u16()?;
+        let default_vert_origin_y = reader.read_i16()?;
+        let num_vert_origin_ymetrics = reader.read_u16()?;
+        let mut vert_origin_ymetrics = Vec::with_capacity(num_vert_origin_ymetrics as usize);
+        for _ in 0..num_vert_origin_ymetrics {
+            vert_origin_ymetrics.push(VertOriginYMetrics {
+                glyph_index: reader.read_u16()?,
+                vert_origin_y: reader.read_i16()?,
+            });
+        }
+        Ok(Self {
+            major_version,
+            minor_version,
+            default_vert_origin_y,
+            num_vert_origin_ymetrics,
+            vert_origin_ymetrics,
+        })
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for VORG {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(self.major_version)?;
+        writer.write(self.minor_version)?;
+        writer.write(self.default_vert_origin_y)?;
+        writer.write(self.vert_origin_ymetrics.len() as u16)?;
+        for m in &self.vert_origin_ymetrics {
+            writer.write(m.glyph_index)?;
+            writer.write(m.vert_origin_y)?;
+        }
+        Ok(writer.position() - start)
+    }
+}
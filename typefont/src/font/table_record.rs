@@ -2,6 +2,10 @@ use super::{Offset32, Tag};
 use crate::font::io::ReadFrom;
 use crate::io::error::IOError;
 use crate::io::reader::ReaderBoxed;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
 
 /// 表标签是赋予`OpenType`字体文件中表格的名称。
 /// `table_records`数组使得给定字体只包含它实际需要的那些表格成为可能。
@@ -38,6 +42,33 @@ pub struct TableRecord {
     length: u32,
 }
 
+impl TableRecord {
+    pub fn new(table_tag: Tag, checksum: u32, offset: Offset32, length: u32) -> Self {
+        Self {
+            table_tag,
+            checksum,
+            offset,
+            length,
+        }
+    }
+
+    pub fn tag(&self) -> &Tag {
+        &self.table_tag
+    }
+
+    pub fn checksum(&self) -> u32 {
+        self.checksum
+    }
+
+    pub fn offset(&self) -> Offset32 {
+        self.offset
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+}
+
 /// `head`表格在校验和计算中是一个特例，
 /// 因为它包含一个`checksum_adjustment`字段，
 /// 该字段是在表格的校验和被计算并写入表格目录条目之后计算并写入的，
@@ -57,15 +88,81 @@ pub struct TableRecord {
 ///
 /// 在字体集合文件中，表格校验和必须反映这些表格在集合文件中的状态。
 /// `head`表格中的`checksumAdjustment`字段不用于集合文件中，可以设置为零。
-pub fn calc_table_checksum(table: &Vec<u32>) -> u32 {
-    let mut sum = 0u32;
-    for v in table {
-        sum += v;
+pub fn calc_table_checksum(table: &[u8]) -> u32 {
+    table.chunks(4).fold(0u32, |sum, chunk| {
+        // 长度不是 4 的倍数时以 0 填充
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// 校验和与表记录不一致的表
+pub struct ChecksumMismatch {
+    tag: Tag,
+    /// 表记录中的校验和
+    recorded: u32,
+    /// 根据表数据计算的校验和
+    computed: u32,
+}
+
+impl ChecksumMismatch {
+    pub fn new(tag: Tag, recorded: u32, computed: u32) -> Self {
+        Self {
+            tag,
+            recorded,
+            computed,
+        }
+    }
+
+    pub fn tag(&self) -> &Tag {
+        &self.tag
     }
-    sum
+
+    pub fn recorded(&self) -> u32 {
+        self.recorded
+    }
+
+    pub fn computed(&self) -> u32 {
+        self.computed
+    }
+}
+
+/// 字体完整性校验结果
+pub struct ChecksumReport {
+    mismatches: Vec<ChecksumMismatch>,
+    /// `head.checksum_adjustment` 的记录值与计算值，字体没有 `head` 表时为 `None`
+    checksum_adjustment: Option<(u32, u32)>,
 }
 
-impl ReadFrom<ReaderBoxed> for TableRecord {
+impl ChecksumReport {
+    pub fn new(mismatches: Vec<ChecksumMismatch>, checksum_adjustment: Option<(u32, u32)>) -> Self {
+        Self {
+            mismatches,
+            checksum_adjustment,
+        }
+    }
+
+    /// 校验和与表记录不一致的表
+    pub fn mismatches(&self) -> &Vec<ChecksumMismatch> {
+        &self.mismatches
+    }
+
+    /// `head.checksum_adjustment` 的 (记录值, 计算值)
+    pub fn checksum_adjustment(&self) -> Option<(u32, u32)> {
+        self.checksum_adjustment
+    }
+
+    /// 所有表的校验和及 `checksum_adjustment` 均正确
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+            && self
+                .checksum_adjustment
+                .is_none_or(|(recorded, computed)| recorded == computed)
+    }
+}
+
+impl ReadFrom<ReaderBoxed<'_>> for TableRecord {
     fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
         Ok(Self {
             table_tag: Tag::read_from(reader)?,
@@ -75,3 +172,15 @@ impl ReadFrom<ReaderBoxed> for TableRecord {
         })
     }
 }
+
+#[cfg(feature = "writer")]
+impl<'w> WriteTo<dyn Writer + 'w> for TableRecord {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let start = writer.position();
+        writer.write(self.table_tag.to_bytes().as_slice())?;
+        writer.write(self.checksum)?;
+        writer.write(self.offset)?;
+        writer.write(self.length)?;
+        Ok(writer.position() - start)
+    }
+}
@@ -1,4 +1,26 @@
 use super::{Offset32, Tag};
+use crate::font::io::ReadFrom;
+use crate::font::table::dsig::DSIG;
+use crate::font::open_type::{OpenType, TableCache, open_file};
+use crate::io::error::{ErrorContext, IOError};
+use crate::io::reader::ReaderBoxed;
+use crate::io::slice_reader::SliceReader;
+use std::cell::RefCell;
+use std::rc::Rc;
+#[cfg(feature = "writer")]
+use crate::font::io::WriteTo;
+#[cfg(feature = "writer")]
+use crate::font::open_type::{SerializedTables, TableDirectory};
+#[cfg(feature = "writer")]
+use crate::font::table_record::{TableRecord, calc_table_checksum};
+#[cfg(feature = "writer")]
+use crate::io::vec_writer::VecWriter;
+#[cfg(feature = "writer")]
+use crate::io::writer::{Write, Writer};
+#[cfg(feature = "writer")]
+use std::collections::HashMap;
+#[cfg(feature = "dsig")]
+use crate::font::table::dsig::SignatureCoverage;
 
 /// # 字体集合文件结构
 ///
@@ -36,8 +58,236 @@ use super::{Offset32, Tag};
 ///
 /// 包含TrueType字形轮廓的集合文件应使用.TTC作为文件扩展名。
 /// 包含CFF或CFF2轮廓的集合文件应使用.OTC作为文件扩展名。
+///
+/// 集合中的字体共享同一个读取器和表缓存，多个字体引用的同一个表只读取一次。
 pub struct Ttc<H: TtcHeader> {
     header: H,
+    /// 与 `header.table_directory_offsets` 一一对应
+    fonts: Vec<OpenType>,
+    reader: Rc<RefCell<ReaderBoxed<'static>>>,
+}
+
+impl<H: TtcHeader> Ttc<H> {
+    pub fn header(&self) -> &H {
+        &self.header
+    }
+
+    pub fn fonts(&self) -> &Vec<OpenType> {
+        &self.fonts
+    }
+
+    pub fn font(&self, index: usize) -> Option<&OpenType> {
+        self.fonts.get(index)
+    }
+
+    /// 读取版本 `2` 头部中 `dsig_offset` 处的 `DSIG` 表，没有签名时返回 `None`
+    pub fn dsig(&self) -> Result<Option<DSIG>, IOError> {
+        let header = match self.header.as_v2() {
+            Some(header) if header.has_dsig() => header,
+            _ => return Ok(None),
+        };
+        let offset = header.dsig_offset as usize;
+        let mut reader = self.reader.borrow_mut();
+        reader
+            .sub_reader(offset, header.dsig_length as usize)
+            .and_then(|mut reader| {
+                let dsig = DSIG::read_from(&mut reader);
+                dsig.at(reader.position())
+            })
+            .in_table(&Tag::from(*b"DSIG"), offset)
+            .map(Some)
+    }
+}
+
+impl Ttc<Box<dyn TtcHeader>> {
+    /// 读取 `TTC` 头部和每个字体的表目录，表在访问时才读取
+    pub fn new(mut reader: ReaderBoxed<'static>) -> Result<Self, IOError> {
+        let header = <Box<dyn TtcHeader>>::read_from(&mut reader)
+            .at(reader.position())
+            .context("ttcHeader", 0)?;
+        let reader = Rc::new(RefCell::new(reader));
+        let cache = TableCache::default();
+        let mut fonts = Vec::with_capacity(header.table_directory_offsets().len());
+        for (i, &offset) in header.table_directory_offsets().iter().enumerate() {
+            let font = OpenType::read_at(reader.clone(), offset as usize, cache.clone())
+                .context(format!("font[{}]", i), 0)?;
+            fonts.push(font);
+        }
+        Ok(Self {
+            header,
+            fonts,
+            reader,
+        })
+    }
+
+    /// 从内存数据读取，如 `Vec<u8>`、`Arc<[u8]>`、`&'static [u8]`
+    pub fn from_bytes<B: AsRef<[u8]> + 'static>(bytes: B) -> Result<Self, IOError> {
+        Self::new(Box::new(SliceReader::new(bytes)))
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<H: TtcHeader> Ttc<H> {
+    /// 将集合拆分为单独的字体文件数据，每个字体的表目录和校验和会重新计算
+    pub fn split(&self) -> Result<Vec<Vec<u8>>, IOError> {
+        let mut files = Vec::with_capacity(self.fonts.len());
+        for font in &self.fonts {
+            let mut writer = VecWriter::new();
+            font.write_to(&mut writer)?;
+            files.push(writer.into_inner());
+        }
+        Ok(files)
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<H: TtcHeader> Ttc<H> {
+    /// 写入去除签名的集合：头部的 `DSIG` 字段置为 0，并去除各字体中的 `DSIG` 表
+    pub fn strip_dsig(&self, writer: &mut dyn Writer) -> Result<usize, IOError> {
+        let mut fonts = Vec::with_capacity(self.fonts.len());
+        for font in &self.fonts {
+            let tables = font
+                .serialize_tables()?
+                .into_iter()
+                .filter(|(tag, _)| tag.to_bytes() != *b"DSIG")
+                .collect();
+            fonts.push((font.table_directory().sfnt_version(), tables));
+        }
+        write_collection_tables(fonts, self.header.major_version(), writer)
+    }
+
+    /// 检查头部 `DSIG` 中的签名摘要是否与去除签名后的集合数据一致
+    ///
+    /// 摘要按文件中的原始数据计算：头部的 `DSIG` 字段置为 0，并去除 `DSIG` 表的数据，
+    /// 其余数据保持不变，因此与重新写入集合的 [Ttc::strip_dsig] 不同。
+    #[cfg(feature = "dsig")]
+    pub fn dsig_coverage(&self) -> Result<SignatureCoverage, IOError> {
+        let (dsig, header) = match (self.dsig()?, self.header.as_v2()) {
+            (Some(dsig), Some(header)) => (dsig, header),
+            _ => return Ok(SignatureCoverage::Unsigned),
+        };
+        let mut bytes = {
+            let mut reader = self.reader.borrow_mut();
+            let size = reader.size();
+            reader.seek(0)?;
+            reader.read_bytes_expected(size)?
+        };
+        // `DSIG` 字段位于偏移量数组之后
+        let fields = 12 + 4 * header.num_fonts as usize;
+        if let Some(fields) = bytes.get_mut(fields..fields + 12) {
+            fields.fill(0);
+        }
+        let start = (header.dsig_offset as usize).min(bytes.len());
+        let end = (start + (header.dsig_length as usize).next_multiple_of(4)).min(bytes.len());
+        bytes.drain(start..end);
+        Ok(dsig.coverage(&bytes))
+    }
+}
+
+/// 按原头部的版本重新写入集合，见 [write_collection]
+#[cfg(feature = "writer")]
+impl<'w, H: TtcHeader> WriteTo<dyn Writer + 'w> for Ttc<H> {
+    fn write_to(&self, writer: &mut (dyn Writer + 'w)) -> Result<usize, IOError> {
+        let fonts: Vec<&OpenType> = self.fonts.iter().collect();
+        write_collection(&fonts, self.header.major_version(), writer)
+    }
+}
+
+/// 将多个字体写入为 `TTC` 版本 `major_version`（`1` 或 `2`）的字体集合
+///
+/// 所有字体的表按序列化后的数据去重，内容相同的表只写入一次，由各字体的表目录共同引用。
+/// 集合中不使用 `head.checksum_adjustment`，写入时置为 0；
+/// 版本 `2` 的头部不包含数字签名，`DSIG` 相关字段为 0。
+#[cfg(feature = "writer")]
+pub fn write_collection(
+    fonts: &[&OpenType],
+    major_version: u16,
+    writer: &mut dyn Writer,
+) -> Result<usize, IOError> {
+    let mut fonts_tables = Vec::with_capacity(fonts.len());
+    for font in fonts {
+        fonts_tables.push((
+            font.table_directory().sfnt_version(),
+            font.serialize_tables()?,
+        ));
+    }
+    write_collection_tables(fonts_tables, major_version, writer)
+}
+
+/// 写入集合，`fonts` 为每个字体的 `sfnt_version` 与序列化后的表
+#[cfg(feature = "writer")]
+fn write_collection_tables(
+    fonts: Vec<(u32, SerializedTables)>,
+    major_version: u16,
+    writer: &mut dyn Writer,
+) -> Result<usize, IOError> {
+    let header_len = match major_version {
+        1 => 12 + 4 * fonts.len(),
+        2 => 24 + 4 * fonts.len(),
+        version => {
+            return Err(IOError::UnableOperate(format!(
+                "unsupported TTC version {}",
+                version
+            )));
+        }
+    };
+    let start = writer.position();
+
+    let mut offset = start + header_len;
+    let mut table_directory_offsets = Vec::with_capacity(fonts.len());
+    for (_, tables) in &fonts {
+        table_directory_offsets.push(offset as Offset32);
+        offset += 12 + 16 * tables.len();
+    }
+
+    // 内容相同的表共享同一个偏移量
+    let mut table_offsets: HashMap<&[u8], Offset32> = HashMap::new();
+    let mut unique_tables: Vec<&[u8]> = vec![];
+    let mut directories = Vec::with_capacity(fonts.len());
+    for (sfnt_version, tables) in &fonts {
+        let mut records = Vec::with_capacity(tables.len());
+        for (tag, bytes) in tables {
+            let table_offset = *table_offsets.entry(bytes.as_slice()).or_insert_with(|| {
+                let table_offset = offset as Offset32;
+                unique_tables.push(bytes);
+                offset += bytes.len().next_multiple_of(4);
+                table_offset
+            });
+            records.push(TableRecord::new(
+                tag.clone(),
+                calc_table_checksum(bytes),
+                table_offset,
+                bytes.len() as u32,
+            ));
+        }
+        directories.push(TableDirectory::new(*sfnt_version, records)?);
+    }
+
+    writer.write(b"ttcf".as_slice())?;
+    writer.write(major_version)?;
+    writer.write(0u16)?;
+    writer.write(fonts.len() as u32)?;
+    for &table_directory_offset in &table_directory_offsets {
+        writer.write(table_directory_offset)?;
+    }
+    if major_version == 2 {
+        writer.write(0u32)?;
+        writer.write(0u32)?;
+        writer.write(0u32)?;
+    }
+    for directory in &directories {
+        directory.write_to(writer)?;
+    }
+    for bytes in unique_tables {
+        writer.write(bytes)?;
+        writer.write(vec![0u8; bytes.len().next_multiple_of(4) - bytes.len()])?;
+    }
+    Ok(writer.position() - start)
+}
+
+/// 读取字体集合文件，启用 `mmap` 特性时通过内存映射读取
+pub fn read_collection(path: &str) -> Result<Ttc<Box<dyn TtcHeader>>, IOError> {
+    Ttc::new(open_file(path)?)
 }
 
 /// `TTC`头部有两个版本：`1.0`版用于没有数字签名的`TTC`文件。
@@ -153,8 +403,106 @@ impl TtcHeader for TtcV2 {
     }
 }
 
+impl TtcV2 {
+    /// `0x44534947`（`DSIG`），没有签名时为 0
+    pub fn dsig_tag(&self) -> u32 {
+        self.dsig_tag
+    }
+
+    pub fn dsig_length(&self) -> u32 {
+        self.dsig_length
+    }
+
+    pub fn dsig_offset(&self) -> u32 {
+        self.dsig_offset
+    }
+
+    pub fn has_dsig(&self) -> bool {
+        self.dsig_tag == u32::from_be_bytes(*b"DSIG") && self.dsig_length != 0
+    }
+}
+
 impl AsMut<TtcV2> for TtcV2 {
     fn as_mut(&mut self) -> &mut TtcV2 {
         self
     }
 }
+
+impl TtcHeader for Box<dyn TtcHeader> {
+    fn ttc_tag(&self) -> &Tag {
+        self.as_ref().ttc_tag()
+    }
+
+    fn major_version(&self) -> u16 {
+        self.as_ref().major_version()
+    }
+
+    fn minor_version(&self) -> u16 {
+        self.as_ref().minor_version()
+    }
+
+    fn num_fonts(&self) -> u32 {
+        self.as_ref().num_fonts()
+    }
+
+    fn table_directory_offsets(&self) -> &Vec<Offset32> {
+        self.as_ref().table_directory_offsets()
+    }
+
+    fn as_v1(&self) -> Option<&TtcV1> {
+        self.as_ref().as_v1()
+    }
+
+    fn as_v2(&self) -> Option<&TtcV2> {
+        self.as_ref().as_v2()
+    }
+}
+
+/// 按 `major_version` 读取 [TtcV1] 或 [TtcV2]
+impl ReadFrom<ReaderBoxed<'_>> for Box<dyn TtcHeader> {
+    fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
+        let ttc_tag = Tag::read_from(reader)?;
+        if ttc_tag.to_bytes() != *b"ttcf" {
+            return Err(IOError::unexpected("ttcTag", "'ttcf'", format!("'{}'", ttc_tag), 0));
+        }
+        let major_version = reader.read_u16()?;
+        let minor_version = reader.read_u16()?;
+        let num_fonts = reader.read_u32()?;
+        // 先检查偏移量数组的长度，避免按文件中的数量分配内存
+        let max_fonts = reader.remaining() / 4;
+        if num_fonts as usize > max_fonts {
+            return Err(IOError::unexpected(
+                "numFonts",
+                format!("<= {}", max_fonts),
+                num_fonts,
+                8,
+            ));
+        }
+        let mut table_directory_offsets = Vec::with_capacity(num_fonts as usize);
+        for _ in 0..num_fonts {
+            table_directory_offsets.push(reader.read_u32()?);
+        }
+        Ok(match major_version {
+            1 => Box::new(TtcV1 {
+                ttc_tag,
+                major_version,
+                minor_version,
+                num_fonts,
+                table_directory_offsets,
+            }),
+            2 => Box::new(TtcV2 {
+                ttc_tag,
+                major_version,
+                minor_version,
+                num_fonts,
+                table_directory_offsets,
+                dsig_tag: reader.read_u32()?,
+                dsig_length: reader.read_u32()?,
+                dsig_offset: reader.read_u32()?,
+            }),
+            version => {
+                return Err(IOError::unexpected("majorVersion", "1 or 2", version, 4));
+            }
+        })
+    }
+}
@@ -1,7 +1,170 @@
-#[derive(Debug)]
+use crate::types::Tag;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone)]
 pub enum IOError {
     NoEnoughData,
     UnexpectedEof,
     UnableCast,
     UnableOperate(String),
+    /// 值不符合预期，如错误的魔数、版本号、格式号
+    Unexpected { expected: String, found: String },
+    /// 带有位置信息的错误，见 [ErrorContext]
+    Located(Box<LocatedError>),
+}
+
+/// 带有位置信息的错误
+///
+/// 显示为 `cmap/encodingRecord[2]/subtable format 4/segCountX2: expected even, found 0x1F3 at 0x1A4`
+#[derive(Debug, Clone)]
+pub struct LocatedError {
+    error: IOError,
+    /// 所在的表
+    tag: Option<Tag>,
+    /// 从外到内的结构与字段路径
+    path: Vec<String>,
+    /// 字节偏移量，添加表上下文后为相对于文件开头的偏移量
+    offset: Option<usize>,
+}
+
+impl IOError {
+    /// 字段值不符合预期，`offset` 为字段相对于当前读取器开头的偏移量
+    pub fn unexpected(
+        field: &str,
+        expected: impl Display,
+        found: impl Display,
+        offset: usize,
+    ) -> IOError {
+        IOError::Located(Box::new(LocatedError {
+            error: IOError::Unexpected {
+                expected: expected.to_string(),
+                found: found.to_string(),
+            },
+            tag: None,
+            path: vec![field.to_string()],
+            offset: Some(offset),
+        }))
+    }
+
+    /// 去除位置信息后的错误
+    pub fn kind(&self) -> &IOError {
+        match self {
+            IOError::Located(located) => located.error.kind(),
+            e => e,
+        }
+    }
+
+    pub fn location(&self) -> Option<&LocatedError> {
+        match self {
+            IOError::Located(located) => Some(located),
+            _ => None,
+        }
+    }
+
+    fn into_located(self) -> Box<LocatedError> {
+        match self {
+            IOError::Located(located) => located,
+            error => Box::new(LocatedError {
+                error,
+                tag: None,
+                path: vec![],
+                offset: None,
+            }),
+        }
+    }
+}
+
+impl LocatedError {
+    pub fn error(&self) -> &IOError {
+        &self.error
+    }
+
+    pub fn tag(&self) -> Option<&Tag> {
+        self.tag.as_ref()
+    }
+
+    pub fn path(&self) -> &Vec<String> {
+        &self.path
+    }
+
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+}
+
+impl Display for IOError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IOError::NoEnoughData => f.write_str("not enough data"),
+            IOError::UnexpectedEof => f.write_str("unexpected end of data"),
+            IOError::UnableCast => f.write_str("unable to cast data"),
+            IOError::UnableOperate(msg) => write!(f, "unable to operate: {}", msg),
+            IOError::Unexpected { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            IOError::Located(located) => Display::fmt(located, f),
+        }
+    }
+}
+
+impl Display for LocatedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut path: Vec<String> = vec![];
+        if let Some(tag) = &self.tag {
+            path.push(tag.to_string().trim_end().to_string());
+        }
+        path.extend(self.path.iter().cloned());
+        if !path.is_empty() {
+            write!(f, "{}: ", path.join("/"))?;
+        }
+        write!(f, "{}", self.error)?;
+        if let Some(offset) = self.offset {
+            write!(f, " at 0x{:X}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for IOError {}
+
+/// 为错误添加位置信息
+pub trait ErrorContext<T> {
+    /// 错误发生在 `segment` 描述的结构中，该结构位于当前读取器的 `base` 处
+    fn context(self, segment: impl Display, base: usize) -> Result<T, IOError>;
+
+    /// 错误发生在 `tag` 表中，该表位于文件的 `table_offset` 处
+    fn in_table(self, tag: &Tag, table_offset: usize) -> Result<T, IOError>;
+
+    /// 错误还没有偏移量时，以当前读取器的 `position` 作为偏移量，读取出错时读取器停在出错的字段处。
+    ///
+    /// 用于读取器不随错误传出的地方，如子读取器和函数内部的读取器。
+    fn at(self, position: usize) -> Result<T, IOError>;
+}
+
+impl<T> ErrorContext<T> for Result<T, IOError> {
+    fn context(self, segment: impl Display, base: usize) -> Result<T, IOError> {
+        self.map_err(|e| {
+            let mut located = e.into_located();
+            located.path.insert(0, segment.to_string());
+            located.offset = located.offset.map(|offset| offset + base);
+            IOError::Located(located)
+        })
+    }
+
+    fn in_table(self, tag: &Tag, table_offset: usize) -> Result<T, IOError> {
+        self.map_err(|e| {
+            let mut located = e.into_located();
+            located.tag = Some(tag.clone());
+            located.offset = located.offset.map(|offset| offset + table_offset);
+            IOError::Located(located)
+        })
+    }
+
+    fn at(self, position: usize) -> Result<T, IOError> {
+        self.map_err(|e| {
+            let mut located = e.into_located();
+            located.offset.get_or_insert(position);
+            IOError::Located(located)
+        })
+    }
 }
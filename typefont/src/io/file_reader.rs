@@ -60,10 +60,9 @@ impl Reader for FileReader {
 
     fn read_bytes(&mut self, len: usize) -> crate::io::reader::Result<Vec<u8>> {
         let mut buf = Vec::with_capacity(len);
-        buf.resize(len, 0);
-        match self.file.read(buf.as_mut_slice()) {
-            Ok(_) => {
-                self.pos += len;
+        match (&mut self.file).take(len as u64).read_to_end(&mut buf) {
+            Ok(size) => {
+                self.pos += size;
                 Ok(buf)
             }
             Err(e) => Err(crate::io::error::IOError::UnableOperate(e.to_string())),
@@ -1,32 +1,64 @@
+use crate::io::error::IOError;
 use crate::io::writer::Writer;
 use std::fs::File;
-use std::io::Write;
+use std::io::{Seek, SeekFrom, Write};
 use std::path::Path;
 
 pub struct FileWriter {
     file: File,
     pos: usize,
+    len: usize,
 }
 
 impl FileWriter {
+    /// 创建文件，文件已存在时清空原有内容
     pub fn open<P: AsRef<Path>>(path: P) -> Result<FileWriter, std::io::Error> {
-        let file = File::open(path)?;
-        Ok(Self { file, pos: 0 })
+        let file = File::create(path)?;
+        Ok(Self {
+            file,
+            pos: 0,
+            len: 0,
+        })
+    }
+
+    /// 将缓冲的数据写入磁盘
+    pub fn flush(&mut self) -> Result<(), IOError> {
+        self.file
+            .flush()
+            .map_err(|e| IOError::UnableOperate(e.to_string()))
     }
 }
 
 impl Writer for FileWriter {
     fn written(&self) -> usize {
+        self.len
+    }
+
+    fn position(&self) -> usize {
         self.pos
     }
 
+    fn seek(&mut self, pos: usize) -> Result<(), IOError> {
+        if pos > self.len {
+            return Err(IOError::NoEnoughData);
+        }
+        match self.file.seek(SeekFrom::Start(pos as u64)) {
+            Ok(_) => {
+                self.pos = pos;
+                Ok(())
+            }
+            Err(e) => Err(IOError::UnableOperate(e.to_string())),
+        }
+    }
+
     fn write_bytes(&mut self, bytes: &[u8], off: usize, len: usize) -> crate::io::writer::Result {
-        match self.file.write(&bytes[off..off + len]) {
-            Ok(size) => {
-                self.pos += size;
+        match self.file.write_all(&bytes[off..off + len]) {
+            Ok(_) => {
+                self.pos += len;
+                self.len = self.len.max(self.pos);
                 Ok(len)
             }
-            Err(e) => Err(crate::io::error::IOError::UnableOperate(e.to_string())),
+            Err(e) => Err(IOError::UnableOperate(e.to_string())),
         }
     }
 }
@@ -0,0 +1,58 @@
+use crate::io::reader::Reader;
+use crate::io::slice_reader::SliceReader;
+use memmap2::Mmap;
+use std::fs::File;
+use std::path::Path;
+
+/// 通过内存映射读取文件，读取时不产生系统调用，也不需要将整个文件读入内存
+///
+/// 映射期间文件被其他进程修改或截断是未定义行为，只应用于不会被修改的字体文件。
+pub struct MmapReader {
+    inner: SliceReader<Mmap>,
+}
+
+impl MmapReader {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<MmapReader, std::io::Error> {
+        let file = File::open(path)?;
+        // SAFETY: 见类型文档，调用方需保证文件在映射期间不被修改
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(Self {
+            inner: SliceReader::new(mmap),
+        })
+    }
+
+    /// 映射的全部数据，可用于 [FontRef](crate::font::font_ref::FontRef) 等零拷贝读取
+    pub fn data(&self) -> &[u8] {
+        self.inner.data()
+    }
+}
+
+impl Reader for MmapReader {
+    fn position(&self) -> usize {
+        self.inner.position()
+    }
+
+    fn size(&self) -> usize {
+        self.inner.size()
+    }
+
+    fn mark(&mut self) -> crate::io::reader::Result<()> {
+        self.inner.mark()
+    }
+
+    fn get_mark(&self) -> usize {
+        self.inner.get_mark()
+    }
+
+    fn reset(&mut self) -> crate::io::reader::Result<()> {
+        self.inner.reset()
+    }
+
+    fn seek(&mut self, pos: usize) -> crate::io::reader::Result<()> {
+        self.inner.seek(pos)
+    }
+
+    fn read_bytes(&mut self, len: usize) -> crate::io::reader::Result<Vec<u8>> {
+        self.inner.read_bytes(len)
+    }
+}
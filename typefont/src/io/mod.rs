@@ -1,8 +1,13 @@
 pub mod error;
 pub mod file_reader;
+#[cfg(feature = "mmap")]
+pub mod mmap_reader;
 pub mod reader;
+pub mod slice_reader;
 
 #[cfg(feature = "writer")]
 pub mod file_writer;
 #[cfg(feature = "writer")]
+pub mod vec_writer;
+#[cfg(feature = "writer")]
 pub mod writer;
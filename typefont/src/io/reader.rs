@@ -55,6 +55,32 @@ pub trait Reader {
 
     fn seek(&mut self, pos: usize) -> Result<()>;
 
+    /// 创建从 `offset` 开始、长度为 `len` 的子读取器，
+    /// 子读取器的位置 `0` 对应此读取器的 `offset`，如表或子表的开头。
+    ///
+    /// 子读取器不能读取范围以外的数据，读取超出 `len` 的数据返回 [IOError::NoEnoughData]。
+    /// 子读取器借用此读取器，读取时会改变此读取器的位置。
+    fn sub_reader(&mut self, offset: usize, len: usize) -> Result<ReaderBoxed<'_>> {
+        match offset.checked_add(len) {
+            Some(end) if end <= self.size() => Ok(Box::new(SubReader {
+                parent: self,
+                start: offset,
+                len,
+                pos: 0,
+                mark: 0,
+            })),
+            _ => Err(IOError::NoEnoughData),
+        }
+    }
+
+    /// 创建从 `offset` 开始直到数据末尾的子读取器，用于长度未知的子表
+    fn sub_reader_from(&mut self, offset: usize) -> Result<ReaderBoxed<'_>> {
+        match self.size().checked_sub(offset) {
+            Some(len) => self.sub_reader(offset, len),
+            None => Err(IOError::NoEnoughData),
+        }
+    }
+
     fn eof(&self) -> bool {
         self.remaining() == 0
     }
@@ -105,4 +131,98 @@ pub trait Reader {
     }
 }
 
-pub type ReaderBoxed = Box<dyn Reader>;
+pub type ReaderBoxed<'a> = Box<dyn Reader + 'a>;
+
+impl<R: Reader + ?Sized> Reader for Box<R> {
+    fn position(&self) -> usize {
+        (**self).position()
+    }
+
+    fn size(&self) -> usize {
+        (**self).size()
+    }
+
+    fn mark(&mut self) -> Result<()> {
+        (**self).mark()
+    }
+
+    fn get_mark(&self) -> usize {
+        (**self).get_mark()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        (**self).reset()
+    }
+
+    fn seek(&mut self, pos: usize) -> Result<()> {
+        (**self).seek(pos)
+    }
+
+    fn sub_reader(&mut self, offset: usize, len: usize) -> Result<ReaderBoxed<'_>> {
+        (**self).sub_reader(offset, len)
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<Vec<u8>> {
+        (**self).read_bytes(len)
+    }
+}
+
+/// 见 [Reader::sub_reader]
+struct SubReader<'r, R: Reader + ?Sized> {
+    parent: &'r mut R,
+    /// 在父读取器中的起始位置
+    start: usize,
+    len: usize,
+    pos: usize,
+    mark: usize,
+}
+
+impl<R: Reader + ?Sized> Reader for SubReader<'_, R> {
+    fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn size(&self) -> usize {
+        self.len
+    }
+
+    fn mark(&mut self) -> Result<()> {
+        self.mark = self.pos;
+        Ok(())
+    }
+
+    fn get_mark(&self) -> usize {
+        self.mark
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.seek(self.mark)
+    }
+
+    fn seek(&mut self, pos: usize) -> Result<()> {
+        self.pos = pos.min(self.len);
+        Ok(())
+    }
+
+    fn sub_reader(&mut self, offset: usize, len: usize) -> Result<ReaderBoxed<'_>> {
+        // 直接基于父读取器创建，避免嵌套
+        match offset.checked_add(len) {
+            Some(end) if end <= self.len => Ok(Box::new(SubReader {
+                parent: &mut *self.parent,
+                start: self.start + offset,
+                len,
+                pos: 0,
+                mark: 0,
+            })),
+            _ => Err(IOError::NoEnoughData),
+        }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<Vec<u8>> {
+        let len = len.min(self.remaining());
+        self.parent.seek(self.start + self.pos)?;
+        let res = self.parent.read_bytes(len)?;
+        self.pos += res.len();
+        Ok(res)
+    }
+}
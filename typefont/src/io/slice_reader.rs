@@ -0,0 +1,82 @@
+use crate::io::error::IOError;
+use crate::io::reader::Reader;
+use std::cmp::min;
+
+/// 从内存数据读取，`B` 可以是 `&[u8]`、`Vec<u8>`、`Arc<[u8]>` 等
+pub struct SliceReader<B: AsRef<[u8]>> {
+    data: B,
+    pos: usize,
+    mark: usize,
+}
+
+impl<B: AsRef<[u8]>> SliceReader<B> {
+    pub fn new(data: B) -> Self {
+        Self {
+            data,
+            pos: 0,
+            mark: 0,
+        }
+    }
+
+    /// 全部数据
+    pub fn data(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+
+    pub fn into_inner(self) -> B {
+        self.data
+    }
+}
+
+impl<'a> SliceReader<&'a [u8]> {
+    /// 读取指定长度的数据，返回的数据借用自原始数据，不复制
+    pub fn read_slice(&mut self, len: usize) -> crate::io::reader::Result<&'a [u8]> {
+        if !self.can_read(len) {
+            return Err(IOError::NoEnoughData);
+        }
+        let slice = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(slice)
+    }
+}
+
+impl<B: AsRef<[u8]>> From<B> for SliceReader<B> {
+    fn from(data: B) -> Self {
+        Self::new(data)
+    }
+}
+
+impl<B: AsRef<[u8]>> Reader for SliceReader<B> {
+    fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn size(&self) -> usize {
+        self.data.as_ref().len()
+    }
+
+    fn mark(&mut self) -> crate::io::reader::Result<()> {
+        self.mark = self.pos;
+        Ok(())
+    }
+
+    fn get_mark(&self) -> usize {
+        self.mark
+    }
+
+    fn reset(&mut self) -> crate::io::reader::Result<()> {
+        self.seek(self.mark)
+    }
+
+    fn seek(&mut self, pos: usize) -> crate::io::reader::Result<()> {
+        self.pos = min(self.size(), pos);
+        Ok(())
+    }
+
+    fn read_bytes(&mut self, len: usize) -> crate::io::reader::Result<Vec<u8>> {
+        let end = min(self.size(), self.pos + len);
+        let buf = self.data.as_ref()[self.pos..end].to_vec();
+        self.pos = end;
+        Ok(buf)
+    }
+}
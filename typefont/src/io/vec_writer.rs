@@ -0,0 +1,58 @@
+use crate::io::error::IOError;
+use crate::io::writer::Writer;
+
+/// 写入内存的写入器
+#[derive(Default)]
+pub struct VecWriter {
+    data: Vec<u8>,
+    pos: usize,
+}
+
+impl VecWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            pos: 0,
+        }
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl Writer for VecWriter {
+    fn written(&self) -> usize {
+        self.data.len()
+    }
+
+    fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn seek(&mut self, pos: usize) -> Result<(), IOError> {
+        if pos > self.data.len() {
+            return Err(IOError::NoEnoughData);
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8], off: usize, len: usize) -> crate::io::writer::Result {
+        let bytes = &bytes[off..off + len];
+        let end = self.pos + len;
+        let overlap = end.min(self.data.len()).saturating_sub(self.pos);
+        self.data[self.pos..self.pos + overlap].copy_from_slice(&bytes[..overlap]);
+        self.data.extend_from_slice(&bytes[overlap..]);
+        self.pos = end;
+        Ok(len)
+    }
+}
@@ -7,13 +7,19 @@ macro_rules! write_fn {
         $name:ident,$($t:ident),*
     )=>{
         $(
-        impl Write<$t> for dyn $name  {
+        impl Write<$t> for dyn $name + '_ {
             fn write(&mut self, v: $t) -> Result {
                 self.write(v.to_be_bytes().as_slice())
             }
         }
 
-        impl ShlAssign<$t> for dyn Writer{
+        impl Patch<$t> for dyn $name + '_ {
+            fn patch_at(&mut self, pos: usize, v: $t) -> Result {
+                self.patch(pos, v.to_be_bytes().as_slice())
+            }
+        }
+
+        impl ShlAssign<$t> for dyn Writer + '_ {
             fn shl_assign(&mut self, rhs: $t) {
                 self.write(rhs.to_be_bytes().as_slice()).unwrap();
             }
@@ -29,41 +35,80 @@ pub trait Write<T: ?Sized> {
     fn write(&mut self, v: T) -> Result;
 }
 
+/// 在指定位置回填值，见 [Writer::patch]
+pub trait Patch<T: ?Sized> {
+    fn patch_at(&mut self, pos: usize, v: T) -> Result;
+}
+
 pub trait Writer {
     /// 已写入的数据长度
     fn written(&self) -> usize;
 
-    /// 读取指定长度的数据
+    /// 当前写入位置，相对于数据开头，回填后可能小于 [Writer::written]
+    fn position(&self) -> usize;
+
+    /// 移动写入位置，`pos` 不能超过 [Writer::written]
+    fn seek(&mut self, pos: usize) -> std::result::Result<(), IOError>;
+
+    /// 写入数据，覆盖当前位置已有的数据
     ///
-    /// `len` 最大读取长度
+    /// `len` 最大写入长度
     fn write_bytes(&mut self, bytes: &[u8], off: usize, len: usize) -> Result;
+
+    /// 预留 `len` 字节并以 0 填充，返回预留位置，用于之后回填偏移量、长度或校验和
+    fn reserve(&mut self, len: usize) -> Result {
+        let pos = self.position();
+        self.write_bytes(&vec![0; len], 0, len)?;
+        Ok(pos)
+    }
+
+    /// 在 `pos` 处回填数据，写入位置保持不变
+    fn patch(&mut self, pos: usize, bytes: &[u8]) -> Result {
+        if pos + bytes.len() > self.written() {
+            return Err(IOError::NoEnoughData);
+        }
+        let current = self.position();
+        self.seek(pos)?;
+        let res = self.write_bytes(bytes, 0, bytes.len());
+        self.seek(current)?;
+        res
+    }
+
+    /// 以 0 填充到 `align` 字节对齐，返回填充长度，`align` 为 0 时不填充
+    fn pad_to(&mut self, align: usize) -> Result {
+        let len = self
+            .position()
+            .checked_rem(align)
+            .map_or(0, |rem| (align - rem) % align);
+        self.write_bytes(&vec![0; len], 0, len)
+    }
 }
 
-impl Write<Vec<u8>> for dyn Writer {
+impl Write<Vec<u8>> for dyn Writer + '_ {
     fn write(&mut self, v: Vec<u8>) -> Result {
         self.write_bytes(&v, 0, v.len())
     }
 }
 
-impl Write<&Vec<u8>> for dyn Writer {
+impl Write<&Vec<u8>> for dyn Writer + '_ {
     fn write(&mut self, v: &Vec<u8>) -> Result {
-        self.write_bytes(&v, 0, v.len())
+        self.write_bytes(v, 0, v.len())
     }
 }
 
-impl Write<&[u8]> for dyn Writer {
+impl Write<&[u8]> for dyn Writer + '_ {
     fn write(&mut self, v: &[u8]) -> Result {
         self.write_bytes(v, 0, v.len())
     }
 }
 
-impl Write<String> for dyn Writer {
+impl Write<String> for dyn Writer + '_ {
     fn write(&mut self, v: String) -> Result {
         self.write(v.as_bytes())
     }
 }
 
-impl Write<bool> for dyn Writer {
+impl Write<bool> for dyn Writer + '_ {
     fn write(&mut self, v: bool) -> Result {
         let bs = [if v { 1 } else { 0 }];
         self.write_bytes(&bs, 0, 1)
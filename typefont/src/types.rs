@@ -76,6 +76,7 @@ macro_rules! num_op {
 ///
 /// author: YJL
 ///
+#[derive(Clone, Copy)]
 pub struct F2D14 {
     value: i16,
 }
@@ -94,6 +95,16 @@ impl F2D14 {
         Self { value: 0 }
     }
 
+    /// 从字体数据中的原始 16 位值构造
+    pub fn from_bits(value: i16) -> Self {
+        Self { value }
+    }
+
+    /// 字体数据中的原始 16 位值
+    pub fn to_bits(&self) -> i16 {
+        self.value
+    }
+
     fn trunc(v: i32) -> i16 {
         if v > i16::MAX as i32 {
             i16::MAX
@@ -179,28 +190,25 @@ impl From<f32> for F2D14 {
 
 num_op!(F2D14);
 
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Tag(String);
 
 impl Tag {
-    fn to_bytes(&self) -> [u8; 4] {
+    pub fn to_bytes(&self) -> [u8; 4] {
         let bs = self.0.as_bytes();
         [bs[0], bs[1], bs[2], bs[3]]
     }
-
-    fn to_string(&self) -> String {
-        self.0.clone()
-    }
 }
 
 impl Debug for Tag {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.to_string())
+        f.write_str(&self.0)
     }
 }
 
 impl Display for Tag {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.to_string())
+        f.write_str(&self.0)
     }
 }
 
@@ -243,7 +251,7 @@ impl TryFrom<&[u8]> for Tag {
     }
 }
 
-impl ReadFrom<ReaderBoxed> for Tag {
+impl ReadFrom<ReaderBoxed<'_>> for Tag {
     fn read_from(reader: &mut ReaderBoxed) -> Result<Self, IOError> {
         let bs = reader.read_bytes(4)?;
         match Self::try_from(bs.as_slice()) {
@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+use typefont::font::GlyphId;
+use typefont::font::encoding::LegacyEncoding;
+use typefont::font::open_type::{OpenType, read_font};
+use typefont::font::table::cmap::{Cmap, CmapSubTable, VariationResult};
+
+/// 只包含 `cmap` 表的字体
+fn font_with_cmap(cmap: &[u8]) -> OpenType {
+    let mut out = vec![];
+    out.extend_from_slice(&[0, 1, 0, 0, 0, 1, 0, 16, 0, 0, 0, 0]);
+    out.extend_from_slice(b"cmap");
+    out.extend_from_slice(&0u32.to_be_bytes());
+    out.extend_from_slice(&28u32.to_be_bytes());
+    out.extend_from_slice(&(cmap.len() as u32).to_be_bytes());
+    out.extend_from_slice(cmap);
+    OpenType::from_bytes(out).unwrap()
+}
+
+fn push_u16(out: &mut Vec<u8>, values: &[u16]) {
+    for value in values {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+fn push_u24(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_be_bytes()[1..]);
+}
+
+/// (0, 5) 格式 14 子表和 (3, 1) 格式 4 子表，`U+845B` 映射到字形 10
+///
+/// - `U+E0100`：默认 UVS `U+845B..=U+845C`
+/// - `U+E0101`：非默认 UVS `U+845B` ⇒ 字形 20
+fn uvs_cmap() -> Vec<u8> {
+    let mut format14 = vec![];
+    push_u16(&mut format14, &[14]);
+    format14.extend_from_slice(&49u32.to_be_bytes());
+    format14.extend_from_slice(&2u32.to_be_bytes());
+    push_u24(&mut format14, 0xE0100);
+    format14.extend_from_slice(&32u32.to_be_bytes());
+    format14.extend_from_slice(&0u32.to_be_bytes());
+    push_u24(&mut format14, 0xE0101);
+    format14.extend_from_slice(&0u32.to_be_bytes());
+    format14.extend_from_slice(&40u32.to_be_bytes());
+    format14.extend_from_slice(&1u32.to_be_bytes());
+    push_u24(&mut format14, 0x845B);
+    format14.push(1);
+    format14.extend_from_slice(&1u32.to_be_bytes());
+    push_u24(&mut format14, 0x845B);
+    push_u16(&mut format14, &[20]);
+
+    let mut format4 = vec![];
+    let delta = 10u16.wrapping_sub(0x845B);
+    push_u16(&mut format4, &[4, 32, 0, 4, 4, 1, 0]);
+    push_u16(&mut format4, &[0x845B, 0xFFFF, 0]);
+    push_u16(&mut format4, &[0x845B, 0xFFFF, delta, 1, 0, 0]);
+
+    let mut cmap = vec![];
+    push_u16(&mut cmap, &[0, 2, 0, 5]);
+    cmap.extend_from_slice(&20u32.to_be_bytes());
+    push_u16(&mut cmap, &[3, 1]);
+    cmap.extend_from_slice(&(20 + format14.len() as u32).to_be_bytes());
+    cmap.extend_from_slice(&format14);
+    cmap.extend_from_slice(&format4);
+    cmap
+}
+
+#[test]
+fn variation_sequences() {
+    let font = font_with_cmap(&uvs_cmap());
+    assert_eq!(font.glyph_index('\u{845B}'), Some(10));
+    assert_eq!(
+        font.glyph_index_for_variation('\u{845B}', '\u{E0100}'),
+        VariationResult::Default
+    );
+    assert_eq!(
+        font.glyph_index_for_variation('\u{845B}', '\u{E0101}'),
+        VariationResult::Glyph(20)
+    );
+    // 默认 UVS 中的 `U+845C` 在 Unicode 子表中没有映射
+    assert_eq!(
+        font.glyph_index_for_variation('\u{845C}', '\u{E0100}'),
+        VariationResult::NotFound
+    );
+    assert_eq!(
+        font.glyph_index_for_variation('\u{845B}', '\u{FE00}'),
+        VariationResult::NotFound
+    );
+
+    let cmap = font.cmap().unwrap().unwrap();
+    let sequences: Vec<_> = cmap.variation_sequences().collect();
+    assert_eq!(
+        sequences,
+        [
+            (0x845B, 0xE0100, VariationResult::Default),
+            (0x845C, 0xE0100, VariationResult::Default),
+            (0x845B, 0xE0101, VariationResult::Glyph(20)),
+        ]
+    );
+}
+
+#[test]
+fn compile_cmap() {
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let cmap = font.cmap().unwrap().unwrap();
+    let mappings: BTreeMap<u32, GlyphId> = cmap.mappings().collect();
+    let compiled = Cmap::compile(&mappings, &[], true).unwrap();
+    let formats: Vec<_> = compiled
+        .encoding_records()
+        .iter()
+        .map(|r| (r.platform_id(), r.encoding_id(), r.subtable().format()))
+        .collect();
+    assert_eq!(
+        formats,
+        [(0, 3, 4), (0, 4, 12), (1, 0, 6), (3, 1, 4), (3, 10, 12)]
+    );
+    assert!(compiled.mappings().eq(cmap.mappings()));
+    let bmp: Vec<_> = compiled.encoding_records()[0]
+        .subtable()
+        .mappings()
+        .collect();
+    let original: Vec<_> = cmap.encoding_records()[0].subtable().mappings().collect();
+    assert_eq!(bmp, original);
+    let mac: Vec<_> = compiled.encoding_records()[2]
+        .subtable()
+        .mappings()
+        .collect();
+    // 原字体的 Mac 子表还映射了几个 `Unicode` 子表中没有的控制字符
+    let original: Vec<_> = cmap.encoding_records()[2]
+        .subtable()
+        .mappings()
+        .filter(|&(code, _)| code >= 0x20)
+        .collect();
+    assert_eq!(mac, original);
+    // 按长度最小选择段的表示方式，子表不大于原字体
+    match (
+        compiled.encoding_records()[0].subtable(),
+        cmap.encoding_records()[0].subtable(),
+    ) {
+        (CmapSubTable::Format4(compiled), CmapSubTable::Format4(original)) => {
+            assert!(compiled.length() <= original.length());
+        }
+        _ => unreachable!(),
+    }
+
+    // 超出 `Unicode` 范围的码位和变体选择器
+    assert!(Cmap::compile(&BTreeMap::from([(0x110000, 1)]), &[], false).is_err());
+    let variations = [(0x845B, 0x1000000, VariationResult::Default)];
+    assert!(Cmap::compile(&BTreeMap::new(), &variations, false).is_err());
+}
+
+#[cfg(feature = "writer")]
+#[test]
+fn write_compiled_cmap() {
+    use typefont::font::io::WriteTo;
+    use typefont::io::vec_writer::VecWriter;
+
+    let mappings = BTreeMap::from([(0x41, 1), (0x42, 2), (0x845B, 10), (0x20000, 30)]);
+    let variations = [
+        (0x845B, 0xE0100, VariationResult::Default),
+        (0x845B, 0xE0101, VariationResult::Glyph(20)),
+    ];
+    let compiled = Cmap::compile(&mappings, &variations, false).unwrap();
+    let mut writer = VecWriter::new();
+    compiled.write_to(&mut writer).unwrap();
+
+    let font = font_with_cmap(&writer.into_inner());
+    let cmap = font.cmap().unwrap().unwrap();
+    let formats: Vec<_> = cmap
+        .encoding_records()
+        .iter()
+        .map(|r| (r.platform_id(), r.encoding_id(), r.subtable().format()))
+        .collect();
+    assert_eq!(
+        formats,
+        [(0, 3, 4), (0, 4, 12), (0, 5, 14), (3, 1, 4), (3, 10, 12)]
+    );
+    assert!(cmap.mappings().eq(mappings.into_iter()));
+    assert!(cmap.variation_sequences().eq(variations.into_iter()));
+
+    // 字形索引不连续的大量 BMP 映射放不下格式 4 子表，其余映射由格式 12 子表提供
+    let mappings: BTreeMap<u32, GlyphId> = (0..35000)
+        .map(|i| (0x4E00 + i, 40000 - i as GlyphId))
+        .collect();
+    let compiled = Cmap::compile(&mappings, &[], false).unwrap();
+    let mut writer = VecWriter::new();
+    compiled.write_to(&mut writer).unwrap();
+
+    let font = font_with_cmap(&writer.into_inner());
+    let cmap = font.cmap().unwrap().unwrap();
+    let records = cmap.encoding_records();
+    let formats: Vec<_> = records
+        .iter()
+        .map(|r| (r.platform_id(), r.encoding_id(), r.subtable().format()))
+        .collect();
+    assert_eq!(formats, [(0, 3, 4), (0, 4, 12), (3, 1, 4), (3, 10, 12)]);
+    let bmp: Vec<_> = records[0].subtable().mappings().collect();
+    assert!(bmp.len() > 30000 && bmp.len() < mappings.len());
+    let prefix: Vec<_> = mappings.clone().into_iter().take(bmp.len()).collect();
+    assert_eq!(bmp, prefix);
+    assert!(records[1].subtable().mappings().eq(mappings.into_iter()));
+}
+
+/// 只有一个 (3, 10) 编码记录的 `cmap` 表
+fn single_subtable_cmap(subtable: &[u8]) -> Vec<u8> {
+    let mut cmap = vec![];
+    push_u16(&mut cmap, &[0, 1, 3, 10]);
+    cmap.extend_from_slice(&12u32.to_be_bytes());
+    cmap.extend_from_slice(subtable);
+    cmap
+}
+
+/// 格式 8、12 和 13 的子表，`is32` 全为 0
+fn grouped_subtable(format: u16, groups: &[(u32, u32, u32)]) -> Vec<u8> {
+    let header_len = if format == 8 { 8208 } else { 16 };
+    let mut subtable = vec![];
+    push_u16(&mut subtable, &[format, 0]);
+    subtable.extend_from_slice(&((header_len + 12 * groups.len()) as u32).to_be_bytes());
+    subtable.extend_from_slice(&0u32.to_be_bytes());
+    if format == 8 {
+        subtable.extend_from_slice(&[0; 8192]);
+    }
+    subtable.extend_from_slice(&(groups.len() as u32).to_be_bytes());
+    for &(start, end, glyph_id) in groups {
+        for value in [start, end, glyph_id] {
+            subtable.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+    subtable
+}
+
+fn read_subtable(subtable: &[u8]) -> Result<Vec<(u32, GlyphId)>, String> {
+    let font = font_with_cmap(&single_subtable_cmap(subtable));
+    let cmap = font.cmap().map_err(|e| e.to_string())?.unwrap();
+    Ok(cmap.encoding_records()[0].subtable().mappings().collect())
+}
+
+#[test]
+fn byte_encoding_subtable() {
+    let mut format0 = vec![];
+    push_u16(&mut format0, &[0, 262, 0]);
+    format0.extend((0..=255u8).map(|code| if code == b'A' { 5 } else { 0 }));
+    assert_eq!(read_subtable(&format0).unwrap(), [(0x41, 5)]);
+
+    // `glyphIdArray` 不足 256 字节
+    format0.truncate(100);
+    assert!(read_subtable(&format0).is_err());
+}
+
+#[test]
+fn sequential_subtables() {
+    let format8 = grouped_subtable(8, &[(0x20, 0x21, 3), (0x30, 0x30, 7)]);
+    assert_eq!(
+        read_subtable(&format8).unwrap(),
+        [(0x20, 3), (0x21, 4), (0x30, 7)]
+    );
+    // 分组重叠
+    let format8 = grouped_subtable(8, &[(0x20, 0x30, 3), (0x30, 0x31, 7)]);
+    let err = read_subtable(&format8).unwrap_err();
+    assert!(err.contains("groups[1]/startCharCode: expected > 0x30, found 0x30"));
+
+    // 字形索引超出 `u32` 的范围时没有映射
+    let format12 = grouped_subtable(12, &[(0x20, 0x22, u32::MAX)]);
+    let font = font_with_cmap(&single_subtable_cmap(&format12));
+    let cmap = font.cmap().unwrap().unwrap();
+    assert_eq!(cmap.encoding_records()[0].subtable().glyph_id(0x21), None);
+    assert_eq!(cmap.mappings().count(), 0);
+
+    let format13 = grouped_subtable(13, &[(0x4E00, 0x4E02, 1), (0x5000, 0x5000, 2)]);
+    assert_eq!(
+        read_subtable(&format13).unwrap(),
+        [(0x4E00, 1), (0x4E01, 1), (0x4E02, 1), (0x5000, 2)]
+    );
+    // 分组未按 `startCharCode` 升序排列
+    let format13 = grouped_subtable(13, &[(0x5000, 0x5000, 2), (0x4E00, 0x4E02, 1)]);
+    let err = read_subtable(&format13).unwrap_err();
+    assert!(err.contains("groups[1]/startCharCode: expected > 0x5000, found 0x4E00"));
+}
+
+#[test]
+fn trimmed_array_subtable() {
+    let format10 = |start: u32, glyph_ids: &[u16]| {
+        let mut subtable = vec![];
+        push_u16(&mut subtable, &[10, 0]);
+        subtable.extend_from_slice(&(20 + 2 * glyph_ids.len() as u32).to_be_bytes());
+        subtable.extend_from_slice(&0u32.to_be_bytes());
+        subtable.extend_from_slice(&start.to_be_bytes());
+        subtable.extend_from_slice(&(glyph_ids.len() as u32).to_be_bytes());
+        push_u16(&mut subtable, glyph_ids);
+        subtable
+    };
+    assert_eq!(
+        read_subtable(&format10(0x10000, &[5, 0, 6])).unwrap(),
+        [(0x10000, 5), (0x10002, 6)]
+    );
+    // 字符代码超出 `u32` 的范围
+    let err = read_subtable(&format10(u32::MAX, &[5, 6])).unwrap_err();
+    assert!(err.contains("numChars: expected <= 0x1, found 0x2"));
+}
+
+/// (3, 2) Shift-JIS 格式 2 子表：`A` ⇒ 5，`0x82A0`（あ）⇒ 7，`0x82A2`（い）⇒ 8
+fn shift_jis_cmap() -> Vec<u8> {
+    let mut format2 = vec![];
+    push_u16(&mut format2, &[2, 1052, 0]);
+    let mut keys = [0u16; 256];
+    keys[0x82] = 8;
+    push_u16(&mut format2, &keys);
+    // `id_range_offset` 相对于字段自身，指向 `glyph_id_array[0]` 和 `glyph_id_array[256]`
+    push_u16(&mut format2, &[0, 256, 0, 10]);
+    push_u16(&mut format2, &[0xA0, 3, 0, 514]);
+    let mut glyph_ids = [0u16; 259];
+    glyph_ids[0x41] = 5;
+    glyph_ids[256..].copy_from_slice(&[7, 0, 8]);
+    push_u16(&mut format2, &glyph_ids);
+
+    let mut cmap = vec![];
+    push_u16(&mut cmap, &[0, 1, 3, 2]);
+    cmap.extend_from_slice(&12u32.to_be_bytes());
+    cmap.extend_from_slice(&format2);
+    cmap
+}
+
+#[test]
+fn legacy_encoding() {
+    let font = font_with_cmap(&shift_jis_cmap());
+    let cmap = font.cmap().unwrap().unwrap();
+    assert_eq!(font.glyph_index('A'), None);
+    assert_eq!(
+        cmap.legacy_subtable()
+            .map(|(encoding, subtable)| (encoding, subtable.format())),
+        Some((LegacyEncoding::ShiftJis, 2))
+    );
+    // 末尾不完整的高字节作为单字节代码
+    assert_eq!(
+        cmap.legacy_glyph_indices(LegacyEncoding::ShiftJis, b"A\x82\xA0\x82\xA1\x82\xA2\x82"),
+        [Some(5), Some(7), None, Some(8), None]
+    );
+    assert_eq!(
+        cmap.legacy_glyph_indices(LegacyEncoding::Big5, b"A"),
+        [None]
+    );
+}
+
+#[cfg(feature = "cjk")]
+#[test]
+fn legacy_to_unicode() {
+    let font = font_with_cmap(&shift_jis_cmap());
+    let cmap = font.cmap().unwrap().unwrap().unicode_from_legacy().unwrap();
+    let formats: Vec<_> = cmap
+        .encoding_records()
+        .iter()
+        .map(|r| (r.platform_id(), r.encoding_id(), r.subtable().format()))
+        .collect();
+    assert_eq!(formats, [(3, 1, 4), (3, 2, 2)]);
+    assert_eq!(cmap.glyph_index('A'), Some(5));
+    assert_eq!(cmap.glyph_index('あ'), Some(7));
+    assert_eq!(cmap.glyph_index('い'), Some(8));
+    assert_eq!(cmap.mappings().count(), 3);
+    assert!(cmap.unicode_from_legacy().is_none());
+
+    // `Johab` 韩文音节按字母组合计算，符号经 `KS X 1001` 转换
+    assert_eq!(LegacyEncoding::Johab.to_char(0x8861), Some('가'));
+    assert_eq!(LegacyEncoding::Johab.to_char(0x8841), Some('ㄱ'));
+    assert_eq!(LegacyEncoding::Johab.to_char(0xD931), Some('\u{3000}'));
+    assert_eq!(LegacyEncoding::Wansung.to_char(0xB0A1), Some('가'));
+    assert_eq!(LegacyEncoding::Prc.to_char(0xB0A1), Some('啊'));
+    assert_eq!(LegacyEncoding::Big5.to_char(0xA440), Some('一'));
+}
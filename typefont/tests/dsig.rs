@@ -0,0 +1,114 @@
+#![cfg(feature = "dsig")]
+
+use sha2::{Digest, Sha256};
+use typefont::font::open_type::OpenType;
+use typefont::font::table::dsig::SignatureCoverage;
+use typefont::font::ttc::Ttc;
+use typefont::io::vec_writer::VecWriter;
+
+/// 只包含 `SHA-256` 摘要的 `DSIG` 表
+fn dsig_table(digest: &[u8]) -> Vec<u8> {
+    let mut pkcs7 = vec![0x30, 0x31, 0x30, 0x0D, 0x06, 0x09];
+    pkcs7.extend_from_slice(&[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01]);
+    pkcs7.extend_from_slice(&[0x05, 0x00, 0x04, 0x20]);
+    pkcs7.extend_from_slice(digest);
+    let mut dsig = vec![0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 1];
+    dsig.extend_from_slice(&(8 + pkcs7.len() as u32).to_be_bytes());
+    dsig.extend_from_slice(&20u32.to_be_bytes());
+    dsig.extend_from_slice(&[0, 0, 0, 0]);
+    dsig.extend_from_slice(&(pkcs7.len() as u32).to_be_bytes());
+    dsig.extend_from_slice(&pkcs7);
+    dsig
+}
+
+/// 在字体末尾添加 [dsig_table]，其余表记录的顺序不变
+fn sign(bytes: &[u8], digest: &[u8]) -> Vec<u8> {
+    let dsig = dsig_table(digest);
+    let num_tables = u16::from_be_bytes([bytes[4], bytes[5]]) as usize;
+    let directory_len = 12 + 16 * num_tables;
+    let mut out = bytes[..12].to_vec();
+    out[4..6].copy_from_slice(&(num_tables as u16 + 1).to_be_bytes());
+    let mut records: Vec<[u8; 16]> = bytes[12..directory_len]
+        .chunks(16)
+        .map(|r| {
+            let mut r: [u8; 16] = r.try_into().unwrap();
+            let offset = u32::from_be_bytes(r[8..12].try_into().unwrap()) + 16;
+            r[8..12].copy_from_slice(&offset.to_be_bytes());
+            r
+        })
+        .collect();
+    let mut record = [0u8; 16];
+    record[..4].copy_from_slice(b"DSIG");
+    record[8..12].copy_from_slice(&(bytes.len() as u32 + 16).to_be_bytes());
+    record[12..].copy_from_slice(&(dsig.len() as u32).to_be_bytes());
+    let index = records.partition_point(|r| r[..4] < record[..4]);
+    records.insert(index, record);
+    for record in records {
+        out.extend_from_slice(&record);
+    }
+    out.extend_from_slice(&bytes[directory_len..]);
+    out.extend_from_slice(&dsig);
+    out
+}
+
+#[test]
+fn dsig_coverage() {
+    let bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let signed = sign(&bytes, &Sha256::digest(&bytes));
+    let font = OpenType::from_bytes(signed.clone()).unwrap();
+    let dsig = font.dsig().unwrap().unwrap();
+    assert_eq!(dsig.pkcs7_blobs().len(), 1);
+    assert_eq!(font.dsig_coverage().unwrap(), SignatureCoverage::Covered);
+
+    let mut writer = VecWriter::new();
+    font.strip_dsig(&mut writer).unwrap();
+    assert_eq!(writer.data(), bytes.as_slice());
+
+    // 修改字形数据后签名失效
+    let mut modified = signed;
+    let len = modified.len();
+    modified[len - dsig.signature_records()[0].length() as usize - 40] ^= 0xFF;
+    let font = OpenType::from_bytes(modified).unwrap();
+    assert_eq!(font.dsig_coverage().unwrap(), SignatureCoverage::Stale);
+
+    let font = OpenType::from_bytes(bytes).unwrap();
+    assert_eq!(font.dsig_coverage().unwrap(), SignatureCoverage::Unsigned);
+}
+
+#[test]
+fn dsig_coverage_original_bytes() {
+    // 交换前两个表记录，表目录不再有序，重新写入时会改变
+    let mut bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let (first, second) = bytes[12..44].split_at_mut(16);
+    first.swap_with_slice(second);
+    let signed = sign(&bytes, &Sha256::digest(&bytes));
+    let font = OpenType::from_bytes(signed).unwrap();
+    assert_eq!(font.dsig().unwrap().unwrap().num_signatures(), 1);
+    assert_eq!(font.dsig_coverage().unwrap(), SignatureCoverage::Covered);
+
+    let mut writer = VecWriter::new();
+    font.strip_dsig(&mut writer).unwrap();
+    assert_ne!(writer.data(), bytes.as_slice());
+
+    // 版本 2 的集合，摘要不包含头部的 `DSIG` 字段
+    let directory_len = 12 + 16 * u16::from_be_bytes([bytes[4], bytes[5]]) as usize;
+    let mut collection = b"ttcf".to_vec();
+    collection.extend_from_slice(&[0, 2, 0, 0, 0, 0, 0, 1, 0, 0, 0, 28]);
+    collection.extend_from_slice(&[0; 12]);
+    collection.extend_from_slice(&bytes[..12]);
+    for record in bytes[12..directory_len].chunks(16) {
+        let offset = u32::from_be_bytes(record[8..12].try_into().unwrap()) + 28;
+        collection.extend_from_slice(&record[..8]);
+        collection.extend_from_slice(&offset.to_be_bytes());
+        collection.extend_from_slice(&record[12..]);
+    }
+    collection.extend_from_slice(&bytes[directory_len..]);
+    let dsig = dsig_table(&Sha256::digest(&collection));
+    let mut signed = collection.clone();
+    signed[16..20].copy_from_slice(b"DSIG");
+    signed[20..24].copy_from_slice(&(dsig.len() as u32).to_be_bytes());
+    signed[24..28].copy_from_slice(&(collection.len() as u32).to_be_bytes());
+    signed.extend_from_slice(&dsig);
+    let ttc = Ttc::from_bytes(signed).unwrap();
+    assert_eq!(ttc.dsig_coverage().unwrap(), SignatureCoverage::Covered);
+}
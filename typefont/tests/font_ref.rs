@@ -0,0 +1,51 @@
+use typefont::font::font_ref::FontRef;
+
+#[test]
+fn borrow_table_data() {
+    let data = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = FontRef::new(&data).unwrap();
+    let range = data.as_ptr_range();
+
+    let glyf = font.glyf().unwrap().unwrap();
+    assert_eq!(glyf.len(), 3377);
+    let glyph = glyf.glyph(36).unwrap().unwrap();
+    assert!(!glyph.is_composite());
+    let instructions = glyph.instructions().unwrap();
+    assert!(range.contains(&instructions.as_ptr()));
+    // 空格没有轮廓
+    assert!(glyf.glyph(3).unwrap().is_none());
+
+    let name = font.name().unwrap().unwrap();
+    let record = name
+        .records()
+        .unwrap()
+        .into_iter()
+        .find(|r| r.platform_id() == 1 && r.name_id() == 1)
+        .unwrap();
+    let family = name.string_data(&record).unwrap();
+    assert!(range.contains(&family.as_ptr()));
+    assert_eq!(family, b"DejaVu Sans Mono");
+
+    let hmtx = font.hmtx().unwrap().unwrap();
+    assert_eq!(hmtx.advance_width(36), Some(1233));
+}
+
+#[test]
+fn loca_ref_bounds() {
+    use typefont::font::table::loca::LocaRef;
+
+    let data = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = FontRef::new(&data).unwrap();
+    let glyf = font.glyf().unwrap().unwrap();
+    assert!(glyf.glyph(3377).is_err());
+    assert!(glyf.glyph(u16::MAX).is_err());
+
+    // 偏移量个数由 `num_glyphs` 决定，多余的数据被忽略
+    let offsets = [0u8, 0, 0, 5, 0, 9, 0, 0];
+    let loca = LocaRef::new(&offsets, 0, 2).unwrap();
+    assert_eq!(loca.len(), 3);
+    assert_eq!(loca.glyph_range(1), Some(10..18));
+    assert_eq!(loca.get(3), None);
+    assert!(LocaRef::new(&offsets, 1, 2).is_err());
+    assert!(LocaRef::new(&offsets, 2, 1).is_err());
+}
@@ -0,0 +1,193 @@
+use typefont::font::font_ref::FontRef;
+use typefont::font::open_type::{OpenType, read_font};
+use typefont::font::table::glyf::{
+    ComponentPlacement, ComponentTransform, GlyfGlyphDescription, GlyphPoint,
+};
+use typefont::types::Tag;
+
+#[test]
+fn read_glyf() {
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let glyf = font.glyf().unwrap().unwrap();
+    let loca = font.loca().unwrap().unwrap();
+    assert_eq!(glyf.len(), 3377);
+    assert_eq!(loca.len(), 3378);
+    // 空格没有轮廓
+    assert!(font.glyph(3).unwrap().is_none());
+
+    let a = font.glyph(36).unwrap().unwrap();
+    assert!(!a.is_composite());
+    let contours = a.contours();
+    assert_eq!(contours.len(), a.header().number_of_contours() as usize);
+    let points: Vec<_> = contours.iter().flat_map(|c| c.iter()).collect();
+    assert_eq!(points.iter().map(|p| p.x()).min(), Some(a.header().x_min()));
+    assert_eq!(points.iter().map(|p| p.x()).max(), Some(a.header().x_max()));
+    assert_eq!(points.iter().map(|p| p.y()).min(), Some(a.header().y_min()));
+    assert_eq!(points.iter().map(|p| p.y()).max(), Some(a.header().y_max()));
+    // 字形数据是同一份表数据中的相邻片段
+    let (a, b) = (glyf.glyph_data(36).unwrap(), glyf.glyph_data(37).unwrap());
+    assert_eq!(a.as_ptr_range().end, b.as_ptr());
+
+    // 与借用视图中的数据一致
+    let bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font_ref = FontRef::new(&bytes).unwrap();
+    let glyf_ref = font_ref.glyf().unwrap().unwrap();
+    for glyph_id in 0..glyf.len() as u16 {
+        let glyph = glyf.glyph(glyph_id).unwrap();
+        let glyph_ref = glyf_ref.glyph(glyph_id).unwrap();
+        assert_eq!(glyph.is_some(), glyph_ref.is_some());
+        let (Some(glyph), Some(glyph_ref)) = (glyph, glyph_ref) else {
+            continue;
+        };
+        assert_eq!(glyph.is_composite(), glyph_ref.is_composite());
+        if let GlyfGlyphDescription::Format1(simple) = glyph.glyph_description() {
+            assert_eq!(
+                *simple.end_pts_of_contours(),
+                glyph_ref.end_pts_of_contours().unwrap()
+            );
+            assert_eq!(simple.instructions(), glyph_ref.instructions().unwrap());
+            assert_eq!(simple.points().len(), simple.flags().len());
+        }
+    }
+}
+
+#[test]
+fn glyph_error_location() {
+    let mut bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    let range = font.loca().unwrap().unwrap().glyph_range(36).unwrap();
+    let glyf = font.table_directory().find(&Tag::from(*b"glyf")).unwrap();
+    let offset = font.table_directory().table_records()[glyf].offset() as usize + range.start;
+    // 第二个轮廓的结束点不大于第一个
+    bytes[offset + 12..offset + 14].copy_from_slice(&[0, 0]);
+
+    let font = OpenType::from_bytes(bytes).unwrap();
+    let err = font.glyph(36).err().unwrap();
+    assert_eq!(err.location().unwrap().offset(), Some(offset + 12));
+    assert!(
+        err.to_string()
+            .starts_with("glyf/glyph[36]/endPtsOfContours[1]: expected > ")
+    );
+    // 其他字形不受影响
+    assert!(font.glyph(37).unwrap().is_some());
+    assert!(font.glyph_outline(37).unwrap().is_some());
+}
+
+#[cfg(feature = "writer")]
+#[test]
+fn write_glyf() {
+    use typefont::font::io::WriteTo;
+    use typefont::io::vec_writer::VecWriter;
+
+    let bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    let mut writer = VecWriter::new();
+    font.glyf().unwrap().unwrap().write_to(&mut writer).unwrap();
+    assert_eq!(
+        writer.into_inner(),
+        font.table_bytes(&Tag::from(*b"glyf")).unwrap().unwrap()
+    );
+}
+
+#[test]
+fn composite_glyph() {
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let glyf = font.glyf().unwrap().unwrap();
+    let e_acute = font.glyph_index('é').unwrap();
+    let glyph = font.glyph(e_acute).unwrap().unwrap();
+    let GlyfGlyphDescription::Format2(composite) = glyph.glyph_description() else {
+        panic!("é is not composite");
+    };
+    assert!(glyph.contours().is_empty());
+    assert_eq!(composite.components().len(), 2);
+    assert_eq!(
+        composite.components()[0].glyph_index(),
+        font.glyph_index('e').unwrap()
+    );
+
+    // 展开后的轮廓为各组件平移后的轮廓
+    let outline = font.glyph_outline(e_acute).unwrap().unwrap();
+    let mut expected = vec![];
+    for component in composite.components() {
+        assert_eq!(component.transform(), ComponentTransform::None);
+        let ComponentPlacement::Offset { x, y } = component.placement() else {
+            panic!("unexpected anchor");
+        };
+        for contour in glyf.outline(component.glyph_index(), 1).unwrap() {
+            expected.push(
+                contour
+                    .iter()
+                    .map(|p| GlyphPoint::new(p.x() + x, p.y() + y, p.on_curve()))
+                    .collect::<Vec<_>>(),
+            );
+        }
+    }
+    assert_eq!(outline, expected);
+
+    // 嵌套的复合字形
+    let max_depth = font
+        .maxp()
+        .unwrap()
+        .unwrap()
+        .as_v1_0()
+        .unwrap()
+        .max_component_depth();
+    let nested = (0..glyf.len() as u16)
+        .find(|&id| {
+            glyf.glyph(id)
+                .unwrap()
+                .is_some_and(|glyph| match glyph.glyph_description() {
+                    GlyfGlyphDescription::Format2(composite) => {
+                        composite.components().iter().any(|c| {
+                            glyf.glyph(c.glyph_index())
+                                .unwrap()
+                                .is_some_and(|g| g.is_composite())
+                        })
+                    }
+                    _ => false,
+                })
+        })
+        .unwrap();
+    assert!(!glyf.outline(nested, max_depth).unwrap().is_empty());
+    assert!(
+        glyf.outline(nested, 1)
+            .unwrap_err()
+            .to_string()
+            .contains("component depth")
+    );
+}
+
+#[test]
+fn composite_cycle() {
+    let mut bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    let e_acute = font.glyph_index('é').unwrap();
+    let range = font.loca().unwrap().unwrap().glyph_range(e_acute).unwrap();
+    let glyf = font.table_directory().find(&Tag::from(*b"glyf")).unwrap();
+    let offset = font.table_directory().table_records()[glyf].offset() as usize + range.start;
+    // 第一个组件引用自身
+    bytes[offset + 12..offset + 14].copy_from_slice(&e_acute.to_be_bytes());
+
+    let font = OpenType::from_bytes(bytes).unwrap();
+    let err = font.glyph_outline(e_acute).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("unable to operate: component cycle [{e_acute}] -> {e_acute}")
+    );
+}
+
+#[test]
+fn composite_without_maxp_v1() {
+    let mut bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    let e_acute = font.glyph_index('é').unwrap();
+    let expected = font.glyph_outline(e_acute).unwrap().unwrap();
+    let maxp = font.table_directory().find(&Tag::from(*b"maxp")).unwrap();
+    let offset = font.table_directory().table_records()[maxp].offset() as usize;
+    // 版本 0.5 的 `maxp` 没有 `maxComponentDepth`，使用默认的嵌套层数
+    bytes[offset..offset + 4].copy_from_slice(&0x00005000u32.to_be_bytes());
+
+    let font = OpenType::from_bytes(bytes).unwrap();
+    assert!(font.maxp().unwrap().unwrap().as_v1_0().is_none());
+    assert_eq!(font.glyph_outline(e_acute).unwrap().unwrap(), expected);
+}
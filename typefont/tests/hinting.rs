@@ -0,0 +1,95 @@
+use typefont::font::hinting::{HintOptions, Hinter};
+use typefont::font::open_type::{OpenType, read_font};
+use typefont::font::raster::{RasterOptions, rasterize_hinted_glyph};
+use typefont::types::Tag;
+
+#[test]
+fn hint_true_type() {
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let hinter = Hinter::new(&font, &HintOptions::new(12)).unwrap();
+    assert_eq!(hinter.ppem(), 12);
+
+    // 竖笔和横笔的边缘都对齐到整数像素
+    let i = font.glyph_index('I').unwrap();
+    let glyph = hinter.hint_glyph(i).unwrap();
+    let points: Vec<_> = glyph.points().iter().map(|p| (p.x(), p.y())).collect();
+    assert_eq!(
+        points,
+        [
+            (64, 576),
+            (384, 576),
+            (384, 512),
+            (256, 512),
+            (256, 64),
+            (384, 64),
+            (384, 0),
+            (64, 0),
+            (64, 64),
+            (192, 64),
+            (192, 512),
+            (64, 512),
+        ]
+    );
+    assert_eq!(glyph.advance_width(), 448);
+
+    let bitmap = rasterize_hinted_glyph(&hinter, i, &RasterOptions::new()).unwrap();
+    assert_eq!((bitmap.width(), bitmap.height()), (5, 9));
+    assert_eq!((bitmap.left(), bitmap.top()), (1, 9));
+    assert!(bitmap.data().iter().all(|&v| v == 0 || v == 255));
+
+    let o = font.glyph_index('o').unwrap();
+    let glyph = hinter.hint_glyph(o).unwrap();
+    assert_eq!(glyph.contours().len(), 2);
+    assert_eq!((glyph.points()[0].x(), glyph.points()[0].y()), (224, 384));
+    assert_eq!((glyph.points()[12].x(), glyph.points()[12].y()), (224, 448));
+
+    // 复合字形的组件分别网格拟合
+    let e_acute = font.glyph_index('é').unwrap();
+    let glyph = hinter.hint_glyph(e_acute).unwrap();
+    assert_eq!(glyph.points().len(), 33);
+    let accent: Vec<_> = glyph.points()[29..]
+        .iter()
+        .map(|p| (p.x(), p.y()))
+        .collect();
+    assert_eq!(accent, [(305, 640), (384, 640), (254, 512), (192, 512)]);
+}
+
+#[test]
+fn hint_errors() {
+    let bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    let i = font.glyph_index('I').unwrap();
+    let o = font.glyph_index('o').unwrap();
+    let range = font.loca().unwrap().unwrap().glyph_range(i).unwrap();
+    let glyf = font.table_directory().find(&Tag::from(*b"glyf")).unwrap();
+    // 只有一条轮廓，指令位于 10 字节的头、2 字节的 `end_pts_of_contours` 和指令长度之后
+    let offset = font.table_directory().table_records()[glyf].offset() as usize + range.start + 14;
+    let with_instructions = |instructions: &[u8]| {
+        let mut bytes = bytes.clone();
+        bytes[offset..offset + instructions.len()].copy_from_slice(instructions);
+        OpenType::from_bytes(bytes).unwrap()
+    };
+
+    // PUSHW[-3] JMPR 跳回开头
+    let font = with_instructions(&[0xB8, 0xFF, 0xFD, 0x1C]);
+    let options = HintOptions::new(12).with_instruction_limit(10_000);
+    let hinter = Hinter::new(&font, &options).unwrap();
+    let err = hinter.hint_glyph(i).unwrap_err();
+    assert!(err.to_string().contains("instruction limit 10000 exceeded"));
+    // 其他字形不受影响
+    assert!(hinter.hint_glyph(o).is_ok());
+
+    // 每次循环 DUP 一次
+    let font = with_instructions(&[0xB0, 1, 0x20, 0xB8, 0xFF, 0xFC, 0x1C]);
+    let hinter = Hinter::new(&font, &HintOptions::new(12)).unwrap();
+    let err = hinter.hint_glyph(i).unwrap_err();
+    assert!(err.to_string().contains("stack overflow"));
+
+    let font = with_instructions(&[0x28]);
+    let hinter = Hinter::new(&font, &HintOptions::new(12)).unwrap();
+    let err = hinter.hint_glyph(i).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("unable to operate: glyph {i}: glyf[0]: invalid opcode 0x28")
+    );
+}
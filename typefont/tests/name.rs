@@ -0,0 +1,149 @@
+use typefont::font::open_type::read_font;
+use typefont::font::table::name::name_id;
+
+#[test]
+fn read_name() {
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let name = font.name().unwrap().unwrap();
+    assert_eq!(name.version(), 0);
+    assert_eq!(
+        name.storage_offset() as usize,
+        6 + 12 * name.name_records().len()
+    );
+    assert_eq!(name.name_records().len(), 22);
+    // 平台 1 的 Mac OS Roman 与平台 3 的 `UTF-16BE`
+    assert_eq!(name.language_tag(&name.name_records()[0]), Some("en"));
+    assert_eq!(name.language_tag(&name.name_records()[11]), Some("en-US"));
+    assert_eq!(
+        name.get(name_id::FAMILY_NAME, "en").as_deref(),
+        Some("DejaVu Sans Mono")
+    );
+    assert_eq!(
+        name.get(name_id::POSTSCRIPT_NAME, "EN-us").as_deref(),
+        Some("DejaVuSansMono")
+    );
+    assert_eq!(name.get(name_id::FAMILY_NAME, "fr-FR"), None);
+    assert!(name.string(12).unwrap().starts_with("DejaVu"));
+    assert_eq!(name.string_data(12).unwrap().len(), 32);
+}
+
+#[cfg(feature = "writer")]
+fn parse_name(bytes: Vec<u8>) -> typefont::font::table::name::Name {
+    use typefont::font::io::ReadFrom;
+    use typefont::font::table::name::Name;
+    use typefont::io::reader::ReaderBoxed;
+    use typefont::io::slice_reader::SliceReader;
+
+    let mut reader: ReaderBoxed = Box::new(SliceReader::new(bytes));
+    Name::read_from(&mut reader).unwrap()
+}
+
+#[cfg(feature = "writer")]
+#[test]
+fn edit_name() {
+    use typefont::font::io::WriteTo;
+    use typefont::io::vec_writer::VecWriter;
+    use typefont::types::Tag;
+
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let bytes = font.table_bytes(&Tag::from(*b"name")).unwrap().unwrap();
+    let mut name = parse_name(bytes);
+
+    name.set(name_id::FAMILY_NAME, "en-US", "DejaVu Sans Mono Internal")
+        .unwrap();
+    // Mac OS Roman 无法编码时不修改记录
+    assert!(name.set(name_id::FAMILY_NAME, "en", "等宽").is_err());
+    name.set(name_id::FAMILY_NAME, "en", "DejaVu Sans Mono Intérieur")
+        .unwrap();
+    // 语言 ID 表中的语言添加平台 3 记录，其余语言添加语言标签记录
+    name.set(name_id::FAMILY_NAME, "ja-JP", "等幅").unwrap();
+    name.set(name_id::FAMILY_NAME, "zh-Hans-CN", "等宽")
+        .unwrap();
+    assert_eq!(name.version(), 1);
+    assert_eq!(name.remove(name_id::LICENSE_INFO_URL, "en-US"), 1);
+    assert_eq!(name.remove(name_id::LICENSE_INFO_URL, "en-US"), 0);
+
+    let mut writer = VecWriter::new();
+    name.write_to(&mut writer).unwrap();
+    let name = parse_name(writer.into_inner());
+    assert_eq!(name.version(), 1);
+    assert_eq!(name.name_records().len(), 23);
+    assert_eq!(name.lang_tags(), ["zh-Hans-CN"]);
+    assert_eq!(
+        name.get(name_id::FAMILY_NAME, "en-US").as_deref(),
+        Some("DejaVu Sans Mono Internal")
+    );
+    assert_eq!(
+        name.get(name_id::FAMILY_NAME, "en").as_deref(),
+        Some("DejaVu Sans Mono Intérieur")
+    );
+    assert_eq!(
+        name.get(name_id::FAMILY_NAME, "ja-JP").as_deref(),
+        Some("等幅")
+    );
+    assert_eq!(
+        name.get(name_id::FAMILY_NAME, "zh-Hans-CN").as_deref(),
+        Some("等宽")
+    );
+    assert_eq!(name.get(name_id::LICENSE_INFO_URL, "en-US"), None);
+    // 记录保持排序
+    let records = name.name_records();
+    assert!(records.windows(2).all(|w| {
+        let key = |r: &typefont::font::table::name::NameRecord| {
+            (
+                r.platform_id(),
+                r.encoding_id(),
+                r.language_id(),
+                r.name_id(),
+            )
+        };
+        key(&w[0]) < key(&w[1])
+    }));
+    // 相同的字符串只保存一次
+    let unique_id = records
+        .iter()
+        .find(|r| r.platform_id() == 3 && r.name_id() == name_id::UNIQUE_IDENTIFIER)
+        .unwrap();
+    let full_name = records
+        .iter()
+        .find(|r| r.platform_id() == 3 && r.name_id() == name_id::FULL_NAME)
+        .unwrap();
+    assert_eq!(unique_id.string_offset(), full_name.string_offset());
+}
+
+#[cfg(feature = "writer")]
+#[test]
+fn rename_family() {
+    use typefont::font::io::WriteTo;
+    use typefont::font::open_type::OpenType;
+    use typefont::font::table::Table;
+    use typefont::io::vec_writer::VecWriter;
+    use typefont::types::Tag;
+
+    let mut font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let tag = Tag::from(*b"name");
+    let mut name = parse_name(font.table_bytes(&tag).unwrap().unwrap());
+    name.set(name_id::FAMILY_NAME, "en-US", "DejaVu Sans Mono Internal")
+        .unwrap();
+    font.set_table(tag, Table::Name(name));
+    // 字体中没有的表添加到表目录中
+    let test = Tag::from(*b"TEST");
+    let table = Table::Unknown {
+        tag: test.clone(),
+        bytes: vec![1, 2, 3],
+    };
+    font.set_table(test.clone(), table);
+    let family = |font: &OpenType| {
+        let name = font.name().unwrap().unwrap();
+        name.get(name_id::FAMILY_NAME, "en-US")
+    };
+    assert_eq!(family(&font).as_deref(), Some("DejaVu Sans Mono Internal"));
+
+    let mut writer = VecWriter::new();
+    font.write_to(&mut writer).unwrap();
+    let font = OpenType::from_bytes(writer.into_inner()).unwrap();
+    assert_eq!(family(&font).as_deref(), Some("DejaVu Sans Mono Internal"));
+    assert_eq!(font.table_bytes(&test).unwrap(), Some(vec![1, 2, 3]));
+    assert_eq!(font.maxp().unwrap().unwrap().num_glyphs(), 3377);
+    assert!(font.verify_checksums().unwrap().is_ok());
+}
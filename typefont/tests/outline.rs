@@ -0,0 +1,275 @@
+use typefont::font::open_type::{OpenType, read_font};
+use typefont::font::outline::{BoundsPen, ControlBoundsPen, CubicPen, PathCommand, RecordingPen};
+
+/// 只包含一个表的字体
+fn font_with_table(tag: &[u8; 4], table: &[u8]) -> OpenType {
+    let mut out = vec![];
+    out.extend_from_slice(&[0x4F, 0x54, 0x54, 0x4F, 0, 1, 0, 16, 0, 0, 0, 0]);
+    out.extend_from_slice(tag);
+    out.extend_from_slice(&0u32.to_be_bytes());
+    out.extend_from_slice(&28u32.to_be_bytes());
+    out.extend_from_slice(&(table.len() as u32).to_be_bytes());
+    out.extend_from_slice(table);
+    OpenType::from_bytes(out).unwrap()
+}
+
+/// `offSize` 为 1 的 INDEX，`count_size` 为 `count` 字段的字节数
+fn index(count_size: usize, objects: &[&[u8]]) -> Vec<u8> {
+    let mut out = (objects.len() as u32).to_be_bytes()[4 - count_size..].to_vec();
+    if objects.is_empty() {
+        return out;
+    }
+    out.push(1);
+    let mut offset = 1;
+    out.push(offset);
+    for object in objects {
+        offset += object.len() as u8;
+        out.push(offset);
+    }
+    for object in objects {
+        out.extend_from_slice(object);
+    }
+    out
+}
+
+/// 5 字节的 DICT 整数操作数
+fn operand(value: usize) -> Vec<u8> {
+    let mut out = vec![29];
+    out.extend_from_slice(&(value as i32).to_be_bytes());
+    out
+}
+
+fn dict(entries: &[(usize, &[u8])]) -> Vec<u8> {
+    entries
+        .iter()
+        .flat_map(|&(value, operator)| [operand(value), operator.to_vec()].concat())
+        .collect()
+}
+
+#[test]
+fn draw_true_type() {
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let o = font.glyph_index('o').unwrap();
+    let mut pen = RecordingPen::new();
+    font.draw_glyph(o, &mut pen).unwrap();
+    let commands = pen.commands();
+    // 两条轮廓，隐含的曲线上点生成连续的二次曲线
+    assert_eq!(
+        commands
+            .iter()
+            .filter(|c| **c == PathCommand::Close)
+            .count(),
+        2
+    );
+    assert!(matches!(commands[0], PathCommand::MoveTo(..)));
+    assert!(
+        commands
+            .iter()
+            .any(|c| matches!(c, PathCommand::QuadTo(..)))
+    );
+
+    let mut bounds = BoundsPen::new();
+    let mut control_bounds = ControlBoundsPen::new();
+    pen.replay(&mut bounds);
+    pen.replay(&mut control_bounds);
+    let bounds = bounds.bounds().unwrap();
+    let control_bounds = control_bounds.bounds().unwrap();
+    let header = font.glyph(o).unwrap().unwrap().header();
+    assert_eq!(
+        (
+            bounds.x_min(),
+            bounds.y_min(),
+            bounds.x_max(),
+            bounds.y_max()
+        ),
+        (
+            header.x_min() as f32,
+            header.y_min() as f32,
+            header.x_max() as f32,
+            header.y_max() as f32
+        )
+    );
+    assert!(control_bounds.x_min() <= bounds.x_min() && control_bounds.y_max() >= bounds.y_max());
+
+    // 转换为三次曲线后端点不变
+    let mut cubic = CubicPen::new(RecordingPen::new());
+    pen.replay(&mut cubic);
+    let cubic = cubic.into_inner().into_commands();
+    assert_eq!(cubic.len(), commands.len());
+    for (quad, cubic) in commands.iter().zip(&cubic) {
+        match (quad, cubic) {
+            (PathCommand::QuadTo(_, _, x, y), PathCommand::CurveTo(_, _, _, _, cx, cy)) => {
+                assert_eq!((x, y), (cx, cy))
+            }
+            (PathCommand::QuadTo(..), _) => panic!("quadratic curve left"),
+            (quad, cubic) => assert_eq!(quad, cubic),
+        }
+    }
+
+    // 复合字形与空字形
+    let mut pen = ControlBoundsPen::new();
+    font.draw_glyph(font.glyph_index('é').unwrap(), &mut pen)
+        .unwrap();
+    assert!(pen.bounds().unwrap().y_max() > bounds.y_max());
+    let mut pen = RecordingPen::new();
+    font.draw_glyph(font.glyph_index(' ').unwrap(), &mut pen)
+        .unwrap();
+    assert!(pen.commands().is_empty());
+}
+
+#[test]
+fn draw_cff() {
+    // 宽度 10，(100, 100) 开始，忽略 `dotsection`，水平线后调用全局子程序画曲线
+    let glyph = [149, 239, 239, 21, 12, 0, 247, 92, 6, 32, 29, 14];
+    let global_subr = [189, 139, 189, 189, 139, 189, 8, 11];
+    let private = [139, 21];
+
+    let top_dict_len = 5 * 3 + 2;
+    let top_dict_index_len = 2 + 1 + 2 + top_dict_len;
+    let char_strings_offset = 4 + 6 + top_dict_index_len + 2 + 13;
+    let private_offset = char_strings_offset + 7 + glyph.len();
+    let top_dict = dict(&[
+        (char_strings_offset, &[17]),
+        (private.len(), &[]),
+        (private_offset, &[18]),
+    ]);
+    let mut cff = vec![1, 0, 4, 1];
+    cff.extend(index(2, &[b"A"]));
+    cff.extend(index(2, &[&top_dict]));
+    cff.extend(index(2, &[]));
+    cff.extend(index(2, &[&global_subr]));
+    assert_eq!(cff.len(), char_strings_offset);
+    cff.extend(index(2, &[&[14], &glyph]));
+    assert_eq!(cff.len(), private_offset);
+    cff.extend_from_slice(&private);
+
+    let font = font_with_table(b"CFF ", &cff);
+    let table = font.cff().unwrap().unwrap();
+    assert_eq!(table.font_name(), "A");
+    assert_eq!(table.len(), 2);
+    let mut pen = RecordingPen::new();
+    font.draw_glyph(1, &mut pen).unwrap();
+    assert_eq!(
+        pen.commands(),
+        &[
+            PathCommand::MoveTo(100.0, 100.0),
+            PathCommand::LineTo(300.0, 100.0),
+            PathCommand::CurveTo(350.0, 100.0, 400.0, 150.0, 400.0, 200.0),
+            PathCommand::Close,
+        ]
+    );
+    let mut pen = BoundsPen::new();
+    font.draw_glyph(0, &mut pen).unwrap();
+    assert_eq!(pen.bounds(), None);
+    assert!(font.draw_glyph(2, &mut pen).is_err());
+}
+
+#[test]
+fn draw_cff_seac() {
+    // A 为水平线，acute 为竖线，Aacute 的 `endchar` 组合 A（代码 65）和平移 (50, 200) 的 acute（代码 194）
+    let a = [139, 139, 21, 239, 139, 5, 14];
+    let acute = [139, 139, 21, 139, 189, 5, 14];
+    let a_acute = [189, 247, 92, 204, 247, 86, 14];
+    // B（代码 66）不在 `charset` 中
+    let b_acute = [189, 247, 92, 205, 247, 86, 14];
+    let private = [139, 21];
+
+    let top_dict_len = 5 * 4 + 3;
+    let char_strings_offset = 4 + 6 + 2 + 1 + 2 + top_dict_len + 2 + 2;
+    let charset_offset = char_strings_offset + 2 + 1 + 6 + 1 + 4 * 7;
+    let private_offset = charset_offset + 9;
+    let top_dict = dict(&[
+        (char_strings_offset, &[17]),
+        (private.len(), &[]),
+        (private_offset, &[18]),
+        (charset_offset, &[15]),
+    ]);
+    let mut cff = vec![1, 0, 4, 1];
+    cff.extend(index(2, &[b"A"]));
+    cff.extend(index(2, &[&top_dict]));
+    cff.extend(index(2, &[]));
+    cff.extend(index(2, &[]));
+    assert_eq!(cff.len(), char_strings_offset);
+    cff.extend(index(2, &[&[14], &a, &acute, &a_acute, &b_acute]));
+    assert_eq!(cff.len(), charset_offset);
+    // 格式 0，SID 分别为 A、acute、Aacute、Bacute（自定义字符串）
+    cff.extend_from_slice(&[0, 0, 34, 0, 125, 0, 174, 1, 135]);
+    assert_eq!(cff.len(), private_offset);
+    cff.extend_from_slice(&private);
+
+    let font = font_with_table(b"CFF ", &cff);
+    assert_eq!(
+        font.cff().unwrap().unwrap().charset(),
+        &[0, 34, 125, 174, 391]
+    );
+    let mut pen = RecordingPen::new();
+    font.draw_glyph(3, &mut pen).unwrap();
+    assert_eq!(
+        pen.commands(),
+        &[
+            PathCommand::MoveTo(0.0, 0.0),
+            PathCommand::LineTo(100.0, 0.0),
+            PathCommand::Close,
+            PathCommand::MoveTo(50.0, 200.0),
+            PathCommand::LineTo(50.0, 250.0),
+            PathCommand::Close,
+        ]
+    );
+    let err = font.draw_glyph(4, &mut pen).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unable to operate: seac of glyph 4: character code 66 not in charset"
+    );
+}
+
+#[test]
+fn reject_cff2_index_count() {
+    // 空的 `Top DICT` 之后，全局子程序 INDEX 的 `count` 远超剩余数据
+    let mut cff2 = vec![2, 0, 5, 0, 0];
+    cff2.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 1, 1, 2]);
+    let font = font_with_table(b"CFF2", &cff2);
+    assert!(font.cff2().is_err());
+}
+
+#[test]
+fn draw_cff2() {
+    // (100, 100) 开始，`blend` 只保留默认值 200 作为水平线的长度
+    let glyph = [239, 239, 21, 247, 92, 149, 159, 140, 16, 6];
+
+    let top_dict_len = 6 + 7 + 6;
+    let vstore_offset = 5 + top_dict_len + 4;
+    let char_strings_offset = vstore_offset + 24;
+    let fd_array_offset = char_strings_offset + 4 + 1 + 3 + glyph.len();
+    let private_offset = fd_array_offset + 4 + 1 + 2 + 11;
+    let top_dict = dict(&[
+        (char_strings_offset, &[17]),
+        (fd_array_offset, &[12, 36]),
+        (vstore_offset, &[24]),
+    ]);
+    let mut cff2 = vec![2, 0, 5];
+    cff2.extend_from_slice(&(top_dict.len() as u16).to_be_bytes());
+    cff2.extend(&top_dict);
+    cff2.extend(index(4, &[]));
+    assert_eq!(cff2.len(), vstore_offset);
+    // `ItemVariationStore` 中一个有 2 个区域的 `ItemVariationData`
+    cff2.extend_from_slice(&[0, 22, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 12]);
+    cff2.extend_from_slice(&[0, 0, 0, 0, 0, 2, 0, 0, 0, 1]);
+    assert_eq!(cff2.len(), char_strings_offset);
+    cff2.extend(index(4, &[&[], &glyph]));
+    assert_eq!(cff2.len(), fd_array_offset);
+    cff2.extend(index(4, &[&dict(&[(0, &[]), (private_offset, &[18])])]));
+    assert_eq!(cff2.len(), private_offset);
+
+    let font = font_with_table(b"CFF2", &cff2);
+    assert_eq!(font.cff2().unwrap().unwrap().region_counts(), &[2]);
+    let mut pen = RecordingPen::new();
+    font.draw_glyph(1, &mut pen).unwrap();
+    assert_eq!(
+        pen.commands(),
+        &[
+            PathCommand::MoveTo(100.0, 100.0),
+            PathCommand::LineTo(300.0, 100.0),
+            PathCommand::Close,
+        ]
+    );
+}
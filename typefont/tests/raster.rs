@@ -0,0 +1,120 @@
+use typefont::font::open_type::{OpenType, read_font};
+use typefont::font::outline::OutlinePen;
+use typefont::font::raster::{
+    MAX_BITMAP_PIXELS, RasterOptions, Rasterizer, Transform, rasterize_glyph,
+};
+use typefont::types::Tag;
+
+#[test]
+fn rasterize_true_type() {
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let units_per_em = font.head().unwrap().unwrap().units_per_em() as f32;
+    let i = font.glyph_index('I').unwrap();
+
+    // 每个设计单位一个像素，水平和竖直的边都落在像素边界上
+    let bitmap = rasterize_glyph(&font, i, units_per_em, &RasterOptions::new()).unwrap();
+    assert_eq!((bitmap.width(), bitmap.height()), (829, 1493));
+    assert_eq!((bitmap.left(), bitmap.top()), (201, 1493));
+    assert!(bitmap.data().iter().all(|&v| v == 0 || v == 255));
+    assert_eq!(bitmap.pixel(414, 746), 255);
+
+    // 半个像素的偏移让左右边缘各覆盖一半
+    let options = RasterOptions::new().with_offset(0.5, 0.0);
+    let bitmap = rasterize_glyph(&font, i, units_per_em, &options).unwrap();
+    assert_eq!(bitmap.width(), 830);
+    assert_eq!(&bitmap.data()[..2], &[128, 255]);
+    assert_eq!(bitmap.pixel(829, 0), 128);
+
+    // 内外两条轮廓方向相反，中间留空
+    let o = font.glyph_index('o').unwrap();
+    let bitmap = rasterize_glyph(&font, o, 24.0, &RasterOptions::new()).unwrap();
+    assert_eq!((bitmap.width(), bitmap.height()), (12, 15));
+    assert_eq!(bitmap.pixel(6, 7), 0);
+    assert_eq!(bitmap.pixel(1, 7), 255);
+
+    let options = RasterOptions::new().with_transform(Transform::scale(2.0, 1.0));
+    let bitmap = rasterize_glyph(&font, i, units_per_em, &options).unwrap();
+    assert_eq!((bitmap.width(), bitmap.left()), (1658, 402));
+
+    let space = font.glyph_index(' ').unwrap();
+    let bitmap = rasterize_glyph(&font, space, 16.0, &RasterOptions::new()).unwrap();
+    assert!(bitmap.data().is_empty());
+}
+
+#[test]
+fn rasterize_non_zero() {
+    let square = |pen: &mut Rasterizer, x: f32, y: f32, size: f32, clockwise: bool| {
+        pen.move_to(x, y);
+        match clockwise {
+            true => {
+                pen.line_to(x, y + size);
+                pen.line_to(x + size, y + size);
+                pen.line_to(x + size, y);
+            }
+            false => {
+                pen.line_to(x + size, y);
+                pen.line_to(x + size, y + size);
+                pen.line_to(x, y + size);
+            }
+        }
+        pen.close();
+    };
+
+    // 同向重叠的部分仍然完全覆盖
+    let mut pen = Rasterizer::new(Transform::identity());
+    square(&mut pen, 0.0, 0.0, 4.0, true);
+    square(&mut pen, 2.0, 2.0, 4.0, true);
+    let bitmap = pen.into_bitmap().unwrap();
+    assert_eq!((bitmap.width(), bitmap.height()), (6, 6));
+    assert_eq!(bitmap.pixel(2, 2), 255);
+    assert_eq!(bitmap.pixel(0, 0), 0);
+    assert_eq!(bitmap.pixel(5, 5), 0);
+
+    // 反向的轮廓挖空，曲线在像素中部分覆盖
+    let mut pen = Rasterizer::new(Transform::identity());
+    square(&mut pen, 0.0, 0.0, 6.0, true);
+    square(&mut pen, 2.0, 2.0, 2.0, false);
+    pen.move_to(0.0, 10.0);
+    pen.quad_to(3.0, 16.0, 6.0, 10.0);
+    pen.close();
+    let bitmap = pen.into_bitmap().unwrap();
+    assert_eq!(bitmap.top(), 13);
+    assert_eq!(bitmap.pixel(2, 10), 0);
+    assert_eq!(bitmap.pixel(1, 10), 255);
+    let curve = &bitmap.data()[..6 * 3];
+    assert!(curve.iter().any(|&v| v != 0 && v != 255));
+}
+
+#[test]
+fn reject_invalid_size() {
+    let mut bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    let i = font.glyph_index('I').unwrap();
+    for ppem in [0.0, -16.0, f32::NAN, f32::INFINITY] {
+        assert!(rasterize_glyph(&font, i, ppem, &RasterOptions::new()).is_err());
+    }
+
+    // 位图超过像素数上限，或变换后的坐标不是有限值
+    let err = rasterize_glyph(&font, i, 1e6, &RasterOptions::new())
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains(&MAX_BITMAP_PIXELS.to_string()));
+    let options = RasterOptions::new().with_transform(Transform::scale(f32::MAX, 1.0));
+    assert!(rasterize_glyph(&font, i, 16.0, &options).is_err());
+    let mut pen = Rasterizer::new(Transform::identity());
+    pen.move_to(f32::MIN, 0.0);
+    pen.line_to(f32::MAX, 1.0);
+    assert!(pen.into_bitmap().is_err());
+
+    let head = font.table_directory().find(&Tag::from(*b"head")).unwrap();
+    let offset = font.table_directory().table_records()[head].offset() as usize;
+    bytes[offset + 18..offset + 20].fill(0);
+    let font = OpenType::from_bytes(bytes).unwrap();
+    assert_eq!(
+        rasterize_glyph(&font, i, 16.0, &RasterOptions::new())
+            .err()
+            .unwrap()
+            .to_string(),
+        "unable to operate: head.unitsPerEm is 0"
+    );
+}
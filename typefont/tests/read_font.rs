@@ -1,7 +1,193 @@
-use typefont::font::open_type::read_font;
+use std::sync::Arc;
+use typefont::font::open_type::{OpenType, read_font};
+use typefont::font::table::Table;
+use typefont::font::table::head::HEAD_MAGIC_NUMBER;
+use typefont::io::error::IOError;
+use typefont::types::Tag;
 
 #[test]
 fn test() {
     let r = read_font("test.otf".into());
     assert!(r.is_ok());
 }
+
+#[test]
+fn read_tables() {
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let tables = font.tables().unwrap();
+    assert_eq!(tables.len(), font.table_directory().table_records().len());
+
+    let head = tables.iter().find_map(|t| match t {
+        Table::Head(head) => Some(head),
+        _ => None,
+    });
+    assert_eq!(head.unwrap().units_per_em(), 2048);
+
+    assert!(tables.iter().any(|t| matches!(t, Table::Unknown { .. })));
+}
+
+#[test]
+fn read_table_lazily() {
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    // `hmtx` 依赖的 `hhea` 和 `maxp` 会在读取时一并读取
+    let hmtx = font.hmtx().unwrap().unwrap();
+    assert_eq!(hmtx.advance_width(36), Some(1233));
+    assert_eq!(font.maxp().unwrap().unwrap().num_glyphs(), 3377);
+    assert!(font.table(&"abcd".try_into().unwrap()).unwrap().is_none());
+}
+
+#[test]
+fn read_from_bytes() {
+    let bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(Arc::<[u8]>::from(bytes)).unwrap();
+    let head = font.head().unwrap().unwrap();
+    assert_eq!(head.units_per_em(), 2048);
+    assert_eq!(head.magic_number(), HEAD_MAGIC_NUMBER);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn read_mmap() {
+    use typefont::font::font_ref::FontRef;
+    use typefont::io::mmap_reader::MmapReader;
+
+    let reader = MmapReader::open("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = FontRef::new(reader.data()).unwrap();
+    assert_eq!(font.head().unwrap().unwrap().units_per_em(), 2048);
+
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    assert_eq!(font.maxp().unwrap().unwrap().num_glyphs(), 3377);
+}
+
+#[test]
+fn located_error() {
+    let mut bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    let record = font
+        .table_directory()
+        .table_records()
+        .iter()
+        .find(|r| r.tag().to_bytes() == *b"head")
+        .unwrap();
+    let magic_offset = record.offset() as usize + 12;
+    bytes[magic_offset..magic_offset + 4].copy_from_slice(&[0, 0, 0, 0]);
+
+    let font = OpenType::from_bytes(bytes).unwrap();
+    let err = font.head().err().unwrap();
+    let location = err.location().unwrap();
+    assert_eq!(location.tag(), Some(&Tag::from(*b"head")));
+    assert_eq!(location.offset(), Some(magic_offset));
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "head/magicNumber: expected 0x5F0F3CF5, found 0x00000000 at 0x{:X}",
+            magic_offset
+        )
+    );
+}
+
+#[test]
+fn truncated_table_offset() {
+    let mut bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    let index = font.table_directory().find(&Tag::from(*b"hhea")).unwrap();
+    let offset = font.table_directory().table_records()[index].offset() as usize;
+    // 表记录的长度截断到 `caretSlopeRun` 之前
+    let length = 12 + 16 * index + 12;
+    bytes[length..length + 4].copy_from_slice(&20u32.to_be_bytes());
+
+    let font = OpenType::from_bytes(bytes).unwrap();
+    let err = font.hhea().err().unwrap();
+    assert!(matches!(err.kind(), IOError::NoEnoughData));
+    assert_eq!(err.location().unwrap().offset(), Some(offset + 20));
+    assert_eq!(
+        err.to_string(),
+        format!("hhea: not enough data at 0x{:X}", offset + 20)
+    );
+}
+
+#[test]
+fn verify_checksums() {
+    let mut bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    assert!(font.verify_checksums().unwrap().is_ok());
+
+    let glyf = font.table_directory().find(&Tag::from(*b"glyf")).unwrap();
+    let offset = font.table_directory().table_records()[glyf].offset() as usize;
+    bytes[offset + 1] ^= 0xFF;
+    let font = OpenType::from_bytes(bytes).unwrap();
+    let report = font.verify_checksums().unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.mismatches().len(), 1);
+    assert_eq!(report.mismatches()[0].tag(), &Tag::from(*b"glyf"));
+}
+
+#[test]
+fn read_cmap() {
+    let mut bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    let cmap = font.cmap().unwrap().unwrap();
+    let records = cmap.encoding_records();
+    let formats: Vec<_> = records
+        .iter()
+        .map(|r| (r.platform_id(), r.encoding_id(), r.subtable().format()))
+        .collect();
+    assert_eq!(
+        formats,
+        [(0, 3, 4), (0, 4, 12), (1, 0, 6), (3, 1, 4), (3, 10, 12)]
+    );
+    // 格式 4 与格式 12 的映射一致
+    for code in [0x41, 0xE9, 0x2500, 0xFFFD] {
+        let glyph_id = records[0].subtable().glyph_id(code);
+        assert!(glyph_id.is_some());
+        assert_eq!(records[1].subtable().glyph_id(code), glyph_id);
+    }
+    assert_eq!(records[0].subtable().glyph_id(0x10000), None);
+
+    // 格式 4 第二个段的 `startCode` 大于 `endCode`
+    let cmap_offset = font
+        .table_directory()
+        .table_records()
+        .iter()
+        .find(|r| r.tag().to_bytes() == *b"cmap")
+        .unwrap()
+        .offset() as usize;
+    let subtable = cmap_offset + records[0].subtable_offset() as usize;
+    let seg_count_x2 = u16::from_be_bytes([bytes[subtable + 6], bytes[subtable + 7]]) as usize;
+    let start_code = subtable + 16 + seg_count_x2;
+    bytes[start_code + 2..start_code + 4].copy_from_slice(&[0xFF, 0xFE]);
+    let font = OpenType::from_bytes(bytes).unwrap();
+    let err = font.cmap().err().unwrap();
+    assert_eq!(err.location().unwrap().offset(), Some(start_code + 2));
+    assert!(
+        err.to_string()
+            .starts_with("cmap/encodingRecord[0]/subtable format 4/startCode[1]: expected <= ")
+    );
+}
+
+#[test]
+fn glyph_index() {
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let cmap = font.cmap().unwrap().unwrap();
+    // 优先选择 (3, 10) 格式 12 子表
+    assert_eq!(cmap.best_unicode_subtable().unwrap().format(), 12);
+    assert_eq!(font.glyph_index('A'), Some(36));
+    assert_eq!(font.glyph_index('\u{10FFFF}'), None);
+
+    let mappings: Vec<_> = cmap.mappings().collect();
+    assert!(mappings.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(mappings.iter().all(|&(code, glyph_id)| {
+        font.glyph_index(char::from_u32(code).unwrap()) == Some(glyph_id)
+    }));
+    // 格式 4 子表的映射与格式 12 子表的 BMP 部分一致
+    let bmp: Vec<_> = cmap.encoding_records()[3].subtable().mappings().collect();
+    assert!(bmp.len() < mappings.len());
+    assert_eq!(bmp, mappings[..bmp.len()]);
+
+    let codepoints = cmap.codepoints_by_glyph();
+    assert_eq!(codepoints[&36], [0x41]);
+    assert_eq!(
+        codepoints.values().map(Vec::len).sum::<usize>(),
+        mappings.len()
+    );
+}
@@ -0,0 +1,26 @@
+use typefont::io::error::IOError;
+use typefont::io::reader::Reader;
+use typefont::io::slice_reader::SliceReader;
+
+#[test]
+fn sub_reader_bounds() {
+    let data: Vec<u8> = (0..16).collect();
+    let mut reader = SliceReader::new(data);
+    assert!(matches!(reader.sub_reader(12, 8), Err(IOError::NoEnoughData)));
+
+    let mut table = reader.sub_reader(4, 8).unwrap();
+    assert_eq!(table.position(), 0);
+    assert_eq!(table.size(), 8);
+    assert_eq!(table.read_u32().unwrap(), 0x04050607);
+    assert!(matches!(table.sub_reader(6, 4), Err(IOError::NoEnoughData)));
+
+    // 子表偏移量相对于表开头
+    let mut subtable = table.sub_reader(2, 4).unwrap();
+    assert_eq!(subtable.read_u16().unwrap(), 0x0607);
+    assert_eq!(subtable.read_u16().unwrap(), 0x0809);
+    assert!(matches!(subtable.read_u8(), Err(IOError::NoEnoughData)));
+    drop(subtable);
+
+    table.seek(6).unwrap();
+    assert!(matches!(table.read_u32(), Err(IOError::NoEnoughData)));
+}
@@ -0,0 +1,81 @@
+use typefont::font::open_type::read_font;
+use typefont::font::svg::{SvgOptions, glyph_document, glyph_path, text_document};
+
+#[test]
+fn svg_path() {
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let i = font.glyph_index('I').unwrap();
+    assert_eq!(
+        glyph_path(&font, i, &SvgOptions::new()).unwrap(),
+        "M201 -1493L1030 -1493L1030 -1323L717 -1323L717 -170L1030 -170L1030 0\
+         L201 0L201 -170L514 -170L514 -1323L201 -1323Z"
+    );
+    assert_eq!(
+        glyph_path(&font, i, &SvgOptions::new().with_relative(true)).unwrap(),
+        "m201 -1493l829 0l0 170l-313 0l0 1153l313 0l0 170l-829 0l0 -170l313 0\
+         l0 -1153l-313 0Z"
+    );
+    // 二次曲线的隐含点可能落在半个单位上
+    let o = font.glyph_index('o').unwrap();
+    let path = glyph_path(&font, o, &SvgOptions::new()).unwrap();
+    assert!(path.starts_with("M616 -991Q476 -991 404 -882"));
+    assert!(path.contains("-236.5"));
+    let path = glyph_path(&font, o, &SvgOptions::new().with_precision(0)).unwrap();
+    assert!(!path.contains('.'));
+
+    // 相对坐标累加后与绝对坐标一致，不累积取整误差
+    let options = SvgOptions::new().with_precision(0).with_relative(true);
+    let relative = glyph_path(&font, o, &options).unwrap();
+    assert_eq!(absolute_points(&relative), absolute_points(&path));
+}
+
+/// 路径中所有点的绝对坐标
+fn absolute_points(path: &str) -> Vec<(i32, i32)> {
+    let mut points = vec![];
+    let (mut current, mut start) = ((0, 0), (0, 0));
+    let mut rest = path;
+    while let Some(command) = rest.chars().next() {
+        let end = rest[1..]
+            .find(|c: char| c.is_ascii_alphabetic())
+            .map_or(rest.len(), |i| i + 1);
+        let numbers: Vec<i32> = rest[1..end]
+            .split_whitespace()
+            .map(|n| n.parse().unwrap())
+            .collect();
+        rest = &rest[end..];
+        let origin = match command.is_ascii_lowercase() {
+            true => current,
+            false => (0, 0),
+        };
+        for pair in numbers.chunks(2) {
+            points.push((origin.0 + pair[0], origin.1 + pair[1]));
+        }
+        current = match command {
+            'Z' | 'z' => start,
+            _ => *points.last().unwrap(),
+        };
+        if command.eq_ignore_ascii_case(&'m') {
+            start = current;
+        }
+    }
+    points
+}
+
+#[test]
+fn svg_document() {
+    let font = read_font("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let document =
+        glyph_document(&font, font.glyph_index('I').unwrap(), &SvgOptions::new()).unwrap();
+    // `hhea` 的 `ascender` 为 1901，`descender` 为 -483，前进宽度为 1233
+    assert!(document.starts_with(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 -1901 1233 2384\">\n<path d=\"M201 -1493"
+    ));
+    assert!(document.ends_with("Z\"/>\n</svg>\n"));
+
+    // 空格没有路径，但占用前进宽度
+    let options = SvgOptions::new().with_font_size(16.0);
+    let document = text_document(&font, "H i", &options).unwrap();
+    assert!(document.contains("viewBox=\"0 -1901 3699 2384\" width=\"28.9\" height=\"18.62\""));
+    assert_eq!(document.matches("<path").count(), 2);
+    assert!(document.contains("<path d=\"M2722 -1120"));
+}
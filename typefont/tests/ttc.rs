@@ -0,0 +1,96 @@
+use typefont::font::open_type::OpenType;
+use typefont::font::ttc::{Ttc, TtcHeader};
+
+/// 由同一个字体构造包含两个字体的集合，两个表目录指向同一份表数据
+fn build_collection() -> Vec<u8> {
+    let bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    let num_tables = font.table_directory().table_records().len();
+    let directory_len = 12 + 16 * num_tables;
+    let header_len = 12 + 4 * 2;
+    let shift = (header_len + directory_len * 2) as u32;
+
+    let mut out = vec![];
+    out.extend_from_slice(b"ttcf");
+    out.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 2]);
+    out.extend_from_slice(&(header_len as u32).to_be_bytes());
+    out.extend_from_slice(&((header_len + directory_len) as u32).to_be_bytes());
+    for _ in 0..2 {
+        out.extend_from_slice(&bytes[..12]);
+        for record in font.table_directory().table_records() {
+            out.extend_from_slice(&record.tag().to_bytes());
+            out.extend_from_slice(&record.checksum().to_be_bytes());
+            out.extend_from_slice(&(record.offset() - directory_len as u32 + shift).to_be_bytes());
+            out.extend_from_slice(&record.length().to_be_bytes());
+        }
+    }
+    out.extend_from_slice(&bytes[directory_len..]);
+    out
+}
+
+#[test]
+fn read_collection() {
+    let ttc = Ttc::from_bytes(build_collection()).unwrap();
+    assert_eq!(ttc.header().num_fonts(), 2);
+    assert!(ttc.header().as_v1().is_some());
+    assert_eq!(ttc.fonts().len(), 2);
+
+    let first = ttc.font(0).unwrap();
+    let second = ttc.font(1).unwrap();
+    assert!(first.in_collection());
+    assert_eq!(first.maxp().unwrap().unwrap().num_glyphs(), 3377);
+    // 共享的表只读取一次
+    let hmtx = first.hmtx().unwrap().unwrap();
+    assert!(std::ptr::eq(hmtx, second.hmtx().unwrap().unwrap()));
+    assert_eq!(hmtx.advance_width(36), Some(1233));
+    assert!(second.verify_checksums().unwrap().is_ok());
+}
+
+#[test]
+fn reject_single_font() {
+    let bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    assert!(Ttc::from_bytes(bytes).is_err());
+}
+
+#[test]
+fn reject_truncated_header() {
+    // `numFonts` 超出数据长度时不按其分配内存
+    let mut header = b"ttcf".to_vec();
+    header.extend([0, 1, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF]);
+    let err = Ttc::from_bytes(header).err().unwrap();
+    assert!(
+        err.to_string()
+            .contains("numFonts: expected <= 0, found 4294967295")
+    );
+}
+
+#[cfg(feature = "writer")]
+#[test]
+fn write_and_split_collection() {
+    use typefont::font::io::WriteTo;
+    use typefont::font::ttc::write_collection;
+    use typefont::io::vec_writer::VecWriter;
+
+    let bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    let mut writer = VecWriter::new();
+    write_collection(&[&font, &font], 2, &mut writer).unwrap();
+    // 两个字体的表完全相同，只写入一份
+    let directory_len = 12 + 16 * font.table_directory().table_records().len();
+    assert_eq!(writer.data().len(), 24 + 8 + bytes.len() + directory_len);
+
+    let ttc = Ttc::from_bytes(writer.into_inner()).unwrap();
+    assert!(ttc.header().as_v2().is_some());
+    let first = ttc.font(0).unwrap().table_directory().table_records();
+    let second = ttc.font(1).unwrap().table_directory().table_records();
+    assert!(first.iter().zip(second).all(|(a, b)| a.offset() == b.offset()));
+    assert!(ttc.font(1).unwrap().verify_checksums().unwrap().is_ok());
+
+    let files = ttc.split().unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[1], bytes);
+
+    let mut writer = VecWriter::new();
+    ttc.write_to(&mut writer).unwrap();
+    assert_eq!(Ttc::from_bytes(writer.into_inner()).unwrap().fonts().len(), 2);
+}
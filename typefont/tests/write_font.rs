@@ -0,0 +1,71 @@
+#![cfg(feature = "writer")]
+
+use typefont::font::io::WriteTo;
+use typefont::font::open_type::{OpenType, TableDirectory};
+use typefont::font::table_record::TableRecord;
+use typefont::io::vec_writer::VecWriter;
+use typefont::types::Tag;
+
+#[test]
+fn round_trip() {
+    let bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    let mut writer = VecWriter::new();
+    font.write_to(&mut writer).unwrap();
+    // 原字体表记录有序、已对齐且校验和正确，重新写入后应完全一致
+    assert_eq!(writer.data(), bytes.as_slice());
+
+    let font = OpenType::from_bytes(writer.into_inner()).unwrap();
+    assert_eq!(font.maxp().unwrap().unwrap().num_glyphs(), 3377);
+}
+
+#[test]
+fn write_unparsed_table() {
+    let mut bytes = std::fs::read("tests/fonts/DejaVuSansMono.ttf").unwrap();
+    let font = OpenType::from_bytes(bytes.clone()).unwrap();
+    let cmap = Tag::from(*b"cmap");
+    let index = font.table_directory().find(&cmap).unwrap();
+    let offset = font.table_directory().table_records()[index].offset() as usize;
+    // 第一个子表的格式改为不存在的格式 99
+    let subtable = u32::from_be_bytes(bytes[offset + 8..offset + 12].try_into().unwrap());
+    let format = offset + subtable as usize;
+    bytes[format..format + 2].copy_from_slice(&99u16.to_be_bytes());
+
+    let font = OpenType::from_bytes(bytes).unwrap();
+    assert!(font.cmap().is_err());
+    let mut writer = VecWriter::new();
+    font.write_to(&mut writer).unwrap();
+
+    // 无法解析的表按原始数据写入，其余的表不受影响
+    let written = OpenType::from_bytes(writer.into_inner()).unwrap();
+    assert!(written.cmap().is_err());
+    assert_eq!(
+        written.table_bytes(&cmap).unwrap(),
+        font.table_bytes(&cmap).unwrap()
+    );
+    assert_eq!(written.maxp().unwrap().unwrap().num_glyphs(), 3377);
+    assert!(written.verify_checksums().unwrap().is_ok());
+}
+
+#[test]
+fn table_directory_limits() {
+    let records = |n: usize| {
+        (0..n)
+            .map(|i| TableRecord::new(Tag::from((i as u32).to_be_bytes()), 0, 0, 0))
+            .collect()
+    };
+    let directory = TableDirectory::new(0x00010000, records(20)).unwrap();
+    assert_eq!(
+        (
+            directory.search_range(),
+            directory.entry_selector(),
+            directory.range_shift()
+        ),
+        (256, 4, 64)
+    );
+    // 超过 4095 个表时二分查找参数超出 `u16` 的范围
+    let directory = TableDirectory::new(0x00010000, records(5000)).unwrap();
+    assert_eq!(directory.num_tables(), 5000);
+    assert_eq!(directory.search_range(), u16::MAX);
+    assert!(TableDirectory::new(0x00010000, records(65536)).is_err());
+}
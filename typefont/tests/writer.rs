@@ -0,0 +1,36 @@
+#![cfg(feature = "writer")]
+
+use typefont::io::file_writer::FileWriter;
+use typefont::io::vec_writer::VecWriter;
+use typefont::io::writer::{Patch, Write, Writer};
+
+fn write_table(writer: &mut dyn Writer) {
+    writer.write(0x0001u16).unwrap();
+    let length = writer.reserve(4).unwrap();
+    writer.write(b"abc".as_slice()).unwrap();
+    writer.pad_to(4).unwrap();
+    assert_eq!(writer.pad_to(0).unwrap(), 0);
+    let written = writer.written() as u32;
+    writer.patch_at(length, written).unwrap();
+    assert_eq!(writer.position(), 12);
+}
+
+#[test]
+fn back_patch() {
+    let mut writer = VecWriter::new();
+    write_table(&mut writer);
+    assert_eq!(writer.data(), &[0, 1, 0, 0, 0, 12, b'a', b'b', b'c', 0, 0, 0]);
+    assert!(writer.patch(10, &[0; 4]).is_err());
+}
+
+#[test]
+fn write_file() {
+    let path = std::env::temp_dir().join("typefont_write_file.bin");
+    let mut writer = FileWriter::open(&path).unwrap();
+    write_table(&mut writer);
+    writer.flush().unwrap();
+    drop(writer);
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(bytes, [0, 1, 0, 0, 0, 12, b'a', b'b', b'c', 0, 0, 0]);
+}